@@ -0,0 +1,172 @@
+//! Benchmarks of the searches on representative problem instances.
+//!
+//! Run via `cargo bench`; pass a filter such as `cargo bench -- hanoi` to run some of them.
+
+use criterion::{criterion_group, BenchmarkId, Criterion};
+use river_crossing::config::{solve_with_config, SearchConfig};
+use river_crossing::history::History;
+use river_crossing::problems::bridge_and_torch::BridgeAndTorch;
+use river_crossing::problems::hanoi::Hanoi;
+use river_crossing::problems::humans_and_zombies::HumansAndZombies;
+use river_crossing::problems::Problem;
+use river_crossing::search::{Action, State};
+use river_crossing::strategies::{Objective, Strategy};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Solves the problem minimizing the objective, returning the length of the plan.
+fn solve<P, S, A>(problem: &P, objective: Objective) -> usize
+where
+    P: Problem<State = S>,
+    S: State<Action = A> + Clone + Eq + Debug + Send + Sync,
+    A: Action<State = S> + Clone + Debug + Send,
+    S::Hash: Eq + Hash,
+{
+    let config = SearchConfig::new()
+        .objective(objective)
+        .verbose(false)
+        .goal(|s| problem.is_goal(s));
+    let outcome = solve_with_config(problem.initial(), &config, &mut History::new())
+        .expect("configuration is valid");
+    outcome.into_plan().expect("problem is solvable").len()
+}
+
+/// Breadth-first search on growing populations, stressing the visited set.
+fn humans_and_zombies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bfs/humans-and-zombies");
+    for (people, boat) in [(3, 2), (10, 4), (50, 6)] {
+        let problem = HumansAndZombies::new(people, people, boat);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{people}/{people}/{boat}")),
+            &problem,
+            |b, problem| b.iter(|| solve(problem, Objective::Trips)),
+        );
+    }
+    group.finish();
+}
+
+/// Uniform-cost search minimizing the time, stressing the frontier with the many
+/// groups of people that can cross at once.
+fn bridge_and_torch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ucs/bridge-and-torch");
+    let times = [1, 2, 5, 8, 10, 12, 15, 20];
+    for people in [4, 6, 8] {
+        let problem = BridgeAndTorch::new(2, u8::MAX, times[..people].to_vec());
+        group.bench_with_input(
+            BenchmarkId::from_parameter(people),
+            &problem,
+            |b, problem| b.iter(|| solve(problem, Objective::Time)),
+        );
+    }
+    group.finish();
+}
+
+/// Breadth-first search on the Tower of Hanoi, whose state space grows threefold per disk.
+fn hanoi(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bfs/hanoi");
+    for disks in [4, 6, 8] {
+        let problem = Hanoi::new(disks);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(disks),
+            &problem,
+            |b, problem| b.iter(|| solve(problem, Objective::Trips)),
+        );
+    }
+    group.finish();
+}
+
+/// Iterative deepening A* on the Tower of Hanoi, with and without caching the actions of the
+/// states it explores over and over again.
+fn hanoi_ida_star(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ida-star/hanoi");
+    for disks in [3, 4] {
+        let problem = Hanoi::new(disks);
+        for memoize in [false, true] {
+            let name = if memoize { "memoized" } else { "plain" };
+            group.bench_with_input(BenchmarkId::new(name, disks), &problem, |b, problem| {
+                b.iter(|| {
+                    let config = SearchConfig::new()
+                        .strategy(Strategy::IterativeDeepening)
+                        .memoize(memoize)
+                        .verbose(false);
+                    let outcome =
+                        solve_with_config(problem.initial(), &config, &mut History::new())
+                            .expect("configuration is valid");
+                    outcome.into_plan().expect("problem is solvable").len()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Breadth-first search on the compactly encoded Humans and Zombies states, for comparison
+/// with the generic search.
+#[cfg(feature = "compact")]
+fn humans_and_zombies_compact(c: &mut Criterion) {
+    use river_crossing::problems::humans_and_zombies_compact::search_compact;
+
+    let mut group = c.benchmark_group("compact/humans-and-zombies");
+    for (people, boat) in [(3, 2), (10, 4), (50, 6)] {
+        let initial = HumansAndZombies::new(people, people, boat).initial();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{people}/{people}/{boat}")),
+            &initial,
+            |b, initial| b.iter(|| search_compact(initial).expect("problem is solvable").len()),
+        );
+    }
+    group.finish();
+}
+
+/// Breadth-first search on a large Humans and Zombies instance, expanding every level serially
+/// and in parallel.
+#[cfg(feature = "rayon")]
+fn humans_and_zombies_parallel(c: &mut Criterion) {
+    use river_crossing::parallel::parallel_search;
+
+    let mut group = c.benchmark_group("parallel/humans-and-zombies");
+    for (people, boat) in [(20, 4), (50, 6)] {
+        let problem = HumansAndZombies::new(people, people, boat);
+        let instance = format!("{people}/{people}/{boat}");
+        group.bench_with_input(
+            BenchmarkId::new("serial", &instance),
+            &problem,
+            |b, problem| b.iter(|| solve(problem, Objective::Trips)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parallel", &instance),
+            &problem,
+            |b, problem| {
+                b.iter(|| {
+                    parallel_search(problem.initial(), &mut History::new())
+                        .into_plan()
+                        .expect("problem is solvable")
+                        .len()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    humans_and_zombies,
+    bridge_and_torch,
+    hanoi,
+    hanoi_ida_star
+);
+#[cfg(feature = "compact")]
+criterion_group!(compact, humans_and_zombies_compact);
+#[cfg(feature = "rayon")]
+criterion_group!(parallel, humans_and_zombies_parallel);
+
+// Like `criterion_main!`, but running the groups of the enabled features only.
+fn main() {
+    benches();
+    #[cfg(feature = "compact")]
+    compact();
+    #[cfg(feature = "rayon")]
+    parallel();
+    Criterion::default().configure_from_args().final_summary();
+}
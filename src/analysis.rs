@@ -0,0 +1,216 @@
+//! Analytics of the shape of an explored search tree, such as its effective branching factor.
+
+use crate::history::History;
+use crate::stats::thousands;
+use serde::Serialize;
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+
+thread_local! {
+    /// The counters of the searches running on the current thread, if they are being counted.
+    static COUNTERS: Cell<Option<SearchCounters>> = const { Cell::new(None) };
+}
+
+/// Counts of the successor states generated by a search.
+///
+/// Only the searches expanding states one by one while discarding those observed before, i.e.
+/// the breadth-first, greedy and traced searches, count them; the others leave them at zero.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SearchCounters {
+    /// The number of successor states generated, including duplicates.
+    pub generated: usize,
+    /// The number of successor states discarded because they were observed before.
+    pub duplicates: usize,
+}
+
+/// Runs the specified function, counting the successor states generated by the searches it
+/// runs on the current thread.
+pub fn counting<T>(f: impl FnOnce() -> T) -> (T, SearchCounters) {
+    let outer = COUNTERS.with(|c| c.replace(Some(SearchCounters::default())));
+    let result = f();
+    let counters = COUNTERS
+        .with(|c| c.replace(outer))
+        .expect("counters are in place");
+    (result, counters)
+}
+
+/// Gets the counters of the searches run on the current thread so far, if they are being
+/// [counted](counting).
+pub(crate) fn current_counters() -> Option<SearchCounters> {
+    COUNTERS.with(Cell::get)
+}
+
+/// Counts a generated successor state, and whether it was a duplicate, if searches on the
+/// current thread are being [counted](counting).
+pub(crate) fn count_generated(duplicate: bool) {
+    COUNTERS.with(|c| {
+        if let Some(mut counters) = c.get() {
+            counters.generated += 1;
+            counters.duplicates += usize::from(duplicate);
+            c.set(Some(counters));
+        }
+    });
+}
+
+/// The shape of an explored search tree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchAnalysis {
+    /// The branching factor a uniform tree as deep as the goal, or the deepest state if there is
+    /// no goal, would need to hold as many states; [`None`] if the tree has a single level.
+    pub branching_factor: Option<f64>,
+    /// The number of states recorded at every depth, starting with the initial state.
+    pub states_per_depth: Vec<usize>,
+    /// The number of successor states generated, if the search counted them.
+    pub generated: Option<usize>,
+    /// The share of the generated successor states discarded as duplicates, if the search
+    /// counted them.
+    pub duplicate_ratio: Option<f64>,
+}
+
+/// Analyzes the search tree recorded in the history, along with the counters of the search.
+pub fn analyze<S, A>(history: &History<S, A>, counters: &SearchCounters) -> SearchAnalysis {
+    let mut states_per_depth = Vec::new();
+    for lineage in history.iter() {
+        if states_per_depth.len() <= lineage.depth {
+            states_per_depth.resize(lineage.depth + 1, 0);
+        }
+        states_per_depth[lineage.depth] += 1;
+    }
+
+    let depth = match history.goal() {
+        Some(goal) => goal.depth,
+        None => states_per_depth.len().saturating_sub(1),
+    };
+    let counted = counters.generated > 0;
+
+    SearchAnalysis {
+        branching_factor: branching_factor(history.len(), depth),
+        states_per_depth,
+        generated: counted.then_some(counters.generated),
+        duplicate_ratio: counted.then(|| counters.duplicates as f64 / counters.generated as f64),
+    }
+}
+
+/// Finds the branching factor `b` of a uniform tree of the specified depth holding the
+/// specified number of states, i.e. `1 + b + b² + … + b^depth = states`, by bisection.
+fn branching_factor(states: usize, depth: usize) -> Option<f64> {
+    if depth == 0 || states <= depth {
+        return None;
+    }
+
+    let states = states as f64;
+    let tree = |b: f64| {
+        let (mut sum, mut level) = (0.0, 1.0);
+        for _ in 0..=depth {
+            sum += level;
+            level *= b;
+            if sum > states {
+                break;
+            }
+        }
+        sum
+    };
+
+    // A chain of `depth + 1` states has a branching factor of one, and no tree holding more
+    // states than that has a branching factor above their number.
+    let (mut low, mut high) = (1.0, states);
+    for _ in 0..64 {
+        let middle = (low + high) / 2.0;
+        if tree(middle) < states {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+impl SearchAnalysis {
+    /// Gets the lines of the analysis, each as a name and the value following it.
+    pub(crate) fn lines(&self) -> Vec<(String, String)> {
+        let mut lines = Vec::new();
+        if let Some(branching_factor) = self.branching_factor {
+            lines.push((
+                String::from("branching factor"),
+                format!("{branching_factor:.2}"),
+            ));
+        }
+
+        let widest = self
+            .states_per_depth
+            .iter()
+            .enumerate()
+            .max_by_key(|&(depth, states)| (states, std::cmp::Reverse(depth)));
+        if let Some((depth, states)) = widest {
+            lines.push((
+                String::from("depths"),
+                format!(
+                    "{} (widest: {} states at depth {})",
+                    thousands(self.states_per_depth.len() as u64),
+                    thousands(*states as u64),
+                    thousands(depth as u64)
+                ),
+            ));
+        }
+
+        if let (Some(generated), Some(ratio)) = (self.generated, self.duplicate_ratio) {
+            lines.push((
+                String::from("duplicates"),
+                format!(
+                    "{:.1}% of {} generated states",
+                    ratio * 100.0,
+                    thousands(generated as u64)
+                ),
+            ));
+        }
+        lines
+    }
+}
+
+impl Display for SearchAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in self.lines() {
+            writeln!(f, "  {name}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::solve_with_history;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn search_tree_is_analyzed() {
+        let mut history = History::new();
+        let (outcome, counters) =
+            counting(|| quietly(|| solve_with_history(WorldState::default(), &mut history)));
+        assert!(outcome.into_plan().is_some());
+
+        let analysis = analyze(&history, &counters);
+        assert_eq!(analysis.states_per_depth[0], 1);
+        assert_eq!(analysis.states_per_depth.len(), 12);
+        assert_eq!(
+            analysis.states_per_depth.iter().sum::<usize>(),
+            history.len()
+        );
+
+        // Every generated state is either recorded or a duplicate.
+        assert_eq!(counters.generated - counters.duplicates, history.len() - 1);
+        let ratio = analysis.duplicate_ratio.expect("search was counted");
+        assert!(ratio > 0.0 && ratio < 1.0);
+
+        let branching_factor = analysis.branching_factor.expect("tree has several levels");
+        assert!(branching_factor > 1.0 && branching_factor < 2.0);
+    }
+
+    #[test]
+    fn branching_factor_of_a_binary_tree_is_two() {
+        let b = branching_factor(1 + 2 + 4 + 8, 3).expect("tree has several levels");
+        assert!((b - 2.0).abs() < 1e-9);
+        assert_eq!(branching_factor(1, 0), None);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::history::{History, Lineage};
+use crate::plan::Plan;
+use crate::search::{Action, SearchOutcome, State};
+use crate::strategies::Lifo;
+use crate::visited::StateMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Searches the state space for ever cheaper plans reaching a state accepted by the `goal`
+/// predicate, recording every explored state in the specified [`History`].
+///
+/// This is a depth-first branch and bound: the search dives towards the goal, following the
+/// actions leading to the states with the lowest [`State::heuristic`] first, to find a first
+/// plan quickly. Every cheaper plan found afterwards is reported to `on_improvement` together
+/// with its summed `cost`, and from then on, only paths cheaper than it are followed.
+///
+/// Once the state space is exhausted, the last reported plan is the cheapest one. If the search
+/// gives up after exploring `max_nodes` states, the best plan found so far is returned instead;
+/// without any plan, the search is [exhausted](SearchOutcome::Exhausted).
+pub fn anytime_search<S, A, G, C, F>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    max_nodes: usize,
+    history: &mut History<S, A>,
+    on_improvement: F,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    F: FnMut(&Plan<S, A>, u32),
+{
+    branch_and_bound(
+        initial_state,
+        goal,
+        cost,
+        |_| 0,
+        max_nodes,
+        history,
+        on_improvement,
+    )
+}
+
+/// Searches the state space for the cheapest plan like [`anytime_search`], additionally
+/// discarding every path whose cost plus the `lower_bound` of its last state is not cheaper
+/// than the best plan found so far.
+///
+/// The lower bound must be admissible, i.e. never exceed the actual cost of reaching a goal
+/// state from the state; then, once the state space is exhausted, the returned plan is the
+/// cheapest one. The tighter the bound, the fewer states are explored.
+pub fn branch_and_bound<S, A, G, C, L, F>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    lower_bound: L,
+    max_nodes: usize,
+    history: &mut History<S, A>,
+    mut on_improvement: F,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    L: Fn(&S) -> u32,
+    F: FnMut(&Plan<S, A>, u32),
+{
+    let mut cheapest = CheapestPaths::new();
+    cheapest.improve(&initial_state, 0);
+
+    let mut fringe = Lifo::new();
+    fringe.push((0, history.create_root(initial_state)));
+
+    let mut best: Option<(u32, Plan<S, A>)> = None;
+    let mut states_explored = 0;
+    while let Some((path_cost, lineage)) = fringe.pop() {
+        let state = &lineage.state;
+
+        // The state may have been reached more cheaply, or a cheaper plan found, since it was pushed.
+        if cheapest.get(state) < Some(path_cost)
+            || best
+                .as_ref()
+                .is_some_and(|(c, _)| path_cost + lower_bound(state) >= *c)
+        {
+            continue;
+        }
+
+        if states_explored >= max_nodes {
+            trace!("Giving up after exploring {states_explored} states.");
+            return match best {
+                Some((_, plan)) => SearchOutcome::Solved(plan),
+                None => SearchOutcome::Exhausted {
+                    reason: String::from("node budget exhausted"),
+                    states_explored,
+                },
+            };
+        }
+
+        trace!(
+            "Exploring state {} at cost {}: {:?}",
+            lineage.id,
+            path_cost,
+            state
+        );
+        states_explored += 1;
+
+        if goal(state) {
+            trace!("  Goal reached at cost {path_cost}.");
+            history.set_goal(&lineage);
+            let plan: Plan<S, A> = history.backtrack(&lineage).collect();
+            on_improvement(&plan, path_cost);
+            best = Some((path_cost, plan));
+            continue;
+        }
+
+        let mut children = Vec::new();
+        for action in state.get_actions() {
+            let new_state = action.apply(state);
+            let new_cost = path_cost + cost(&action, state);
+            let optimistic_cost = new_cost + lower_bound(&new_state);
+            if best.as_ref().is_some_and(|(c, _)| optimistic_cost >= *c) {
+                trace!("  Ignored:    {:?} (too expensive)", action);
+                continue;
+            }
+
+            if !cheapest.improve(&new_state, new_cost) {
+                trace!("  Ignored:    {:?} (recursion)", action);
+                continue;
+            }
+
+            children.push((new_cost, history.create_entry(action, new_state, &lineage)));
+        }
+
+        // The most promising child is pushed last, so it is explored first.
+        children.sort_by_key(|(_, child): &(u32, Rc<Lineage<S, A>>)| {
+            std::cmp::Reverse(child.state.heuristic())
+        });
+        for child in children {
+            fringe.push(child);
+        }
+    }
+
+    match best {
+        Some((_, plan)) => SearchOutcome::Solved(plan),
+        None => SearchOutcome::Unsolvable { states_explored },
+    }
+}
+
+/// The cheapest known cost of reaching each state, bucketed by the states' hashes.
+struct CheapestPaths<S: State> {
+    buckets: StateMap<S::Hash, Vec<(S, u32)>>,
+}
+
+impl<S> CheapestPaths<S>
+where
+    S: State + Clone + Eq,
+    S::Hash: Eq + Hash,
+{
+    /// Creates a new, empty map.
+    fn new() -> Self {
+        Self {
+            buckets: StateMap::default(),
+        }
+    }
+
+    /// Gets the cheapest known cost of reaching the state.
+    fn get(&self, state: &S) -> Option<u32> {
+        self.buckets
+            .get(&state.unique_hash())?
+            .iter()
+            .find(|(s, _)| s == state)
+            .map(|(_, cost)| *cost)
+    }
+
+    /// Records the cost of reaching the state.
+    /// Returns `true` if the state was not reached at this cost or cheaper before.
+    fn improve(&mut self, state: &S, cost: u32) -> bool {
+        let bucket = self.buckets.entry(state.unique_hash()).or_default();
+        match bucket.iter_mut().find(|(s, _)| s == state) {
+            Some((_, known)) if *known <= cost => false,
+            Some((_, known)) => {
+                *known = cost;
+                true
+            }
+            None => {
+                bucket.push((state.clone(), cost));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::bridge_and_torch::WorldState;
+    use crate::search::uniform_cost_search;
+    use crate::strategies::Objective;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn improvements_are_valid_and_end_in_optimum() {
+        let cost = |a: &_, _: &_| Action::cost(a, Objective::Time);
+        let mut improvements = Vec::new();
+        let outcome = quietly(|| {
+            anytime_search(
+                WorldState::default(),
+                WorldState::is_goal,
+                cost,
+                usize::MAX,
+                &mut History::new(),
+                |plan: &Plan<WorldState, _>, cost| improvements.push((plan.clone(), cost)),
+            )
+        });
+
+        assert!(!improvements.is_empty());
+        assert!(improvements.windows(2).all(|w| w[1].1 < w[0].1));
+        for (plan, plan_cost) in &improvements {
+            assert!(plan.goal().is_goal());
+            let mut state = plan.initial().clone();
+            let mut total = 0;
+            for (action, next) in plan.steps() {
+                assert!(action.is_applicable(&state));
+                total += cost(action, &state);
+                state = action.apply(&state);
+                assert_eq!(&state, next);
+            }
+            assert_eq!(total, *plan_cost);
+        }
+
+        let optimal = quietly(|| {
+            uniform_cost_search(
+                WorldState::default(),
+                WorldState::is_goal,
+                cost,
+                &mut History::new(),
+            )
+        })
+        .into_plan()
+        .expect("problem is solvable");
+        let optimal_cost = optimal.goal().time as u32;
+        assert_eq!(improvements.last().map(|(_, c)| *c), Some(optimal_cost));
+        assert_eq!(
+            outcome.into_plan().map(|p| p.goal().time as u32),
+            Some(optimal_cost)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::history::{History, Lineage};
+use crate::plan::Plan;
+use crate::search::{Action, SearchOutcome, State};
+use crate::visited::StateMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Searches the state space for a plan leading from the initial state to the specified
+/// goal state, using breadth-first search from both ends and meeting in the middle.
+/// Every explored state of either search is recorded in the specified [`History`],
+/// the backward search forming a second tree rooted at the goal state.
+///
+/// The predecessors of a state are obtained by applying the [`Action::inverse`] of its
+/// actions and keeping only those resulting states from which the original action leads
+/// back to the state. This is exact for problems in which every crossing can be undone by
+/// the opposite crossing; for other problems, the search may miss plans.
+///
+/// Since whole levels are expanded at once, always starting with the smaller one, the
+/// returned plan is as short as the one found by the plain breadth-first search.
+pub fn bidirectional_search<S, A>(
+    initial_state: S,
+    goal_state: S,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq,
+    A: Action<State = S> + Clone,
+    S::Hash: Eq + Hash,
+{
+    let mut forward = Side::new(history.create_root(initial_state));
+    let mut backward = Side::new(history.create_root(goal_state));
+
+    if let Some(meeting) = backward.find(&forward.level[0].state) {
+        let meeting = (Rc::clone(&forward.level[0]), meeting);
+        return SearchOutcome::Solved(stitch(history, meeting));
+    }
+
+    let mut states_explored = 0;
+    while !forward.level.is_empty() && !backward.level.is_empty() {
+        let is_forward = forward.level.len() <= backward.level.len();
+        let (expanding, other) = if is_forward {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+
+        states_explored += expanding.level.len();
+        let meetings = expanding.expand(history, other, !is_forward);
+
+        // Several meetings may occur within the same level; keep the shortest path.
+        let shortest = meetings
+            .into_iter()
+            .map(|(ours, theirs)| {
+                if is_forward {
+                    (ours, theirs)
+                } else {
+                    (theirs, ours)
+                }
+            })
+            .min_by_key(|(forward, backward)| forward.depth + backward.depth);
+
+        if let Some(meeting) = shortest {
+            return SearchOutcome::Solved(stitch(history, meeting));
+        }
+    }
+
+    SearchOutcome::Unsolvable { states_explored }
+}
+
+/// A forward and a backward lineage representing the same state.
+type Meeting<S, A> = (Rc<Lineage<S, A>>, Rc<Lineage<S, A>>);
+
+/// One direction of a bidirectional search.
+struct Side<S: State, A> {
+    /// The lineages observed so far, bucketed by their state's hash.
+    observed: StateMap<S::Hash, Vec<Rc<Lineage<S, A>>>>,
+    /// The lineages of the level to expand next.
+    level: Vec<Rc<Lineage<S, A>>>,
+}
+
+impl<S, A> Side<S, A>
+where
+    S: State<Action = A> + Clone + Eq,
+    A: Action<State = S> + Clone,
+    S::Hash: Eq + Hash,
+{
+    /// Creates a new side starting at the specified root.
+    fn new(root: Rc<Lineage<S, A>>) -> Self {
+        let mut side = Self {
+            observed: StateMap::default(),
+            level: Vec::new(),
+        };
+        side.insert(Rc::clone(&root));
+        side.level.push(root);
+        side
+    }
+
+    /// Finds the lineage of the specified state, if it was observed.
+    fn find(&self, state: &S) -> Option<Rc<Lineage<S, A>>> {
+        self.observed
+            .get(&state.unique_hash())?
+            .iter()
+            .find(|lineage| lineage.state == *state)
+            .cloned()
+    }
+
+    /// Records a lineage as observed.
+    fn insert(&mut self, lineage: Rc<Lineage<S, A>>) {
+        self.observed
+            .entry(lineage.state.unique_hash())
+            .or_default()
+            .push(lineage);
+    }
+
+    /// Expands the current level, returning all pairs of a newly observed lineage
+    /// and the lineage of the same state on the `other` side.
+    ///
+    /// When searching `backwards`, the predecessors of the expanded states are generated
+    /// instead, each recorded with the action leading from it to the expanded state.
+    fn expand(
+        &mut self,
+        history: &mut History<S, A>,
+        other: &Self,
+        backwards: bool,
+    ) -> Vec<Meeting<S, A>> {
+        let mut meetings = Vec::new();
+        let mut next_level = Vec::new();
+
+        for parent in std::mem::take(&mut self.level) {
+            for action in parent.state.get_actions() {
+                let state = if backwards {
+                    match predecessor(&action, &parent.state) {
+                        Some(state) => state,
+                        None => continue,
+                    }
+                } else {
+                    action.apply(&parent.state)
+                };
+
+                if self.find(&state).is_some() {
+                    continue;
+                }
+
+                let lineage = history.create_entry(action, state, &parent);
+                self.insert(Rc::clone(&lineage));
+                if let Some(theirs) = other.find(&lineage.state) {
+                    meetings.push((Rc::clone(&lineage), theirs));
+                }
+
+                next_level.push(lineage);
+            }
+        }
+
+        self.level = next_level;
+        meetings
+    }
+}
+
+/// Determines the state from which the specified action leads to the specified state, if any.
+fn predecessor<S, A>(action: &A, state: &S) -> Option<S>
+where
+    S: State<Action = A> + Eq,
+    A: Action<State = S>,
+{
+    let inverse = action.inverse();
+    if !inverse.is_applicable(state) {
+        return None;
+    }
+
+    let predecessor = inverse.apply(state);
+    (action.is_applicable(&predecessor) && action.apply(&predecessor) == *state)
+        .then_some(predecessor)
+}
+
+/// Joins the path to the forward lineage with the reversed path to the backward lineage,
+/// both of which represent the same state.
+fn stitch<S, A>(history: &History<S, A>, (forward, backward): Meeting<S, A>) -> Plan<S, A>
+where
+    S: State<Action = A> + Clone + Eq,
+    A: Action<State = S> + Clone,
+{
+    let mut path = history.backtrack(&forward).collect::<Vec<_>>();
+
+    let mut current = backward;
+    while let Some(parent) = current.parent_id().and_then(|id| history.get(id)) {
+        let action = current.action.clone().expect("only roots have no action");
+        path.push((Some(action), parent.state.clone()));
+        current = Rc::clone(parent);
+    }
+
+    path.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::{
+        Boat, HumansAndZombies, RiverBank, RiverBankState, WorldState,
+    };
+    use crate::problems::Problem;
+    use crate::search::solve_with_history;
+
+    /// Counts the states that were expanded into at least one new state.
+    fn expanded<S, A>(history: &History<S, A>) -> usize {
+        (0..history.len())
+            .filter_map(|id| history.get(id)?.parent_id())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    /// Builds the goal state of the Humans and Zombies problem.
+    fn everyone_right(humans: u8, zombies: u8, boat: u8) -> WorldState {
+        WorldState::new(
+            RiverBankState::new(0, 0),
+            RiverBankState::new(humans, zombies),
+            Boat::new(boat, RiverBank::Right),
+        )
+    }
+
+    #[test]
+    fn bidirectional_search_finds_shortest_plan() {
+        let initial = WorldState::default();
+        let goal = everyone_right(3, 3, 2);
+
+        let expected = solve_with_history(initial.clone(), &mut History::new())
+            .into_plan()
+            .expect("problem is solvable");
+        let plan = bidirectional_search(initial, goal.clone(), &mut History::new())
+            .into_plan()
+            .expect("problem is solvable");
+
+        assert_eq!(plan.len(), 11);
+        assert_eq!(plan.len(), expected.len());
+        assert_eq!(*plan.goal(), goal);
+    }
+
+    #[test]
+    fn bidirectional_search_expands_fewer_states() {
+        // The state space of the default problem is nearly a single path,
+        // so a larger instance is needed for meeting in the middle to pay off.
+        let initial = HumansAndZombies::new(10, 10, 4).initial();
+        let goal = everyone_right(10, 10, 4);
+
+        let mut bfs = History::new();
+        let expected = solve_with_history(initial.clone(), &mut bfs)
+            .into_plan()
+            .expect("problem is solvable");
+
+        let mut bidirectional = History::new();
+        let plan = bidirectional_search(initial, goal, &mut bidirectional)
+            .into_plan()
+            .expect("problem is solvable");
+
+        assert_eq!(plan.len(), expected.len());
+        assert!(expanded(&bidirectional) < expanded(&bfs));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::history::{History, Lineage};
+use crate::markdown::write_row;
+use crate::plan::Plan;
+use crate::search::{
+    a_star_with_fringe, solve_with_fringe, uniform_cost_search, Action, Estimated, SearchOutcome,
+    State,
+};
+use crate::strategies::{Fifo, Lifo, Measured, Objective, PriorityFringe};
+use crate::verbosity::quietly;
+use serde::Serialize;
+use std::fmt::{Debug, Write};
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// How a single search strategy fared on a problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// The name of the strategy.
+    pub strategy: &'static str,
+    /// The number of actions in the plan found, if any.
+    pub length: Option<usize>,
+    /// The cost of the plan found by the compared objective, if any.
+    pub cost: Option<u32>,
+    /// The number of states explored, i.e. taken from the frontier and expanded.
+    pub expanded: usize,
+    /// The number of states the frontier held at once at most.
+    pub peak_frontier: usize,
+}
+
+/// Runs breadth-first, depth-first, greedy best-first and A* search on the same problem,
+/// measuring the plan found by each along with the effort it took.
+///
+/// A* minimizes the specified objective, guided by [`State::heuristic`]; the plans found by
+/// the other strategies are merely measured by it. Since the heuristic may overestimate the
+/// remaining cost, A* is not guaranteed to find the cheapest plan.
+pub fn compare_strategies<S, A, G>(
+    initial_state: S,
+    goal: G,
+    objective: Objective,
+) -> Vec<Comparison>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + Hash,
+    G: Fn(&S) -> bool,
+{
+    let measure = |strategy, outcome: SearchOutcome<S, A>, expanded, peak_frontier| {
+        let plan = outcome.into_plan();
+        Comparison {
+            strategy,
+            length: plan.as_ref().map(|plan| plan.len()),
+            cost: plan.as_ref().map(|plan| {
+                plan.steps()
+                    .iter()
+                    .map(|(action, _)| action.cost(objective))
+                    .sum()
+            }),
+            expanded,
+            peak_frontier,
+        }
+    };
+
+    let mut comparisons = Vec::new();
+
+    let mut fringe = Measured::new(Fifo::new());
+    let outcome = solve_with_fringe(
+        initial_state.clone(),
+        &goal,
+        &mut fringe,
+        &mut History::new(),
+    );
+    comparisons.push(measure("BFS", outcome, fringe.popped(), fringe.peak()));
+
+    let mut fringe = Measured::new(Lifo::new());
+    let outcome = solve_with_fringe(
+        initial_state.clone(),
+        &goal,
+        &mut fringe,
+        &mut History::new(),
+    );
+    comparisons.push(measure("DFS", outcome, fringe.popped(), fringe.peak()));
+
+    let mut fringe = Measured::new(PriorityFringe::new(|lineage: &Rc<Lineage<S, A>>| {
+        lineage.state.heuristic()
+    }));
+    let outcome = solve_with_fringe(
+        initial_state.clone(),
+        &goal,
+        &mut fringe,
+        &mut History::new(),
+    );
+    comparisons.push(measure("greedy", outcome, fringe.popped(), fringe.peak()));
+
+    let mut fringe = Measured::new(PriorityFringe::new(
+        |(cost, estimate, _): &Estimated<S, A>| cost + estimate,
+    ));
+    let (outcome, expanded) = a_star_with_fringe(
+        initial_state,
+        &goal,
+        |action: &A, _: &S| action.cost(objective),
+        S::heuristic,
+        &mut fringe,
+        &mut History::new(),
+    );
+    comparisons.push(measure("A*", outcome, expanded, fringe.peak()));
+
+    comparisons
+}
+
+/// How much more a plan costs than the cheapest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OptimalityGap {
+    /// The objective the costs are measured by.
+    pub objective: Objective,
+    /// The cost of the plan.
+    pub cost: u32,
+    /// The cost of the cheapest plan.
+    pub optimal: u32,
+}
+
+impl OptimalityGap {
+    /// Gets the difference between the cost of the plan and the cheapest one.
+    pub fn absolute(&self) -> u32 {
+        self.cost - self.optimal
+    }
+
+    /// Gets the difference in percent of the cost of the cheapest plan.
+    pub fn percentage(&self) -> f64 {
+        if self.optimal == 0 {
+            return 0.0;
+        }
+        100.0 * self.absolute() as f64 / self.optimal as f64
+    }
+}
+
+/// Determines how far a plan, e.g. one found by greedy best-first search, is from the cheapest
+/// plan by the specified objective, which is found using uniform-cost search from the same
+/// initial state to a state accepted by the `goal` predicate.
+///
+/// Nothing is printed while searching.
+pub fn optimality_gap<S, A, G>(plan: &Plan<S, A>, goal: G, objective: Objective) -> OptimalityGap
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + Hash,
+    G: Fn(&S) -> bool,
+{
+    let cost = |plan: &Plan<S, A>| {
+        plan.steps()
+            .iter()
+            .map(|(action, _)| action.cost(objective))
+            .sum()
+    };
+
+    let outcome = quietly(|| {
+        uniform_cost_search(
+            plan.initial().clone(),
+            goal,
+            |action: &A, _: &S| action.cost(objective),
+            &mut History::new(),
+        )
+    });
+    let optimal = outcome
+        .into_plan()
+        .expect("the goal is reachable, as the plan reaches it");
+
+    OptimalityGap {
+        objective,
+        cost: cost(plan),
+        optimal: cost(&optimal),
+    }
+}
+
+/// Formats the comparisons as a table with aligned columns.
+pub fn format_comparison(comparisons: &[Comparison], objective: Objective) -> String {
+    let rows = rows(comparisons, objective);
+    let widths = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut buffer = String::new();
+    for row in rows {
+        let mut line = String::new();
+        for (column, (cell, width)) in row.iter().zip(&widths).enumerate() {
+            match column {
+                0 => write!(line, "{cell:<width$}"),
+                _ => write!(line, "  {cell:>width$}"),
+            }
+            .expect("writing to a string cannot fail");
+        }
+        buffer.push_str(line.trim_end());
+        buffer.push('\n');
+    }
+    buffer
+}
+
+/// Formats the comparisons as a GitHub-flavored Markdown table.
+pub fn format_comparison_markdown(comparisons: &[Comparison], objective: Objective) -> String {
+    let rows = rows(comparisons, objective);
+
+    let mut buffer = String::new();
+    write_row(&mut buffer, &rows[0]);
+    write_row(&mut buffer, &vec![String::from("---"); rows[0].len()]);
+    for row in &rows[1..] {
+        write_row(&mut buffer, row);
+    }
+    buffer
+}
+
+/// Gets the cells of the comparison table, starting with the headings.
+fn rows(comparisons: &[Comparison], objective: Objective) -> Vec<Vec<String>> {
+    let missing = || String::from("—");
+    let headings = vec![
+        String::from("Strategy"),
+        String::from("Length"),
+        format!("Cost ({objective})"),
+        String::from("Expanded"),
+        String::from("Peak frontier"),
+    ];
+
+    let rows = comparisons.iter().map(|comparison| {
+        vec![
+            comparison.strategy.to_string(),
+            comparison
+                .length
+                .map_or_else(missing, |length| length.to_string()),
+            comparison
+                .cost
+                .map_or_else(missing, |cost| cost.to_string()),
+            comparison.expanded.to_string(),
+            comparison.peak_frontier.to_string(),
+        ]
+    });
+    std::iter::once(headings).chain(rows).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+
+    #[test]
+    fn strategies_are_compared_side_by_side() {
+        let comparisons = quietly(|| {
+            compare_strategies(WorldState::default(), WorldState::is_goal, Objective::Trips)
+        });
+
+        let strategies = comparisons.iter().map(|c| c.strategy).collect::<Vec<_>>();
+        assert_eq!(strategies, ["BFS", "DFS", "greedy", "A*"]);
+        assert_eq!(comparisons[0].length, Some(11));
+        assert!(comparisons.iter().all(|c| c.length >= Some(11)));
+        assert!(comparisons.iter().all(|c| c.peak_frontier > 0));
+
+        let table = format_comparison(&comparisons, Objective::Trips);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("Strategy  Length  Cost (trips)  Expanded  Peak frontier"));
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()));
+    }
+
+    #[test]
+    fn random_walks_are_measured_against_the_shortest_plan() {
+        use crate::problems::humans_and_zombies::HumansAndZombies;
+        use crate::problems::Problem;
+        use crate::search::random_search;
+
+        let initial = HumansAndZombies::new(10, 8, 4).initial();
+        let plan = quietly(|| random_search(initial, 5, 100_000, &mut History::new()))
+            .into_plan()
+            .expect("problem is solvable");
+
+        let gap = optimality_gap(&plan, WorldState::is_goal, Objective::Trips);
+        assert_eq!(gap.optimal, 11);
+        assert_eq!(gap.cost as usize, plan.len());
+        assert!(gap.absolute() > 0);
+        assert_eq!(gap.percentage(), 100.0 * gap.absolute() as f64 / 11.0);
+    }
+}
@@ -0,0 +1,752 @@
+use crate::analysis::{current_counters, SearchCounters};
+use crate::history::{History, Lineage};
+use crate::memo::ActionCache;
+#[cfg(feature = "rayon")]
+use crate::parallel::parallel_search;
+use crate::search::{
+    ida_star, ida_star_memoized, random_search, solve_until, uniform_cost_search, Action,
+    SearchOutcome, State, TIMEOUT_CHECK_INTERVAL,
+};
+use crate::stats::thousands;
+use crate::strategies::{Fifo, Objective, PriorityFringe, Reported, Strategy};
+use crate::symmetry::solve_with_symmetry;
+use crate::verbosity::quietly;
+use crate::visited::Visited;
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The maximum number of steps taken by the random walk before giving up,
+/// unless a node budget is configured.
+pub const RANDOM_WALK_MAX_STEPS: usize = 100_000;
+
+/// A predicate on world states.
+type Predicate<'a, S> = Box<dyn Fn(&S) -> bool + 'a>;
+
+/// Receives the number of states explored so far and the number of states in the fringe.
+type Reporter<'a> = Box<dyn Fn(usize, usize) + 'a>;
+
+/// The configuration of a search, built up from its defaults.
+///
+/// The default configuration runs a plain breadth-first search for a state
+/// satisfying [`State::is_goal`], printing its progress:
+///
+/// ```
+/// use river_crossing::config::{solve_with_config, SearchConfig};
+/// use river_crossing::history::History;
+/// use river_crossing::problems::humans_and_zombies::WorldState;
+///
+/// let config = SearchConfig::new().max_depth(11).verbose(false);
+/// let outcome = solve_with_config(WorldState::default(), &config, &mut History::new());
+/// assert_eq!(outcome.unwrap().into_plan().unwrap().len(), 11);
+/// ```
+pub struct SearchConfig<'a, S> {
+    strategy: Strategy,
+    objective: Objective,
+    seed: u64,
+    max_depth: Option<usize>,
+    max_crossings: Option<usize>,
+    max_nodes: Option<usize>,
+    timeout: Option<Duration>,
+    cancel: Option<Arc<AtomicBool>>,
+    verbose: bool,
+    symmetry: bool,
+    memoize: bool,
+    visited_cap: Option<usize>,
+    heartbeat: Option<usize>,
+    goal: Option<Predicate<'a, S>>,
+    prune: Option<Predicate<'a, S>>,
+    progress: Option<Reporter<'a>>,
+}
+
+impl<'a, S> SearchConfig<'a, S> {
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self {
+            strategy: Strategy::default(),
+            objective: Objective::default(),
+            seed: 0,
+            max_depth: None,
+            max_crossings: None,
+            max_nodes: None,
+            timeout: None,
+            cancel: None,
+            verbose: true,
+            symmetry: false,
+            memoize: false,
+            visited_cap: None,
+            heartbeat: None,
+            goal: None,
+            prune: None,
+            progress: None,
+        }
+    }
+
+    /// Sets the search strategy.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the objective to minimize; anything but [`Objective::Trips`]
+    /// uses uniform-cost search.
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Sets the seed for randomized search strategies.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Limits the number of actions in a plan.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Searches for the plan with the fewest crossings, i.e. trips of the boat, using at most
+    /// `max_crossings` of them; if there is no such plan, the problem is reported as
+    /// [unsolvable](SearchOutcome::Unsolvable) rather than the search as exhausted.
+    ///
+    /// This requires the breadth-first search for the fewest [trips](Objective::Trips), where
+    /// every action is a single crossing.
+    pub fn max_crossings(mut self, max_crossings: usize) -> Self {
+        self.max_crossings = Some(max_crossings);
+        self
+    }
+
+    /// Limits the number of states explored, or the number of steps of a random walk.
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limits the time spent searching.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stops the search once the flag is set.
+    pub fn cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Sets whether the search progress is printed.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Sets whether states are collapsed with their [mirror image](State::mirror)
+    /// during breadth-first search; see [`solve_with_symmetry`].
+    pub fn symmetry(mut self, symmetry: bool) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Sets whether the actions of every state explored are cached rather than expanded anew
+    /// whenever the state is explored again; see [`ActionCache`].
+    ///
+    /// This trades memory for speed in the iterative deepening search, which explores the same
+    /// states in every iteration; the other searches explore every state once and ignore it.
+    pub fn memoize(mut self, memoize: bool) -> Self {
+        self.memoize = memoize;
+        self
+    }
+
+    /// Keeps at most `cap` observed states, evicting the least recently observed one to make
+    /// room for another; see [`Visited::bounded`].
+    ///
+    /// This bounds the memory spent on recognizing states explored before, at the expense of
+    /// the search's guarantees: an evicted state may be explored again, so the search may go
+    /// around in circles forever unless a depth limit, node budget or timeout is configured,
+    /// and the plan found may be longer than the shortest one. Only the breadth-first search
+    /// for the fewest trips without symmetry reduction and the greedy search support this.
+    pub fn visited_cap(mut self, cap: usize) -> Self {
+        self.visited_cap = Some(cap);
+        self
+    }
+
+    /// Searches for a state satisfying the predicate rather than [`State::is_goal`].
+    pub fn goal(mut self, goal: impl Fn(&S) -> bool + 'a) -> Self {
+        self.goal = Some(Box::new(goal));
+        self
+    }
+
+    /// Discards every state for which the predicate returns `true`.
+    pub fn prune(mut self, prune: impl Fn(&S) -> bool + 'a) -> Self {
+        self.prune = Some(Box::new(prune));
+        self
+    }
+
+    /// Reports the progress of the search to the callback whenever a state is taken from the
+    /// fringe, passing the number of states explored so far and the number of states waiting
+    /// in the fringe. This does not change the outcome of the search.
+    ///
+    /// Only the breadth-first search for the fewest trips without symmetry reduction and the
+    /// greedy search report their progress; the other searches ignore the callback.
+    pub fn progress(mut self, report: impl Fn(usize, usize) + 'a) -> Self {
+        self.progress = Some(Box::new(report));
+        self
+    }
+
+    /// Passes a summary line to the [listener](crate::verbosity::set_listener) every `every`
+    /// states explored, telling the number of states explored, the depth of the latest one and
+    /// the size of the fringe, as well as the number of states generated if they are being
+    /// [counted](crate::analysis::counting).
+    ///
+    /// Unlike the progress of every single state explored, this is passed on even if the search
+    /// is not [verbose](SearchConfig::verbose), allowing to monitor long searches without being
+    /// flooded. Like [`SearchConfig::progress`], only the breadth-first search for the fewest
+    /// trips without symmetry reduction and the greedy search support this.
+    pub fn heartbeat(mut self, every: usize) -> Self {
+        self.heartbeat = Some(every);
+        self
+    }
+
+    /// Checks whether the strategy and objective support every configured option.
+    pub fn validate(&self) -> Result<(), String> {
+        let limited = self.max_depth.is_some()
+            || self.max_crossings.is_some()
+            || self.max_nodes.is_some()
+            || self.timeout.is_some()
+            || self.cancel.is_some();
+        let pruning = self.prune.is_some();
+
+        if self.max_crossings.is_some()
+            && (self.strategy != Strategy::BreadthFirst || self.objective != Objective::Trips)
+        {
+            return Err(String::from(
+                "A crossing cap requires the default search strategy and objective.",
+            ));
+        }
+
+        if self.objective != Objective::Trips {
+            if !matches!(
+                self.strategy,
+                Strategy::BreadthFirst | Strategy::IterativeDeepening
+            ) {
+                return Err(format!(
+                    "The {} objective requires the default or the iterative deepening search strategy.",
+                    self.objective
+                ));
+            }
+
+            if limited || pruning {
+                return Err(format!(
+                    "The {} objective does not support limits or pruning.",
+                    self.objective
+                ));
+            }
+        }
+
+        if self.symmetry
+            && (self.strategy != Strategy::BreadthFirst
+                || self.objective != Objective::Trips
+                || limited
+                || pruning)
+        {
+            return Err(String::from(
+                "Symmetry reduction requires the default search strategy and objective, and no limits or pruning.",
+            ));
+        }
+
+        if self.visited_cap == Some(0) {
+            return Err(String::from("The visited cap must be positive."));
+        }
+
+        if self.heartbeat == Some(0) {
+            return Err(String::from("The heartbeat interval must be positive."));
+        }
+
+        if self.visited_cap.is_some()
+            && (!matches!(self.strategy, Strategy::BreadthFirst | Strategy::Greedy)
+                || self.objective != Objective::Trips
+                || self.symmetry)
+        {
+            return Err(String::from(
+                "A visited cap requires the breadth-first or greedy search for the fewest trips, and no symmetry reduction.",
+            ));
+        }
+
+        match self.strategy {
+            Strategy::BreadthFirst | Strategy::Greedy => Ok(()),
+            #[cfg(feature = "rayon")]
+            Strategy::ParallelBreadthFirst if limited || self.goal.is_some() || pruning => {
+                Err(String::from(
+                    "The parallel search does not support limits, custom goals or pruning.",
+                ))
+            }
+            Strategy::Random
+                if self.max_depth.is_some()
+                    || self.timeout.is_some()
+                    || self.cancel.is_some()
+                    || self.goal.is_some()
+                    || pruning =>
+            {
+                Err(String::from(
+                    "The random walk only supports a node budget, limiting its steps.",
+                ))
+            }
+            Strategy::IterativeDeepening if limited || pruning => Err(String::from(
+                "The iterative deepening search does not support limits or pruning.",
+            )),
+            #[cfg(feature = "rayon")]
+            Strategy::ParallelBreadthFirst => Ok(()),
+            Strategy::Random | Strategy::IterativeDeepening => Ok(()),
+        }
+    }
+
+    /// Tests whether the state is a goal state.
+    fn is_goal(&self, state: &S) -> bool
+    where
+        S: State,
+    {
+        match &self.goal {
+            Some(goal) => goal(state),
+            None => state.is_goal(),
+        }
+    }
+
+    /// Tests whether the state is to be discarded.
+    fn is_pruned(&self, state: &S) -> bool {
+        self.prune.as_ref().is_some_and(|prune| prune(state))
+    }
+
+    /// Passes the progress of the search to the callback, if any, and emits a heartbeat if due.
+    fn report(&self, explored: usize, fringe: usize, depth: usize) {
+        if let Some(report) = &self.progress {
+            report(explored, fringe);
+        }
+
+        if self
+            .heartbeat
+            .is_some_and(|every| explored.is_multiple_of(every))
+        {
+            let line = heartbeat(explored, depth, fringe, current_counters());
+            crate::verbosity::emit(format_args!("{line}"));
+        }
+    }
+}
+
+/// Summarizes the progress of a search on a single line, e.g.
+/// `Heartbeat: 1,000 states explored, depth 12, fringe 345, 2,500 generated (40.0% duplicates)`.
+fn heartbeat(
+    explored: usize,
+    depth: usize,
+    fringe: usize,
+    counters: Option<SearchCounters>,
+) -> String {
+    let mut line = format!(
+        "Heartbeat: {} states explored, depth {}, fringe {}",
+        thousands(explored as u64),
+        thousands(depth as u64),
+        thousands(fringe as u64)
+    );
+    if let Some(counters) = counters.filter(|counters| counters.generated > 0) {
+        let duplicates = counters.duplicates as f64 / counters.generated as f64;
+        line.push_str(&format!(
+            ", {} generated ({:.1}% duplicates)",
+            thousands(counters.generated as u64),
+            duplicates * 100.0
+        ));
+    }
+    line
+}
+
+impl<'a, S> Default for SearchConfig<'a, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Searches the state space as configured, recording every explored state
+/// in the specified [`History`].
+///
+/// Fails if the configuration is invalid; see [`SearchConfig::validate`].
+pub fn solve_with_config<S, A>(
+    initial_state: S,
+    config: &SearchConfig<S>,
+    history: &mut History<S, A>,
+) -> Result<SearchOutcome<S, A>, String>
+where
+    S: State<Action = A> + Clone + Eq + Debug + Send + Sync,
+    A: Action<State = S> + Clone + Debug + Send,
+    S::Hash: Eq + std::hash::Hash,
+{
+    config.validate()?;
+
+    let search = || solve(initial_state, config, history);
+    if config.verbose {
+        Ok(search())
+    } else {
+        Ok(quietly(search))
+    }
+}
+
+/// Searches the state space as configured, assuming the configuration is valid.
+fn solve<S, A>(
+    initial_state: S,
+    config: &SearchConfig<S>,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug + Send + Sync,
+    A: Action<State = S> + Clone + Debug + Send,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let goal = |state: &S| config.is_goal(state);
+    let prune = |state: &S| config.is_pruned(state);
+    let max_depth = config
+        .max_depth
+        .unwrap_or(usize::MAX)
+        .min(config.max_crossings.unwrap_or(usize::MAX));
+    // Whether running out of crossings, rather than any other limit, cut off the search.
+    let out_of_crossings = config
+        .max_crossings
+        .is_some_and(|cap| config.max_depth.is_none_or(|depth| cap <= depth));
+
+    let started = Instant::now();
+    let stopped = Cell::new(false);
+    let limit = |explored: usize| {
+        if config
+            .cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            return Some(String::from("cancelled"));
+        }
+
+        if config.max_nodes.is_some_and(|max| explored >= max) {
+            return Some(String::from("node budget exhausted"));
+        }
+
+        match config.timeout {
+            Some(timeout)
+                if explored.is_multiple_of(TIMEOUT_CHECK_INTERVAL)
+                    && started.elapsed() >= timeout =>
+            {
+                Some(format!("timed out after {} ms", timeout.as_millis()))
+            }
+            _ => None,
+        }
+    };
+    let stop = |explored: usize| {
+        let reason = limit(explored);
+        stopped.set(reason.is_some());
+        reason
+    };
+
+    let report = |explored, fringe, lineage: &Rc<Lineage<S, A>>| {
+        config.report(explored, fringe, lineage.depth)
+    };
+    let observed = || match config.visited_cap {
+        Some(cap) => Visited::bounded(cap),
+        None => Visited::new(),
+    };
+
+    match config.strategy {
+        Strategy::BreadthFirst if config.objective != Objective::Trips => uniform_cost_search(
+            initial_state,
+            goal,
+            |action: &A, _: &S| action.cost(config.objective),
+            history,
+        ),
+        Strategy::BreadthFirst if config.symmetry => {
+            solve_with_symmetry(initial_state, goal, history)
+        }
+        Strategy::BreadthFirst => {
+            let fringe = Reported::new(Fifo::new(), report);
+            let outcome = solve_until(
+                initial_state,
+                goal,
+                prune,
+                fringe,
+                observed(),
+                history,
+                max_depth,
+                stop,
+            );
+            match outcome {
+                SearchOutcome::Exhausted {
+                    states_explored, ..
+                } if out_of_crossings && !stopped.get() => {
+                    SearchOutcome::Unsolvable { states_explored }
+                }
+                outcome => outcome,
+            }
+        }
+        Strategy::Greedy => {
+            let fringe =
+                PriorityFringe::new(|lineage: &Rc<Lineage<S, A>>| lineage.state.heuristic());
+            let fringe = Reported::new(fringe, report);
+            solve_until(
+                initial_state,
+                goal,
+                prune,
+                fringe,
+                observed(),
+                history,
+                max_depth,
+                stop,
+            )
+        }
+        #[cfg(feature = "rayon")]
+        Strategy::ParallelBreadthFirst => parallel_search(initial_state, history),
+        Strategy::Random => random_search(
+            initial_state,
+            config.seed,
+            config.max_nodes.unwrap_or(RANDOM_WALK_MAX_STEPS),
+            history,
+        ),
+        Strategy::IterativeDeepening => {
+            let cost = |action: &A, _: &S| action.cost(config.objective);
+            let heuristic = |state: &S| state.admissible_heuristic(config.objective);
+            if config.memoize {
+                let mut cache = ActionCache::new();
+                ida_star_memoized(initial_state, goal, cost, heuristic, &mut cache, history)
+            } else {
+                ida_star(initial_state, goal, cost, heuristic, history)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use std::cell::RefCell;
+
+    #[test]
+    fn default_config_matches_plain_search() {
+        let expected =
+            quietly(|| crate::search::search(WorldState::default())).expect("problem is solvable");
+        let plan = solve_with_config(
+            WorldState::default(),
+            &SearchConfig::new().verbose(false),
+            &mut History::new(),
+        )
+        .expect("configuration is valid")
+        .into_plan()
+        .expect("problem is solvable");
+
+        let states =
+            |plan: crate::plan::Plan<_, _>| plan.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+        assert_eq!(states(plan), states(expected));
+    }
+
+    #[test]
+    fn progress_is_reported_for_every_explored_state() {
+        let reports = RefCell::new(Vec::new());
+        let config = SearchConfig::new()
+            .progress(|explored, fringe| reports.borrow_mut().push((explored, fringe)))
+            .verbose(false);
+        let outcome = solve_with_config(WorldState::default(), &config, &mut History::new())
+            .expect("configuration is valid");
+        let SearchOutcome::Solved(plan) = outcome else {
+            panic!("problem is solvable");
+        };
+        drop(config);
+
+        let reports = reports.into_inner();
+        assert_eq!(plan.len(), 11);
+        assert_eq!(reports.first(), Some(&(1, 0)));
+        assert!(reports
+            .iter()
+            .map(|(explored, _)| *explored)
+            .eq(1..=reports.len()));
+    }
+
+    #[test]
+    fn depth_limit_exhausts_search() {
+        let config = SearchConfig::new().max_depth(10).verbose(false);
+        let outcome = solve_with_config(WorldState::default(), &config, &mut History::new());
+        assert!(matches!(outcome, Ok(SearchOutcome::Exhausted { .. })));
+    }
+
+    #[test]
+    fn crossing_cap_rules_out_longer_plans() {
+        let solve = |max_crossings| {
+            let config = SearchConfig::new()
+                .max_crossings(max_crossings)
+                .verbose(false);
+            solve_with_config(WorldState::default(), &config, &mut History::new())
+                .expect("configuration is valid")
+        };
+
+        // The fewest-trip plan takes eleven crossings.
+        assert!(matches!(solve(10), SearchOutcome::Unsolvable { .. }));
+        assert_eq!(solve(11).into_plan().map(|plan| plan.len()), Some(11));
+    }
+
+    #[test]
+    fn moves_objective_carries_fewer_entities() {
+        use crate::problems::humans_and_zombies::{HumansAndZombies, WorldAction};
+        use crate::problems::Problem;
+
+        let initial = HumansAndZombies::new(3, 3, 4).initial();
+        let solve = |objective| {
+            let config = SearchConfig::new().objective(objective).verbose(false);
+            solve_with_config(initial.clone(), &config, &mut History::new())
+                .expect("configuration is valid")
+                .into_plan()
+                .expect("problem is solvable")
+        };
+        let moves = |plan: &crate::plan::Plan<WorldState, WorldAction>| {
+            plan.steps()
+                .iter()
+                .map(|(action, _)| action.cost(Objective::Moves))
+                .sum::<u32>()
+        };
+
+        // Both take three trips, but the breadth-first plan rows two humans back, not one zombie.
+        let fewest_trips = solve(Objective::Trips);
+        let fewest_moves = solve(Objective::Moves);
+        assert_eq!(fewest_trips.len(), 3);
+        assert_eq!(fewest_moves.len(), 3);
+        assert_eq!(moves(&fewest_trips), 10);
+        assert_eq!(moves(&fewest_moves), 8);
+    }
+
+    #[test]
+    fn natural_objectives_yield_optimal_plans() {
+        use crate::problems::bridge_and_torch::BridgeAndTorch;
+        use crate::problems::humans_and_zombies::HumansAndZombies;
+        use crate::problems::Problem;
+
+        let problem = BridgeAndTorch::default();
+        let objective = problem.natural_objective();
+        let config = SearchConfig::new().objective(objective).verbose(false);
+        let plan = solve_with_config(problem.initial(), &config, &mut History::new())
+            .expect("configuration is valid")
+            .into_plan()
+            .expect("problem is solvable");
+        let minutes = plan
+            .steps()
+            .iter()
+            .map(|(a, _)| a.cost(objective))
+            .sum::<u32>();
+        assert_eq!(objective, Objective::Time);
+        assert_eq!(minutes, 15);
+
+        let problem = HumansAndZombies::default();
+        let objective = problem.natural_objective();
+        let config = SearchConfig::new().objective(objective).verbose(false);
+        let plan = solve_with_config(problem.initial(), &config, &mut History::new())
+            .expect("configuration is valid")
+            .into_plan()
+            .expect("problem is solvable");
+        let trips = plan
+            .steps()
+            .iter()
+            .map(|(a, _)| a.cost(objective))
+            .sum::<u32>();
+        assert_eq!(objective, Objective::Trips);
+        assert_eq!(trips, 11);
+    }
+
+    #[test]
+    fn iterative_deepening_matches_breadth_first_on_larger_boats() {
+        use crate::problems::humans_and_zombies::HumansAndZombies;
+        use crate::problems::Problem;
+
+        // Counting the people left to carry over overestimates the trips once the boat holds
+        // more than two, which used to make IDA* settle for longer plans.
+        for (humans, zombies, boat) in [(6, 4, 4), (10, 10, 6), (5, 5, 3)] {
+            let initial = HumansAndZombies::new(humans, zombies, boat).initial();
+            let solve = |strategy| {
+                let config = SearchConfig::new().strategy(strategy).verbose(false);
+                solve_with_config(initial.clone(), &config, &mut History::new())
+                    .expect("configuration is valid")
+                    .into_plan()
+                    .map(|plan| plan.len())
+            };
+            let expected = solve(Strategy::BreadthFirst);
+            assert!(expected.is_some());
+            assert_eq!(solve(Strategy::IterativeDeepening), expected);
+        }
+    }
+
+    #[test]
+    fn capped_search_terminates_within_the_node_budget() {
+        // Remembering just a couple of states, the search keeps coming back to old ones.
+        let config = SearchConfig::new()
+            .visited_cap(2)
+            .max_nodes(500)
+            .verbose(false);
+        let outcome = solve_with_config(WorldState::default(), &config, &mut History::new())
+            .expect("configuration is valid");
+        match outcome {
+            SearchOutcome::Solved(plan) => assert!(plan.len() >= 11),
+            SearchOutcome::Exhausted {
+                states_explored, ..
+            } => assert_eq!(states_explored, 500),
+            SearchOutcome::Unsolvable { .. } => panic!("problem is solvable"),
+        }
+
+        // Remembering every state, the search is unaffected.
+        let config = SearchConfig::new().visited_cap(100).verbose(false);
+        let outcome = solve_with_config(WorldState::default(), &config, &mut History::new());
+        assert_eq!(
+            outcome.ok().and_then(|o| o.into_plan()).map(|p| p.len()),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn heartbeats_summarize_the_progress() {
+        assert_eq!(
+            heartbeat(1000, 7, 42, None),
+            "Heartbeat: 1,000 states explored, depth 7, fringe 42"
+        );
+        let counters = SearchCounters {
+            generated: 2500,
+            duplicates: 1000,
+        };
+        assert_eq!(
+            heartbeat(1000, 7, 42, Some(counters)),
+            "Heartbeat: 1,000 states explored, depth 7, fringe 42, 2,500 generated (40.0% duplicates)"
+        );
+
+        // Heartbeats do not change the outcome of the search.
+        let config = SearchConfig::new().heartbeat(5).verbose(false);
+        let outcome = solve_with_config(WorldState::default(), &config, &mut History::new());
+        assert_eq!(
+            outcome.ok().and_then(|o| o.into_plan()).map(|p| p.len()),
+            Some(11)
+        );
+        assert!(SearchConfig::<WorldState>::new()
+            .heartbeat(0)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn unsupported_options_are_rejected() {
+        let config = SearchConfig::<WorldState>::new()
+            .strategy(Strategy::Random)
+            .timeout(Duration::from_secs(1));
+        assert!(config.validate().is_err());
+
+        #[cfg(feature = "rayon")]
+        assert!(SearchConfig::<WorldState>::new()
+            .strategy(Strategy::ParallelBreadthFirst)
+            .timeout(Duration::from_secs(1))
+            .validate()
+            .is_err());
+
+        let config = SearchConfig::<WorldState>::new()
+            .objective(Objective::Moves)
+            .max_nodes(10);
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Alternative heuristics to plug into [`a_star`](crate::search::a_star) and
+//! [`ida_star`](crate::search::ida_star) in place of [`State::heuristic`](crate::search::State::heuristic),
+//! in order to compare how much they guide the search.
+//!
+//! Every heuristic is admissible for the number of trips or, for Bridge and Torch, the time,
+//! i.e. it never exceeds the cost of the cheapest plan to the goal.
+
+use crate::problems::bridge_and_torch::{RiverSide, WorldState as BridgeState};
+use crate::problems::humans_and_zombies::WorldState as ZombieState;
+
+/// Provides no guidance at all, turning A* into a uniform-cost search.
+pub fn zero<S>(_state: &S) -> u32 {
+    0
+}
+
+/// Estimates the remaining trips of the Humans and Zombies problem as the number of trips needed
+/// to carry the people still on the starting river bank if nobody ever had to row back.
+pub fn people_over_capacity(state: &ZombieState) -> u32 {
+    let origin = state.bank(state.target.switch_bank());
+    let people = origin.humans as u32 + origin.zombies as u32;
+    people.div_ceil(state.boat.capacity as u32)
+}
+
+/// Estimates the remaining trips of the Humans and Zombies problem, taking into account that
+/// someone has to row the boat back after every trip but the last.
+///
+/// This is never less than [`people_over_capacity`].
+pub fn crossings_remaining(state: &ZombieState) -> u32 {
+    let origin = state.bank(state.target.switch_bank());
+    let people = origin.humans as u32 + origin.zombies as u32;
+    if people == 0 {
+        return 0;
+    }
+
+    let capacity = state.boat.capacity as u32;
+    if state.boat.bank == state.target {
+        // Someone has to bring the boat back first, joining those left to be carried over.
+        2 * forward_trips(people + 1, capacity)
+    } else {
+        2 * forward_trips(people, capacity) - 1
+    }
+}
+
+/// Estimates the remaining time of the Bridge and Torch problem as the walking time of the
+/// slowest person still on the left, plus the walking time of the fastest person for every
+/// time the torch has to be brought back.
+///
+/// This is never less than [`State::heuristic`](crate::search::State::heuristic) of the state.
+pub fn fastest_walker(state: &BridgeState) -> u32 {
+    let left = &state.left.people;
+    let Some(slowest) = left.iter().map(|p| p.walking_time as u32).max() else {
+        return 0;
+    };
+
+    let fastest = left
+        .iter()
+        .chain(&state.right.people)
+        .map(|p| p.walking_time as u32)
+        .min()
+        .unwrap_or_default();
+
+    let capacity = state.forward_capacity as u32;
+    let returns = match state.torch.side {
+        RiverSide::Left => forward_trips(left.len() as u32, capacity) - 1,
+        // Someone has to bring the torch back first, joining those left to cross.
+        RiverSide::Right => forward_trips(left.len() as u32 + 1, capacity),
+    };
+    slowest + returns * fastest
+}
+
+/// Determines the least number of trips across to carry `people` over if every trip but the
+/// last has to bring someone back; at least one person remains for impossible crossings.
+fn forward_trips(people: u32, capacity: u32) -> u32 {
+    if people <= capacity {
+        return 1;
+    }
+
+    match capacity {
+        // Nobody can cross without someone bringing the boat right back.
+        0 | 1 => people,
+        _ => 1 + (people - capacity).div_ceil(capacity - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::History;
+    use crate::plan::Plan;
+    use crate::problems::bridge_and_torch::BridgeAndTorch;
+    use crate::problems::humans_and_zombies::HumansAndZombies;
+    use crate::problems::Problem;
+    use crate::search::{a_star, uniform_cost_search, Action, State};
+    use crate::strategies::Objective;
+    use crate::verbosity::quietly;
+
+    /// Asserts that no heuristic exceeds the remaining cost at any state of the cheapest plan,
+    /// and that A* guided by any of them finds an equally cheap plan.
+    fn assert_admissible<S, A>(initial: S, objective: Objective, heuristics: &[fn(&S) -> u32])
+    where
+        S: State<Action = A> + Clone + Eq + std::fmt::Debug,
+        A: Action<State = S> + Clone + std::fmt::Debug,
+        S::Hash: Eq + std::hash::Hash,
+    {
+        let cost = |action: &A, _: &S| action.cost(objective);
+        let total = |plan: &Plan<S, A>| plan.steps().iter().map(|(a, s)| cost(a, s)).sum::<u32>();
+        let plan =
+            quietly(|| uniform_cost_search(initial.clone(), S::is_goal, cost, &mut History::new()))
+                .into_plan()
+                .expect("problem is solvable");
+
+        let costs = plan
+            .steps()
+            .iter()
+            .map(|(action, _)| action.cost(objective))
+            .collect::<Vec<_>>();
+        let states = std::iter::once(plan.initial()).chain(plan.steps().iter().map(|(_, s)| s));
+        for (index, state) in states.enumerate() {
+            let remaining = costs[index..].iter().sum::<u32>();
+            for heuristic in heuristics {
+                assert!(heuristic(state) <= remaining, "{state:?} overestimated");
+            }
+        }
+
+        for heuristic in heuristics {
+            let guided = quietly(|| {
+                a_star(
+                    initial.clone(),
+                    S::is_goal,
+                    cost,
+                    heuristic,
+                    &mut History::new(),
+                )
+            })
+            .into_plan()
+            .expect("problem is solvable");
+            assert_eq!(total(&guided), total(&plan));
+        }
+    }
+
+    #[test]
+    fn humans_and_zombies_heuristics_are_admissible() {
+        for (people, boat) in [(3, 2), (4, 3), (5, 3), (10, 4), (12, 7), (20, 20)] {
+            let problem = HumansAndZombies::new(people, people, boat);
+            assert_admissible(
+                problem.initial(),
+                Objective::Trips,
+                &[zero, people_over_capacity, crossings_remaining],
+            );
+        }
+    }
+
+    #[test]
+    fn bridge_and_torch_heuristics_are_admissible() {
+        for (bridge, people) in [
+            (2, vec![1, 2, 5, 8]),
+            (2, vec![1, 2, 5, 8, 10]),
+            (3, vec![1, 3, 6, 8, 12]),
+            (2, vec![5, 5, 5, 5]),
+        ] {
+            let problem = BridgeAndTorch::new(bridge, u8::MAX, people);
+            assert_admissible(
+                problem.initial(),
+                Objective::Time,
+                &[zero, BridgeState::heuristic, fastest_walker],
+            );
+        }
+    }
+}
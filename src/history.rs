@@ -1,3 +1,9 @@
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::search::State;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::rc::Rc;
+
 /// Describes the lineage of a world state.
 #[derive(Clone)]
 pub struct Lineage<S, A> {
@@ -5,6 +11,8 @@ pub struct Lineage<S, A> {
     pub id: usize,
     /// The ID of the parent state.
     parent_id: usize,
+    /// The number of actions taken to get to the state.
+    pub depth: usize,
     /// The action that was taken to get to the state.
     /// [`None`] is only meaningful for the root state.
     pub action: Option<A>,
@@ -13,14 +21,29 @@ pub struct Lineage<S, A> {
 }
 
 /// Tracks the history of world states.
-pub struct History<S, A>(Vec<Lineage<S, A>>);
+///
+/// Every state is stored exactly once; the history hands out shared handles
+/// to its entries rather than copies of them.
+pub struct History<S, A> {
+    /// The explored lineages, indexed by their ID.
+    entries: Vec<Rc<Lineage<S, A>>>,
+    /// The ID of the lineage that reached the goal, if any.
+    goal: Option<usize>,
+}
 
 impl<S, A> Lineage<S, A> {
     /// Creates a new lineage for the given state.
-    pub const fn new(id: usize, parent_id: usize, action: Option<A>, state: S) -> Self {
+    pub const fn new(
+        id: usize,
+        parent_id: usize,
+        depth: usize,
+        action: Option<A>,
+        state: S,
+    ) -> Self {
         Self {
             id,
             parent_id,
+            depth,
             action,
             state,
         }
@@ -36,41 +59,94 @@ impl<S, A> Lineage<S, A> {
     }
 }
 
-impl<S, A> History<S, A>
-where
-    S: Clone,
-    A: Clone,
-{
+impl<S, A> History<S, A> {
     pub fn new() -> Self {
-        Self(Vec::default())
+        Self {
+            entries: Vec::default(),
+            goal: None,
+        }
     }
 
-    /// Inserts a new entry into the history.
-    pub fn create_root(&mut self, state: S) -> Lineage<S, A> {
-        let entry = Lineage::new(0, 0, None, state);
-        self.0.push(entry.clone());
+    /// Inserts a new root entry, i.e. one without a parent, into the history.
+    pub fn create_root(&mut self, state: S) -> Rc<Lineage<S, A>> {
+        let id = self.entries.len();
+        let entry = Rc::new(Lineage::new(id, id, 0, None, state));
+        self.entries.push(Rc::clone(&entry));
         entry
     }
 
     /// Inserts a new entry into the history.
-    pub fn create_entry(&mut self, action: A, state: S, parent: &Lineage<S, A>) -> Lineage<S, A> {
-        let id = self.0.len();
-        let entry = Lineage::new(id, parent.id, Some(action), state);
-        self.0.push(entry.clone());
+    pub fn create_entry(
+        &mut self,
+        action: A,
+        state: S,
+        parent: &Lineage<S, A>,
+    ) -> Rc<Lineage<S, A>> {
+        let id = self.entries.len();
+        let entry = Rc::new(Lineage::new(
+            id,
+            parent.id,
+            parent.depth + 1,
+            Some(action),
+            state,
+        ));
+        self.entries.push(Rc::clone(&entry));
         entry
     }
 
+    /// Gets the entry with the specified ID.
+    pub fn get(&self, id: usize) -> Option<&Rc<Lineage<S, A>>> {
+        self.entries.get(id)
+    }
+
+    /// Gets the number of entries in the history.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Determines whether the history has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over all entries in the order they were explored.
+    pub fn iter(&self) -> impl Iterator<Item = &Lineage<S, A>> {
+        self.entries.iter().map(Rc::as_ref)
+    }
+
+    /// Gets the lineage that reached the goal, if any.
+    pub fn goal(&self) -> Option<&Rc<Lineage<S, A>>> {
+        self.goal.and_then(|id| self.entries.get(id))
+    }
+
+    /// Marks the specified lineage as the one that reached the goal.
+    pub fn set_goal(&mut self, lineage: &Lineage<S, A>) {
+        self.goal = Some(lineage.id);
+    }
+}
+
+impl<S, A> Default for History<S, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, A> History<S, A>
+where
+    S: Clone,
+    A: Clone,
+{
     /// Backtracks the path that lead to the specified lineage.
     pub fn backtrack<'a>(
         &'a self,
         mut lineage: &'a Lineage<S, A>,
     ) -> impl Iterator<Item = (Option<A>, S)> {
-        let mut path = Vec::new();
+        let mut path = Vec::with_capacity(lineage.depth + 1);
 
         loop {
             path.push((lineage.action.clone(), lineage.state.clone()));
             if let Some(parent_id) = lineage.parent_id() {
-                lineage = self.0.get(parent_id).expect("entry not found");
+                lineage = self.entries.get(parent_id).expect("entry not found");
             } else {
                 break;
             }
@@ -79,3 +155,308 @@ where
         path.into_iter().rev()
     }
 }
+
+impl<S, A> History<S, A>
+where
+    S: State + PrettyPrintState,
+    A: PrettyPrintAction<S>,
+{
+    /// Renders the explored search tree in Graphviz DOT format.
+    ///
+    /// Every lineage becomes a node labelled with its pretty-printed state, and every
+    /// parent/child relation becomes an edge labelled with the action taken.
+    /// Goal states and the edges along the solution path are highlighted.
+    pub fn to_dot(&self) -> String {
+        let solution = self.solution_ids();
+
+        let mut dot = String::from("digraph search {\n");
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+        for lineage in self.iter() {
+            let style = if lineage.state.is_goal() {
+                ", style=filled, fillcolor=palegreen"
+            } else if solution.contains(&lineage.id) {
+                ", style=filled, fillcolor=lightyellow"
+            } else {
+                ""
+            };
+
+            writeln!(
+                dot,
+                "    n{} [label=\"{}\"{}];",
+                lineage.id,
+                escape_dot(lineage.state.pretty_print().trim()),
+                style
+            )
+            .expect("writing to a string cannot fail");
+        }
+
+        for lineage in self.iter() {
+            let (Some(parent_id), Some(action)) = (lineage.parent_id(), &lineage.action) else {
+                continue;
+            };
+
+            let style = if solution.contains(&lineage.id) {
+                ", color=red, fontcolor=red, penwidth=2"
+            } else {
+                ""
+            };
+
+            writeln!(
+                dot,
+                "    n{} -> n{} [label=\"{}\"{}];",
+                parent_id,
+                lineage.id,
+                escape_dot(action.pretty_print(&lineage.state).trim()),
+                style
+            )
+            .expect("writing to a string cannot fail");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the explored search tree as CSV, with a header row followed by one row per
+    /// lineage in the order they were explored.
+    ///
+    /// The columns are `id`, `parent_id`, `depth`, `action` and `state`, where the action and
+    /// state are pretty-printed; the root has neither a parent nor an action, leaving their
+    /// columns empty. Values containing commas, quotes or line breaks are quoted.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("id,parent_id,depth,action,state\n");
+        for lineage in self.iter() {
+            let parent_id = lineage
+                .parent_id()
+                .map(|id| id.to_string())
+                .unwrap_or_default();
+            let action = lineage
+                .action
+                .as_ref()
+                .map(|action| action.pretty_print(&lineage.state))
+                .unwrap_or_default();
+
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                lineage.id,
+                parent_id,
+                lineage.depth,
+                escape_csv(action.trim()),
+                escape_csv(lineage.state.pretty_print().trim())
+            )
+            .expect("writing to a string cannot fail");
+        }
+        csv
+    }
+
+    /// Renders the explored search tree as a Mermaid `graph TD` flowchart, e.g. for embedding
+    /// in Markdown rendered by GitHub.
+    ///
+    /// Like in [`History::to_dot`], every lineage becomes a node labelled with its pretty-printed
+    /// state and every parent/child relation an edge labelled with the action taken. The states
+    /// along the solution path and the goal state are styled by the `solution` and `goal`
+    /// classes, and the edges along the path are highlighted.
+    pub fn to_mermaid(&self) -> String {
+        let solution = self.solution_ids();
+
+        let mut mermaid = String::from("graph TD\n");
+        mermaid.push_str("    classDef solution fill:#ffffe0,stroke:#b8860b\n");
+        mermaid.push_str("    classDef goal fill:#98fb98,stroke:#228b22\n");
+
+        for lineage in self.iter() {
+            let class = if lineage.state.is_goal() {
+                ":::goal"
+            } else if solution.contains(&lineage.id) {
+                ":::solution"
+            } else {
+                ""
+            };
+
+            writeln!(
+                mermaid,
+                "    n{}[\"{}\"]{}",
+                lineage.id,
+                escape_mermaid(lineage.state.pretty_print().trim()),
+                class
+            )
+            .expect("writing to a string cannot fail");
+        }
+
+        // Links are styled by the order they are defined in.
+        let mut highlighted = Vec::new();
+        let links = self
+            .iter()
+            .filter_map(|lineage| Some((lineage.parent_id()?, lineage.action.as_ref()?, lineage)));
+        for (index, (parent_id, action, lineage)) in links.enumerate() {
+            if solution.contains(&lineage.id) {
+                highlighted.push(index.to_string());
+            }
+
+            writeln!(
+                mermaid,
+                "    n{} -->|\"{}\"| n{}",
+                parent_id,
+                escape_mermaid(action.pretty_print(&lineage.state).trim()),
+                lineage.id
+            )
+            .expect("writing to a string cannot fail");
+        }
+
+        if !highlighted.is_empty() {
+            writeln!(
+                mermaid,
+                "    linkStyle {} stroke:red,stroke-width:2px",
+                highlighted.join(",")
+            )
+            .expect("writing to a string cannot fail");
+        }
+        mermaid
+    }
+
+    /// Collects the IDs of all lineages on the path to the goal.
+    fn solution_ids(&self) -> HashSet<usize> {
+        let mut ids = HashSet::new();
+        let mut current = self.goal();
+        while let Some(lineage) = current {
+            ids.insert(lineage.id);
+            current = lineage.parent_id().and_then(|id| self.entries.get(id));
+        }
+        ids
+    }
+}
+
+/// Escapes a string for use as a quoted DOT label.
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes a string for use as a quoted Mermaid label, replacing the characters Mermaid would
+/// take for markup by entity codes and line breaks by `<br>`.
+fn escape_mermaid(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '#' => escaped.push_str("#35;"),
+            '"' => escaped.push_str("#quot;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            '|' => escaped.push_str("#124;"),
+            '`' => escaped.push_str("#96;"),
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes a string for use as a CSV value, quoting it if necessary.
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::solve_with_history;
+
+    #[test]
+    fn dot_highlights_solution_path() {
+        let mut history = History::new();
+        let plan = solve_with_history(WorldState::default(), &mut history)
+            .into_plan()
+            .expect("problem is solvable");
+
+        let dot = history.to_dot();
+        assert!(dot.starts_with("digraph search {"));
+        assert_eq!(dot.matches("fillcolor=palegreen").count(), 1);
+
+        // One highlighted edge per action taken.
+        assert_eq!(dot.matches("penwidth=2").count(), plan.len());
+    }
+
+    #[test]
+    fn csv_has_a_row_per_explored_lineage() {
+        let mut history = History::new();
+        solve_with_history(WorldState::default(), &mut history);
+
+        let csv = history.to_csv();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0], "id,parent_id,depth,action,state");
+        assert_eq!(rows[1], "0,,0,,HHH ZZZ |B~~~|");
+        assert!(rows[2].starts_with("1,0,1,ZZ →,"));
+        assert_eq!(rows.len(), history.len() + 1);
+
+        assert_eq!(escape_csv("plain text"), "plain text");
+        assert_eq!(escape_csv(r#"a "b", c"#), r#""a ""b"", c""#);
+    }
+
+    #[test]
+    fn mermaid_highlights_solution_path() {
+        let mut history = History::new();
+        let plan = solve_with_history(WorldState::default(), &mut history)
+            .into_plan()
+            .expect("problem is solvable");
+
+        let mermaid = history.to_mermaid();
+        let lines = mermaid.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "graph TD");
+        assert!(mermaid.contains("    n0[\"HHH ZZZ #124;B~~~#124;\"]:::solution\n"));
+        assert!(mermaid.contains("    n0 -->|\"ZZ →\"| n1\n"));
+        assert_eq!(mermaid.matches(":::goal").count(), 1);
+        assert_eq!(mermaid.matches(" -->|").count(), history.len() - 1);
+
+        // One highlighted link per action taken.
+        let highlighted = lines.last().expect("links are highlighted");
+        let indices = highlighted
+            .strip_prefix("    linkStyle ")
+            .and_then(|style| style.split(' ').next())
+            .expect("links are highlighted");
+        assert_eq!(indices.split(',').count(), plan.len());
+
+        assert_eq!(
+            escape_mermaid("a \"b\" <c>\nd"),
+            "a #quot;b#quot; #lt;c#gt;<br>d"
+        );
+    }
+
+    #[test]
+    fn every_explored_entry_but_the_root_has_an_explored_parent() {
+        let mut history = History::new();
+        solve_with_history(WorldState::default(), &mut history);
+        assert!(!history.is_empty());
+
+        let roots = history.iter().filter(|lineage| lineage.depth == 0).count();
+        assert_eq!(roots, 1);
+
+        for lineage in history.iter().skip(1) {
+            let parent = lineage
+                .parent_id()
+                .and_then(|id| history.get(id))
+                .expect("parent is explored");
+            assert!(parent.id < lineage.id);
+            assert_eq!(parent.depth + 1, lineage.depth);
+        }
+        assert_eq!(history.iter().count(), history.len());
+    }
+
+    #[test]
+    fn lineage_depth_counts_actions_taken() {
+        let mut history = History::<u8, char>::new();
+        let mut lineage = history.create_root(0);
+        assert_eq!(lineage.depth, 0);
+
+        for (state, action) in (1..=5).zip("abcde".chars()) {
+            lineage = history.create_entry(action, state, &lineage);
+        }
+
+        assert_eq!(lineage.depth, 5);
+        assert_eq!(history.backtrack(&lineage).count(), lineage.depth + 1);
+    }
+}
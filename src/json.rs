@@ -0,0 +1,164 @@
+use crate::plan::Plan;
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::replay::{RecordedPlan, RecordedStep};
+use crate::search::{Action, ActionSummary};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Formats a plan as a JSON object holding the number of steps, the pretty-printed
+/// initial state and every action along with the state it leads to.
+///
+/// Every action is given both pretty-printed and as the structured [`summary`](Action::describe)
+/// of its direction and the entities it moves, by which it is replayed via [`parse_plan_json`].
+pub fn format_plan_json<S, A>(plan: &Plan<S, A>) -> String
+where
+    S: PrettyPrintState,
+    A: PrettyPrintAction<S> + Action<State = S>,
+{
+    let steps = plan
+        .steps()
+        .iter()
+        .map(|(action, state)| {
+            let summary = serde_json::to_string(&action.describe(state))
+                .expect("action summaries are serializable");
+            format!(
+                r#"{{"action":"{}","summary":{},"state":"{}"}}"#,
+                escape_json(action.pretty_print(state).trim()),
+                summary,
+                escape_json(state.pretty_print().trim())
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        r#"{{"steps":{},"initial":"{}","plan":[{}]}}"#,
+        plan.len(),
+        escape_json(plan.initial().pretty_print().trim()),
+        steps.join(",")
+    )
+}
+
+/// Parses a plan in the format produced by [`format_plan_json`], to be replayed via
+/// [`validate_plan`](crate::replay::validate_plan).
+///
+/// Only the `plan` array and the `summary` of every step are required; the `action` names the
+/// step, if given, and the number of `steps` and the states are ignored.
+pub fn parse_plan_json(json: &str) -> Result<RecordedPlan, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {e}"))?;
+
+    let steps = match value.get("plan") {
+        Some(Value::Array(steps)) => steps,
+        _ => return Err(String::from("The `plan` must be a list of steps.")),
+    };
+
+    let steps = steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let action = match step.get("action") {
+                None => None,
+                Some(Value::String(text)) => Some(text.clone()),
+                Some(_) => return Err(String::from("The `action` must be a string.")),
+            };
+            let summary = step
+                .get("summary")
+                .ok_or_else(|| format!("Step {} has no `summary`.", index + 1))?;
+            let summary = ActionSummary::deserialize(summary)
+                .map_err(|e| format!("Step {} has an invalid `summary`: {e}", index + 1))?;
+            Ok(RecordedStep { action, summary })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(RecordedPlan { steps })
+}
+
+/// Escapes a string for use within a quoted JSON string.
+pub(crate) fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                write!(escaped, "\\u{:04x}", c as u32).expect("writing to a string cannot fail")
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn plan_is_rendered_as_json() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let json = format_plan_json(&plan);
+
+        assert!(json.starts_with(r#"{"steps":11,"initial":"HHH ZZZ |B~~~|","plan":[{"action":"#));
+        assert_eq!(json.matches(r#""action":"#).count(), plan.len());
+        assert!(json.ends_with(r#""state":"|~~~B| HHH ZZZ"}]}"#));
+    }
+
+    #[test]
+    fn plan_steps_are_summarized() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let json: Value = serde_json::from_str(&format_plan_json(&plan)).expect("JSON is valid");
+
+        let summaries = json["plan"]
+            .as_array()
+            .expect("plan is a list")
+            .iter()
+            .map(|step| &step["summary"])
+            .collect::<Vec<_>>();
+        assert_eq!(summaries.len(), plan.len());
+        assert_eq!(summaries[0]["direction"], "right");
+        assert_eq!(summaries[1]["direction"], "left");
+
+        let moved = |direction: &str| {
+            summaries
+                .iter()
+                .filter(|summary| summary["direction"] == direction)
+                .map(|summary| summary["entities"].as_array().expect("entities").len())
+                .sum::<usize>()
+        };
+        assert_eq!(moved("right") - moved("left"), 6);
+    }
+
+    #[test]
+    fn plan_is_parsed_from_json() {
+        let recorded = parse_plan_json(
+            r#"{"initial":"a","plan":[
+                {"action":"b","summary":{"direction":"right","entities":["c"]},"state":"d"},
+                {"summary":{"direction":"left","entities":[]}}
+            ]}"#,
+        )
+        .expect("JSON is valid");
+
+        assert_eq!(recorded.steps.len(), 2);
+        assert_eq!(recorded.steps[0].action.as_deref(), Some("b"));
+        assert_eq!(
+            recorded.steps[0].summary,
+            ActionSummary::new("right", vec![String::from("c")])
+        );
+        assert_eq!(recorded.steps[1].action, None);
+
+        assert!(parse_plan_json(r#"{"plan":[{"action":"b"}]}"#).is_err());
+        assert!(parse_plan_json(r#"{"plan":[{"summary":{"direction":"left"}}]}"#).is_err());
+        assert!(parse_plan_json("[]").is_err());
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(escape_json("a\"b\\c\nd\u{1b}"), r#"a\"b\\c\nd\u001b"#);
+    }
+}
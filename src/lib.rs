@@ -0,0 +1,42 @@
+//! Search-based planning on river crossing puzzles.
+
+/// Passes a line of search progress to the [`verbosity::Listener`], unless verbose output
+/// was disabled for the current thread via [`verbosity::set_verbose`].
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::verbosity::is_verbose() {
+            $crate::verbosity::emit(format_args!($($arg)*));
+        }
+    };
+}
+
+pub mod analysis;
+pub mod anytime;
+pub mod bidirectional;
+pub mod compare;
+pub mod config;
+pub mod heuristics;
+pub mod history;
+pub mod json;
+pub mod markdown;
+pub mod memo;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod plan;
+pub mod pretty_print;
+pub mod problems;
+pub mod random;
+pub mod registry;
+pub mod replay;
+pub mod search;
+pub mod snapshot;
+pub mod stats;
+pub mod strategies;
+pub mod symmetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trace;
+pub mod verbosity;
+pub mod visited;
+#[cfg(feature = "wasm")]
+pub mod wasm;
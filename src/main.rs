@@ -1,194 +1,1485 @@
-mod history;
-mod pretty_print;
-mod problems;
-mod search;
-mod strategies;
-
-use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
-use crate::problems::{bridge_and_torch, humans_and_zombies, wolf_goat_cabbage};
-use crate::search::{search, Action, State};
+use clap::parser::ValueSource;
 use clap::{Arg, ArgAction, ArgMatches, Command};
-use colored::Colorize;
+#[cfg(feature = "indicatif")]
+use indicatif::{ProgressBar, ProgressFinish};
 use itertools::Itertools;
+use river_crossing::analysis::{analyze, counting};
+use river_crossing::anytime::anytime_search;
+use river_crossing::compare::{
+    compare_strategies, format_comparison, format_comparison_markdown, optimality_gap,
+};
+use river_crossing::config::{solve_with_config, SearchConfig};
+use river_crossing::history::History;
+use river_crossing::json::{format_plan_json, parse_plan_json};
+use river_crossing::markdown::{format_plan_markdown, MarkdownAction, MarkdownState};
+use river_crossing::plan::Plan;
+use river_crossing::pretty_print::{
+    format_plan_combined, format_plan_styled, step_through, PrettyPrintAction, PrettyPrintState,
+    RenderStyle,
+};
+use river_crossing::problems::Problem;
+use river_crossing::registry::{self, describe_error, rows_linearly, ProblemInfo, ProblemVisitor};
+use river_crossing::replay::{validate_plan, RecordedPlan};
+use river_crossing::search::{
+    count_shortest_solutions, search_all_optimal, search_preferred_optimal, solve_with_dead_ends,
+    Action, SearchOutcome, State,
+};
+use river_crossing::snapshot::{resume, SearchSnapshot};
+#[cfg(feature = "indicatif")]
+use river_crossing::stats::thousands;
+use river_crossing::stats::{Statistics, Summary};
+use river_crossing::strategies::{Objective, Strategy};
+use river_crossing::trace::{self, format_explanation, solve_with_trace};
+use river_crossing::verbosity::{quietly, set_listener};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fmt::Debug;
+use std::fs::File;
 use std::hash::Hash;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-fn main() {
-    let solver = match get_matches().subcommand() {
-        Some(("humans-and-zombies", matches)) => run_problem(humans_and_zombies(matches)),
-        Some(("bridge-and-torch", matches)) => run_problem(bridge_and_torch(matches)),
-        Some(("wolf-goat-cabbage", matches)) => run_problem(wolf_goat_cabbage(matches)),
-        _ => unreachable!("Unhandled subcommand"),
-    };
+/// The formats to render a solution in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Styled text depicting the scene after every step.
+    Text,
+    /// A Markdown table with one row per step.
+    Markdown,
+    /// A JSON object listing every step.
+    Json,
+}
 
-    solver();
+/// Options that apply to every problem.
+struct Options {
+    /// The file to write the explored search tree to, in Graphviz DOT format.
+    dot: Option<PathBuf>,
+    /// The file to write the explored search tree to, in CSV format.
+    graph_csv: Option<PathBuf>,
+    /// The file to write the explored search tree to, as a Mermaid flowchart.
+    mermaid: Option<PathBuf>,
+    /// The file to write the exploration trace to, in JSON format.
+    trace: Option<PathBuf>,
+    /// Whether to explain why the search passed over the branches off the solution.
+    explain: bool,
+    /// The file to write the solution to instead of the standard output.
+    output: Option<PathBuf>,
+    /// The search strategy to use.
+    strategy: Strategy,
+    /// The seed for randomized search strategies.
+    seed: u64,
+    /// The objective to minimize.
+    objective: Objective,
+    /// Whether to count the distinct minimal-length solutions.
+    count: bool,
+    /// Whether to list the explored states without any applicable actions.
+    dead_ends: bool,
+    /// The file to save the progress of a stopped search to, if any.
+    save: Option<PathBuf>,
+    /// The file to resume a saved search from, if any.
+    load: Option<PathBuf>,
+    /// Whether to print every distinct solution with the minimal number of trips.
+    all: bool,
+    /// Whether to print the solution the problem prefers among those with the minimal number
+    /// of trips, e.g. the one keeping the humans safest.
+    safest: bool,
+    /// Whether to print statistics about the solution.
+    stats: bool,
+    /// Whether to compare several search strategies instead of solving the problem once.
+    compare: bool,
+    /// Whether to reveal the solution one step at a time.
+    interactive: bool,
+    /// Whether to show every round trip as a single step.
+    combine: bool,
+    /// Whether to print every improving solution found by an anytime search.
+    anytime: bool,
+    /// Whether to collapse states with their mirror images.
+    symmetry: bool,
+    /// Whether to cache the actions of the states explored.
+    memoize: bool,
+    /// Whether to print the solution from the goal back to the initial state.
+    reverse: bool,
+    /// Whether to minimize the natural objective of the problem rather than the given one.
+    optimal: bool,
+    /// The format to render the solution in.
+    format: Format,
+    /// The maximum number of crossings of the solution.
+    max_crossings: Option<usize>,
+    /// The maximum number of states to explore.
+    max_nodes: Option<usize>,
+    /// The time after which to give up.
+    timeout: Option<Duration>,
+    /// The symbols to render the river scene with.
+    style: RenderStyle,
+    /// Whether to print a one-line summary of the outcome instead of the solution.
+    quiet: bool,
+    /// Whether to show a live readout of the search progress on the terminal.
+    progress: bool,
+    /// The number of states explored between two summary lines printed instead of every state.
+    heartbeat: Option<usize>,
 }
 
-/// Wraps the selected problem's initial state into a function that
-/// searches and prints the solution.
+/// A live readout of the search progress on the standard error, redrawn in place.
+#[cfg(feature = "indicatif")]
+struct ProgressLine(ProgressBar);
+
+/// The names of the search strategies that can be selected.
+#[cfg(feature = "rayon")]
+const STRATEGIES: [&str; 5] = ["bfs", "greedy", "parallel-bfs", "random", "ida-star"];
+#[cfg(not(feature = "rayon"))]
+const STRATEGIES: [&str; 4] = ["bfs", "greedy", "random", "ida-star"];
+
+/// Builds the instance of the registered problem with the specified name from the arguments
+/// of its subcommand, printing the remark on how it came about, if any.
+fn build_problem(name: &str, matches: &ArgMatches, seed: u64) -> Result<Box<dyn Solver>, String> {
+    let instance = registry::find(name)?.build(matches, seed)?;
+    if let Some(remark) = instance.remark {
+        println!("{remark}");
+    }
+    Ok(instance.problem.visit(Boxed))
+}
+
+/// Prints the name and description of every registered problem.
+fn list_problems() {
+    let width = registry::problems()
+        .iter()
+        .map(|problem| problem.name.len())
+        .max()
+        .unwrap_or_default();
+    for problem in registry::problems() {
+        println!("{:<width$}  {}", problem.name, problem.description);
+    }
+}
+
+/// A problem instance that can be solved in every way the command line offers.
 ///
-/// This is a bit of a hacky solution but works around the cyclic
+/// This erases the types of the states and actions, which works around the cyclic
 /// dependencies of associated types on the State and Action traits.
-fn run_problem<S, A>(initial_state: S) -> Box<dyn FnOnce() -> ()>
+trait Solver {
+    /// Searches and prints the solution, see [`run_problem`].
+    fn run(self: Box<Self>, options: Options);
+
+    /// Solves the instance as part of a batch, see [`solve_instance`].
+    fn solve(&self, options: Options) -> Result<String, String>;
+
+    /// Replays a recorded plan on the instance, see [`replay_instance`].
+    fn replay(&self, recorded: &RecordedPlan, options: &Options) -> Result<String, String>;
+}
+
+/// A problem, wrapped to implement [`Solver`].
+struct Registered<P>(P);
+
+impl<P, S, A> Solver for Registered<P>
 where
-    S: State<Action = A> + Clone + Debug + PrettyPrintState + 'static,
-    A: Action<State = S> + Clone + Debug + PrettyPrintAction<S>,
+    P: Problem<State = S>,
+    S: State<Action = A>
+        + Clone
+        + Eq
+        + Send
+        + Sync
+        + Debug
+        + PrettyPrintState
+        + MarkdownState
+        + Serialize
+        + DeserializeOwned,
+    A: Action<State = S>
+        + Clone
+        + Send
+        + Debug
+        + PrettyPrintAction<S>
+        + MarkdownAction<S>
+        + Serialize
+        + DeserializeOwned,
     S::Hash: Eq + Hash,
 {
-    Box::new(move || {
-        if let Some(history) = search(initial_state) {
-            println!("\nSolution:\n");
-            for (action, state) in history {
-                if let Some(action) = action {
-                    println!("  {}", action.pretty_print(&state).yellow());
+    fn run(self: Box<Self>, options: Options) {
+        let options = options.for_problem(&self.0);
+        run_problem(self.0, options);
+    }
+
+    fn solve(&self, options: Options) -> Result<String, String> {
+        solve_instance(&self.0, &options.for_problem(&self.0))
+    }
+
+    fn replay(&self, recorded: &RecordedPlan, options: &Options) -> Result<String, String> {
+        replay_instance(&self.0, recorded, options)
+    }
+}
+
+/// Boxes a problem as a [`Solver`].
+struct Boxed;
+
+impl ProblemVisitor for Boxed {
+    type Output = Box<dyn Solver>;
+
+    fn visit<P, S, A>(self, problem: P) -> Box<dyn Solver>
+    where
+        P: Problem<State = S> + 'static,
+        S: State<Action = A>
+            + Clone
+            + Eq
+            + Send
+            + Sync
+            + Debug
+            + PrettyPrintState
+            + MarkdownState
+            + Serialize
+            + DeserializeOwned,
+        A: Action<State = S>
+            + Clone
+            + Send
+            + Debug
+            + PrettyPrintAction<S>
+            + MarkdownAction<S>
+            + Serialize
+            + DeserializeOwned,
+        S::Hash: Eq + Hash,
+    {
+        Box::new(Registered(problem))
+    }
+}
+
+fn main() {
+    let matches = get_matches();
+
+    // The states explored would scroll the progress readout out of sight.
+    let quiet = matches
+        .subcommand()
+        .is_some_and(|(_, matches)| matches.get_flag("quiet") || progress_requested(matches));
+    if !quiet {
+        set_listener(Some(Box::new(|line| println!("{line}"))));
+    }
+
+    match matches.subcommand() {
+        Some(("batch", matches)) => {
+            let input = matches
+                .get_one::<PathBuf>("input")
+                .expect("value is required");
+            run_batch(input);
+        }
+        Some(("replay", matches)) => {
+            let options = Options::from(matches);
+            let input = matches
+                .get_one::<PathBuf>("input")
+                .expect("value is required");
+            let problem = matches
+                .get_many::<String>("problem")
+                .expect("value is required")
+                .cloned()
+                .collect_vec();
+            run_replay(input, &problem, &options);
+        }
+        Some(("list", _)) => list_problems(),
+        Some((name, matches)) => {
+            let options = Options::from(matches);
+            or_exit(build_problem(name, matches, options.seed)).run(options);
+        }
+        None => unreachable!("A subcommand is required"),
+    }
+}
+
+/// Unwraps the result of building a problem, exiting with the error otherwise.
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        exit(1);
+    })
+}
+
+impl From<&ArgMatches> for Options {
+    fn from(matches: &ArgMatches) -> Self {
+        Self {
+            dot: matches.get_one::<PathBuf>("dot").cloned(),
+            graph_csv: matches.get_one::<PathBuf>("graph-csv").cloned(),
+            mermaid: matches.get_one::<PathBuf>("mermaid").cloned(),
+            trace: matches.get_one::<PathBuf>("trace").cloned(),
+            explain: matches.get_flag("explain"),
+            output: matches.get_one::<PathBuf>("output").cloned(),
+            strategy: match matches.get_one::<String>("strategy").map(String::as_str) {
+                Some("greedy") => Strategy::Greedy,
+                #[cfg(feature = "rayon")]
+                Some("parallel-bfs") => Strategy::ParallelBreadthFirst,
+                Some("random") => Strategy::Random,
+                Some("ida-star") => Strategy::IterativeDeepening,
+                _ => Strategy::BreadthFirst,
+            },
+            seed: matches.get_one::<u64>("seed").cloned().unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos() as u64)
+            }),
+            objective: match matches.get_one::<String>("objective").map(String::as_str) {
+                Some("time") => Objective::Time,
+                Some("moves") => Objective::Moves,
+                // Crossings taking longer with more aboard are meant to be timed.
+                _ if rows_linearly(matches)
+                    && matches.value_source("objective") == Some(ValueSource::DefaultValue) =>
+                {
+                    Objective::Time
+                }
+                _ => Objective::Trips,
+            },
+            count: matches.get_flag("count"),
+            dead_ends: matches.get_flag("dead-ends"),
+            save: matches.get_one::<PathBuf>("save").cloned(),
+            load: matches.get_one::<PathBuf>("load").cloned(),
+            all: matches.get_flag("all"),
+            safest: matches
+                .try_get_one::<bool>("safest")
+                .ok()
+                .flatten()
+                .is_some_and(|safest| *safest),
+            stats: matches.get_flag("stats"),
+            compare: matches.get_flag("compare"),
+            interactive: matches.get_flag("interactive"),
+            combine: matches.get_flag("combine"),
+            anytime: matches.get_flag("anytime"),
+            symmetry: matches.get_flag("symmetry"),
+            memoize: matches.get_flag("memoize"),
+            reverse: matches.get_flag("reverse"),
+            optimal: matches.get_flag("optimal"),
+            format: match matches.get_one::<String>("format").map(String::as_str) {
+                Some("markdown") => Format::Markdown,
+                Some("json") => Format::Json,
+                _ => Format::Text,
+            },
+            max_crossings: matches.get_one::<usize>("max-crossings").cloned(),
+            max_nodes: matches.get_one::<usize>("max-nodes").cloned(),
+            timeout: matches
+                .get_one::<u64>("timeout-ms")
+                .map(|millis| Duration::from_millis(*millis)),
+            style: RenderStyle {
+                right_to_left: matches.get_flag("rtl"),
+                colors: use_color(matches)
+                    && matches.get_one::<String>("format").map(String::as_str) != Some("json"),
+                ..match (matches.get_flag("emoji"), matches.get_flag("unicode")) {
+                    (true, true) => RenderStyle::emoji().with_unicode_river(),
+                    (true, false) => RenderStyle::emoji(),
+                    (false, true) => RenderStyle::unicode(),
+                    (false, false) => RenderStyle::default(),
                 }
+            },
+            quiet: matches.get_flag("quiet"),
+            heartbeat: matches.get_one::<usize>("heartbeat").cloned(),
+            progress: progress_requested(matches)
+                && !matches.get_flag("quiet")
+                && std::io::stderr().is_terminal(),
+        }
+    }
+}
+
+#[cfg(feature = "indicatif")]
+impl ProgressLine {
+    /// The number of states explored between two updates of the readout.
+    const UPDATE_INTERVAL: usize = 256;
+
+    /// Creates a spinner on the standard error, which is removed from the terminal again
+    /// once dropped along with the search configuration holding it.
+    fn new() -> Self {
+        Self(ProgressBar::new_spinner().with_finish(ProgressFinish::AndClear))
+    }
+
+    /// Updates the readout with the number of states explored and waiting in the frontier;
+    /// the spinner limits how often it is actually redrawn.
+    fn update(&self, explored: usize, frontier: usize) {
+        if !explored.is_multiple_of(Self::UPDATE_INTERVAL) {
+            return;
+        }
 
-                println!("  {}", state.pretty_print());
+        self.0.set_message(format!(
+            "explored {} states, {} in the frontier",
+            thousands(explored as u64),
+            thousands(frontier as u64)
+        ));
+        self.0.tick();
+    }
+}
+
+impl Options {
+    /// Determines whether every step of the search is to be recorded,
+    /// to write the trace or to explain the search.
+    fn traced(&self) -> bool {
+        self.trace.is_some() || self.explain
+    }
+
+    /// Resolves the options depending on the problem, i.e. minimizes its
+    /// [natural objective](Problem::natural_objective) if asked to find the optimal plan.
+    fn for_problem<P: Problem>(self, problem: &P) -> Self {
+        if self.optimal {
+            Self {
+                objective: problem.natural_objective(),
+                ..self
             }
         } else {
-            eprintln!("No solution found.");
+            self
+        }
+    }
+
+    /// Builds the configuration of the search for the specified problem.
+    fn search_config<'a, P, S>(&self, problem: &'a P) -> SearchConfig<'a, S>
+    where
+        P: Problem<State = S>,
+    {
+        let mut config = SearchConfig::new()
+            .strategy(self.strategy)
+            .objective(self.objective)
+            .seed(self.seed)
+            .symmetry(self.symmetry)
+            .memoize(self.memoize);
+        if let Some(max_crossings) = self.max_crossings {
+            config = config.max_crossings(max_crossings);
+        }
+        if let Some(max_nodes) = self.max_nodes {
+            config = config.max_nodes(max_nodes);
+        }
+        if let Some(timeout) = self.timeout {
+            config = config.timeout(timeout);
+        }
+        if matches!(
+            self.strategy,
+            Strategy::BreadthFirst | Strategy::Greedy | Strategy::IterativeDeepening
+        ) {
+            config = config.goal(|s| problem.is_goal(s));
+        }
+        if let Some(every) = self.heartbeat {
+            config = config.heartbeat(every).verbose(false);
+        }
+        #[cfg(feature = "indicatif")]
+        if self.progress {
+            let line = ProgressLine::new();
+            config = config.progress(move |explored, frontier| line.update(explored, frontier));
+        }
+        config
+    }
+
+    /// Formats a plan as requested, i.e. as a Markdown table, as JSON or as text in the
+    /// specified style, possibly combining round trips.
+    fn format<S, A>(&self, plan: &Plan<S, A>, style: &RenderStyle) -> String
+    where
+        S: PrettyPrintState + MarkdownState + Clone,
+        A: PrettyPrintAction<S> + MarkdownAction<S> + Action<State = S> + Clone,
+    {
+        match self.format {
+            Format::Text if self.combine => format_plan_combined(plan, style),
+            Format::Text => format_plan_styled(plan, style),
+            Format::Markdown => format_plan_markdown(plan),
+            Format::Json => format!("{}\n", format_plan_json(plan)),
         }
+    }
+}
+
+/// Reads the snapshot of a search saved by [`save_snapshot`].
+fn load_snapshot<S, A>(path: &Path) -> Result<SearchSnapshot<S, A>, String>
+where
+    S: DeserializeOwned,
+    A: DeserializeOwned,
+{
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read search file {}: {e}", path.display()))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to load search file {}: {e}", path.display()))
+}
+
+/// Writes the snapshot of a search to a file as JSON.
+fn save_snapshot<S, A>(path: &Path, snapshot: &SearchSnapshot<S, A>) -> Result<(), String>
+where
+    S: Serialize,
+    A: Serialize,
+{
+    let json =
+        serde_json::to_string(snapshot).map_err(|e| format!("Failed to save the search: {e}"))?;
+    std::fs::write(path, json)
+        .map_err(|e| format!("Failed to write search file {}: {e}", path.display()))
+}
+
+/// Determines whether colored output should be used.
+///
+/// Colors are disabled by the `--no-color` flag, by a non-empty `NO_COLOR`
+/// environment variable (see <https://no-color.org>) or when standard output
+/// is not a terminal.
+fn use_color(matches: &ArgMatches) -> bool {
+    if matches.get_flag("no-color") {
+        return false;
+    }
+
+    if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+
+    std::io::stdout().is_terminal()
+}
+
+/// Searches and prints the solution of the selected problem.
+fn run_problem<P, S, A>(problem: P, options: Options)
+where
+    P: Problem<State = S>,
+    S: State<Action = A>
+        + Clone
+        + Eq
+        + Send
+        + Sync
+        + Debug
+        + PrettyPrintState
+        + MarkdownState
+        + Serialize
+        + DeserializeOwned,
+    A: Action<State = S>
+        + Clone
+        + Send
+        + Debug
+        + PrettyPrintAction<S>
+        + MarkdownAction<S>
+        + Serialize
+        + DeserializeOwned,
+    S::Hash: Eq + Hash,
+{
+    #[cfg(feature = "color")]
+    colored::control::set_override(options.style.colors);
+
+    if !problem.objectives().contains(&options.objective) {
+        eprintln!(
+            "The {} objective is not supported by this problem; use one of: {}.",
+            options.objective,
+            problem.objectives().iter().join(", ")
+        );
+        exit(1);
+    }
+
+    if options.max_crossings.is_some()
+        && (options.compare
+            || options.traced()
+            || options.dead_ends
+            || options.save.is_some()
+            || options.load.is_some()
+            || options.anytime
+            || options.all
+            || options.safest)
+    {
+        eprintln!("Capping the crossings cannot be combined with comparing strategies, tracing, dead ends, saving or loading searches, the anytime search, --all or --safest.");
+        exit(1);
+    }
+
+    if options.compare {
+        if options.strategy != Strategy::BreadthFirst
+            || options.format == Format::Json
+            || options.dot.is_some()
+            || options.graph_csv.is_some()
+            || options.mermaid.is_some()
+            || options.traced()
+            || options.output.is_some()
+            || options.anytime
+            || options.all
+            || options.safest
+            || options.count
+            || options.dead_ends
+            || options.save.is_some()
+            || options.load.is_some()
+            || options.stats
+            || options.reverse
+        {
+            eprintln!("Comparing strategies cannot be combined with selecting a strategy, JSON output, or options producing further output.");
+            exit(1);
+        }
+
+        let comparisons = quietly(|| {
+            compare_strategies(problem.initial(), |s| problem.is_goal(s), options.objective)
+        });
+        match options.format {
+            Format::Markdown => print!(
+                "{}",
+                format_comparison_markdown(&comparisons, options.objective)
+            ),
+            _ => print!("{}", format_comparison(&comparisons, options.objective)),
+        }
+        return;
+    }
+
+    if options.traced()
+        && (options.objective != Objective::Trips
+            || options.strategy != Strategy::BreadthFirst
+            || options.max_nodes.is_some()
+            || options.timeout.is_some()
+            || options.symmetry)
+    {
+        eprintln!("Tracing or explaining the search requires the default search strategy and objective, and no limits or symmetry reduction.");
+        exit(1);
+    }
+
+    if options.dead_ends
+        && (options.objective != Objective::Trips
+            || options.strategy != Strategy::BreadthFirst
+            || options.max_nodes.is_some()
+            || options.timeout.is_some()
+            || options.symmetry
+            || options.traced()
+            || options.anytime)
+    {
+        eprintln!("Listing dead ends requires the default search strategy and objective, and no limits, symmetry reduction, tracing or anytime search.");
+        exit(1);
+    }
+
+    let resumable = options.save.is_some() || options.load.is_some();
+    if resumable
+        && (options.objective != Objective::Trips
+            || options.strategy != Strategy::BreadthFirst
+            || options.symmetry
+            || options.traced()
+            || options.anytime
+            || options.dead_ends)
+    {
+        eprintln!("Saving and loading searches requires the default search strategy and objective, and no symmetry reduction, tracing, dead ends or anytime search.");
+        exit(1);
+    }
+
+    if options.anytime
+        && (options.strategy != Strategy::BreadthFirst
+            || options.traced()
+            || options.timeout.is_some()
+            || options.symmetry)
+    {
+        eprintln!("The anytime search requires the default search strategy, and no tracing, timeout or symmetry reduction.");
+        exit(1);
+    }
+
+    if options.all
+        && (options.objective != Objective::Trips
+            || options.strategy != Strategy::BreadthFirst
+            || options.traced()
+            || options.anytime)
+    {
+        eprintln!("Printing all optimal solutions requires the default search strategy and objective, and no tracing or anytime search.");
+        exit(1);
+    }
+
+    if options.safest
+        && (options.objective != Objective::Trips
+            || options.strategy != Strategy::BreadthFirst
+            || options.traced()
+            || options.anytime
+            || options.all)
+    {
+        eprintln!("Picking the safest optimal solution requires the default search strategy and objective, and no tracing, anytime search or --all.");
+        exit(1);
+    }
+
+    if options.interactive
+        && (options.format != Format::Text || options.output.is_some() || options.quiet)
+    {
+        eprintln!("Stepping through the solution requires the text format, no output file and no --quiet.");
+        exit(1);
+    }
+
+    if options.combine && (options.format != Format::Text || options.interactive) {
+        eprintln!("Combining round trips requires the text format and cannot be combined with stepping through the solution.");
+        exit(1);
+    }
+
+    let config = options.search_config(&problem);
+    if let Err(e) = config.validate() {
+        eprintln!("{e}");
+        exit(1);
+    }
+
+    // Open the output file early so that a wrong path does not waste a search.
+    let mut output = options
+        .output
+        .as_ref()
+        .map(|path| match File::create(path) {
+            Ok(file) => (path, file),
+            Err(e) => {
+                eprintln!("Failed to create solution file {}: {e}", path.display());
+                exit(1);
+            }
+        });
+
+    let mut explored = History::new();
+    let mut events = Vec::new();
+    let mut dead_ends = Vec::new();
+    let (outcome, counters) = counting(|| {
+        if options.anytime {
+            anytime_search(
+                problem.initial(),
+                |s| problem.is_goal(s),
+                |a: &A, _: &S| a.cost(options.objective),
+                options.max_nodes.unwrap_or(usize::MAX),
+                &mut explored,
+                |plan, cost| {
+                    println!("\nImproved solution ({}: {cost}):\n", options.objective);
+                    print!("{}", format_plan_styled(plan, &options.style));
+                },
+            )
+        } else if options.traced() {
+            let (outcome, trace) =
+                solve_with_trace(problem.initial(), |s| problem.is_goal(s), &mut explored);
+            events = trace;
+            outcome
+        } else if resumable {
+            let snapshot = match &options.load {
+                Some(path) => or_exit(load_snapshot(path)),
+                None => SearchSnapshot::new(problem.initial()),
+            };
+
+            // The limits apply to this run only, not to the search as a whole.
+            let max_nodes = options
+                .max_nodes
+                .map(|max| snapshot.states_explored.saturating_add(max));
+            let started = Instant::now();
+            let stop = |explored: usize| {
+                if max_nodes.is_some_and(|max| explored >= max) {
+                    return Some(String::from("node budget exhausted"));
+                }
+                options
+                    .timeout
+                    .filter(|timeout| started.elapsed() >= *timeout)
+                    .map(|timeout| format!("timed out after {} ms", timeout.as_millis()))
+            };
+
+            let goal = |s: &S| problem.is_goal(s);
+            let (outcome, snapshot) = or_exit(resume(snapshot, goal, &mut explored, stop));
+            if let (Some(path), Some(snapshot)) = (&options.save, snapshot) {
+                or_exit(save_snapshot(path, &snapshot));
+                println!(
+                    "\nSaved the search progress to {0}; resume it with --load {0}.",
+                    path.display()
+                );
+            }
+            outcome
+        } else if options.dead_ends {
+            let (outcome, states) =
+                solve_with_dead_ends(problem.initial(), |s| problem.is_goal(s), &mut explored);
+            dead_ends = states;
+            outcome
+        } else {
+            if options.strategy == Strategy::Random && !options.quiet {
+                println!("Using random seed {}.", options.seed);
+            }
+            let outcome = solve_with_config(problem.initial(), &config, &mut explored)
+                .expect("configuration was validated");
+            // Dropping the configuration removes the progress readout, if any.
+            drop(config);
+            outcome
+        }
+    });
+
+    let summary = options.quiet.then(|| Summary::new(&problem, &outcome));
+    let plans = match outcome {
+        SearchOutcome::Solved(_) if options.all => {
+            quietly(|| search_all_optimal(problem.initial()))
+        }
+        SearchOutcome::Solved(_) if options.safest => quietly(|| {
+            search_preferred_optimal(problem.initial(), |a, b| problem.compare_plans(a, b))
+        })
+        .into_iter()
+        .collect(),
+        SearchOutcome::Solved(plan) => vec![plan],
+        SearchOutcome::Unsolvable { .. } | SearchOutcome::Exhausted { .. } if options.quiet => {
+            Vec::new()
+        }
+        SearchOutcome::Unsolvable { states_explored } => {
+            eprintln!(
+                "No solution found: the problem is proven unsolvable after exploring {} state{}.",
+                states_explored,
+                if states_explored == 1 { "" } else { "s" }
+            );
+            Vec::new()
+        }
+        SearchOutcome::Exhausted {
+            reason,
+            states_explored,
+        } => {
+            eprintln!(
+                "No solution found: the search was stopped after exploring {} state{} ({reason}).",
+                states_explored,
+                if states_explored == 1 { "" } else { "s" }
+            );
+            Vec::new()
+        }
+    };
+
+    let plans = if options.reverse {
+        plans
+            .into_iter()
+            .map(|plan| plan.reversed().unwrap_or_else(|| {
+                eprintln!("The solution cannot be reversed, since its actions cannot be undone exactly.");
+                exit(1);
+            }))
+            .collect_vec()
+    } else {
+        plans
+    };
+
+    let title = |index: usize| {
+        let title = if options.all {
+            format!("Solution {} of {}", index + 1, plans.len())
+        } else {
+            String::from("Solution")
+        };
+        if options.reverse {
+            format!("{title}, from the goal back to the start:")
+        } else {
+            format!("{title}:")
+        }
+    };
+
+    match &mut output {
+        Some((path, file)) => {
+            // Files never get colors, and JSON files hold nothing but the solutions.
+            let style = RenderStyle {
+                colors: false,
+                ..options.style.clone()
+            };
+            let solution = if options.format == Format::Json {
+                plans
+                    .iter()
+                    .map(|plan| options.format(plan, &style))
+                    .join("")
+            } else {
+                plans
+                    .iter()
+                    .enumerate()
+                    .map(|(index, plan)| {
+                        format!("{}\n\n{}", title(index), options.format(plan, &style))
+                    })
+                    .join("\n")
+            };
+
+            if let Err(e) = file.write_all(solution.as_bytes()) {
+                eprintln!("Failed to write solution file {}: {e}", path.display());
+                exit(1);
+            }
+        }
+        None if options.quiet => {}
+        None => {
+            for (index, plan) in plans.iter().enumerate() {
+                println!("\n{}\n", title(index));
+                if options.interactive {
+                    let stdout = std::io::stdout();
+                    let redraw = stdout.is_terminal();
+                    let stdin = std::io::stdin().lock();
+                    if let Err(e) = step_through(plan, &options.style, stdin, stdout, redraw) {
+                        eprintln!("Failed to print the solution: {e}");
+                        exit(1);
+                    }
+                } else {
+                    print!("{}", options.format(plan, &options.style));
+                }
+            }
+        }
+    }
+
+    if let Some(summary) = summary {
+        if options.format == Format::Json {
+            let json = serde_json::to_string(&summary).expect("summary is serializable");
+            println!("{json}");
+        } else {
+            println!("{summary}");
+        }
+    }
+
+    if options.stats {
+        for (index, plan) in plans.iter().enumerate() {
+            let mut statistics = Statistics::new(&problem, plan, explored.len());
+            statistics.analysis = Some(analyze(&explored, &counters));
+            if !options.strategy.is_optimal() {
+                statistics.gap = Some(optimality_gap(
+                    plan,
+                    |s| problem.is_goal(s),
+                    options.objective,
+                ));
+            }
+
+            // JSON statistics go on a line of their own, one per solution.
+            if options.format == Format::Json {
+                let json = serde_json::to_string(&statistics).expect("statistics are serializable");
+                println!("{json}");
+            } else if options.all {
+                println!("\nStatistics of solution {}:\n", index + 1);
+                print!("{statistics}");
+            } else {
+                println!("\nStatistics:\n");
+                print!("{statistics}");
+            }
+        }
+    }
+
+    if options.dead_ends {
+        if dead_ends.is_empty() {
+            println!("\nNone of the explored states is a dead end.");
+        } else {
+            println!("\nDead ends, i.e. explored states without any applicable actions:\n");
+            for state in &dead_ends {
+                println!("  {}", state.pretty_print_styled(&options.style));
+            }
+        }
+    }
+
+    if options.explain && !plans.is_empty() {
+        if let Some(explanation) = format_explanation(&explored, &events, &options.style) {
+            println!("\nWhy the search passed over the other branches:\n");
+            print!("{explanation}");
+        }
+    }
+
+    if options.count {
+        let solutions = quietly(|| count_shortest_solutions(problem.initial()));
+        println!(
+            "\nFound {} distinct solution{} with the minimal number of trips.",
+            solutions,
+            if solutions == 1 { "" } else { "s" }
+        );
+    }
+
+    if let Some(path) = options.trace {
+        if let Err(e) = std::fs::write(&path, trace::to_json(&events)) {
+            eprintln!("Failed to write trace file {}: {e}", path.display());
+            exit(1);
+        }
+    }
+
+    if let Some(path) = options.dot {
+        if let Err(e) = std::fs::write(&path, explored.to_dot()) {
+            eprintln!("Failed to write DOT file {}: {e}", path.display());
+            exit(1);
+        }
+    }
+
+    if let Some(path) = options.graph_csv {
+        if let Err(e) = std::fs::write(&path, explored.to_csv()) {
+            eprintln!("Failed to write CSV file {}: {e}", path.display());
+            exit(1);
+        }
+    }
+
+    if let Some(path) = options.mermaid {
+        if let Err(e) = std::fs::write(&path, explored.to_mermaid()) {
+            eprintln!("Failed to write Mermaid file {}: {e}", path.display());
+            exit(1);
+        }
+    }
+}
+
+/// Solves every instance listed in the specified file, printing one line per instance.
+///
+/// Lines that cannot be parsed or solved are reported without stopping the run;
+/// the process fails once all lines are processed if there were any.
+fn run_batch(input: &Path) {
+    let contents = match std::fs::read_to_string(input) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Failed to read batch file {}: {e}", input.display());
+            exit(1);
+        }
+    };
+
+    let mut failures = 0;
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match solve_line(line) {
+            Ok(result) => println!("{line}: {result}"),
+            Err(e) => {
+                eprintln!("Line {}: {e}", number + 1);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        exit(1);
+    }
+}
+
+/// Parses a line of a batch file as the arguments of a subcommand and solves the instance.
+fn solve_line(line: &str) -> Result<String, String> {
+    let matches = parse_arguments(line.split_whitespace())?;
+    match matches.subcommand() {
+        Some(("batch", _)) => Err(String::from("Batch files cannot be nested.")),
+        Some(("replay", _)) => Err(String::from("Plans cannot be replayed in batch files.")),
+        Some(("list", _)) => Err(String::from("Problems cannot be listed in batch files.")),
+        Some((name, matches)) => {
+            let options = Options::from(matches);
+            build_problem(name, matches, options.seed)?.solve(options)
+        }
+        None => unreachable!("A subcommand is required"),
+    }
+}
+
+/// Parses the arguments of a subcommand, describing any error in a single line.
+fn parse_arguments<'a>(args: impl IntoIterator<Item = &'a str>) -> Result<ArgMatches, String> {
+    let args = std::iter::once("toy-planning").chain(args);
+    command()
+        .try_get_matches_from(args)
+        .map_err(|e| describe_error(&e))
+}
+
+/// Solves a single instance of a batch, describing the outcome in a few words.
+///
+/// Only the options affecting the search itself are honored; anything producing
+/// additional output is rejected.
+fn solve_instance<P, S, A>(problem: &P, options: &Options) -> Result<String, String>
+where
+    P: Problem<State = S>,
+    S: State<Action = A> + Clone + Eq + Send + Sync + Debug,
+    A: Action<State = S> + Clone + Send + Debug,
+    S::Hash: Eq + Hash,
+{
+    if !problem.objectives().contains(&options.objective) {
+        return Err(format!(
+            "The {} objective is not supported by this problem; use one of: {}.",
+            options.objective,
+            problem.objectives().iter().join(", ")
+        ));
+    }
+
+    if options.dot.is_some()
+        || options.graph_csv.is_some()
+        || options.mermaid.is_some()
+        || options.traced()
+        || options.output.is_some()
+        || options.count
+        || options.dead_ends
+        || options.save.is_some()
+        || options.load.is_some()
+        || options.anytime
+        || options.all
+        || options.stats
+        || options.compare
+        || options.interactive
+        || options.progress
+        || options.heartbeat.is_some()
+    {
+        return Err(String::from(
+            "Batch instances cannot write files, show progress, print statistics or several solutions, explain the search, count solutions, list dead ends, save or load searches, compare strategies, step through solutions or use the anytime search.",
+        ));
+    }
+
+    let config = options.search_config(problem).verbose(false);
+    config.validate()?;
+
+    let outcome = solve_with_config(problem.initial(), &config, &mut History::new())?;
+    Ok(match outcome {
+        SearchOutcome::Solved(plan) => {
+            let cost: u32 = plan
+                .steps()
+                .iter()
+                .map(|(action, _)| action.cost(options.objective))
+                .sum();
+            format!(
+                "{} step{} ({}: {cost})",
+                plan.len(),
+                if plan.len() == 1 { "" } else { "s" },
+                options.objective
+            )
+        }
+        SearchOutcome::Unsolvable { .. } => String::from("unsolvable"),
+        SearchOutcome::Exhausted { reason, .. } => format!("no solution found ({reason})"),
     })
 }
 
+/// Replays the plan stored in the specified JSON file on the problem described by the
+/// arguments of its subcommand, printing the plan if it is valid.
+fn run_replay(input: &Path, problem: &[String], options: &Options) {
+    let recorded = match std::fs::read_to_string(input) {
+        Ok(contents) => or_exit(parse_plan_json(&contents)),
+        Err(e) => {
+            eprintln!("Failed to read plan file {}: {e}", input.display());
+            exit(1);
+        }
+    };
+
+    let matches = or_exit(parse_arguments(problem.iter().map(String::as_str)));
+    let replay = match matches.subcommand() {
+        Some(("batch" | "replay" | "list", _)) => {
+            Err(String::from("Plans can only be replayed on a problem."))
+        }
+        Some((name, matches)) => build_problem(name, matches, options.seed)
+            .and_then(|problem| problem.replay(&recorded, options)),
+        None => unreachable!("A subcommand is required"),
+    };
+
+    print!("{}", or_exit(replay));
+}
+
+/// Replays a recorded plan on a single problem instance, formatting it as requested if valid.
+fn replay_instance<P, S, A>(
+    problem: &P,
+    recorded: &RecordedPlan,
+    options: &Options,
+) -> Result<String, String>
+where
+    P: Problem<State = S>,
+    S: State<Action = A> + Clone + PrettyPrintState + MarkdownState,
+    A: Action<State = S> + Clone + PrettyPrintAction<S> + MarkdownAction<S>,
+{
+    if options.combine && options.format != Format::Text {
+        return Err(String::from(
+            "Combining round trips requires the text format.",
+        ));
+    }
+
+    let plan = validate_plan(problem.initial(), recorded, |s| problem.is_goal(s))
+        .map_err(|e| format!("The plan is invalid. {e}."))?;
+
+    Ok(format!(
+        "The plan is valid, reaching the goal in {} step{}:\n\n{}",
+        plan.len(),
+        if plan.len() == 1 { "" } else { "s" },
+        options.format(&plan, &options.style)
+    ))
+}
+
 /// Obtains the matches for the command-line arguments.
 fn get_matches() -> ArgMatches {
+    command().get_matches()
+}
+
+/// Determines whether the live progress readout was requested, which is only available with
+/// the `indicatif` feature.
+fn progress_requested(matches: &ArgMatches) -> bool {
+    matches
+        .try_get_one::<bool>("progress")
+        .ok()
+        .flatten()
+        .is_some_and(|progress| *progress)
+}
+
+/// Builds the command-line interface.
+fn command() -> Command {
     let command = Command::new("toy-planning")
         .subcommand_required(true)
+        .arg(
+            Arg::new("dot")
+                .long("dot")
+                .help("Writes the explored search tree to a Graphviz DOT file")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("graph-csv")
+                .long("graph-csv")
+                .help("Writes the explored search tree to a CSV file, one row per explored state")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("mermaid")
+                .long("mermaid")
+                .help("Writes the explored search tree to a Mermaid flowchart, e.g. for embedding in Markdown")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .help("Writes the solution to a file instead of the standard output")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .help("Writes every step of the exploration to a JSON file")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("strategy")
+                .long("strategy")
+                .help("The search strategy to use")
+                .default_value("bfs")
+                .value_parser(STRATEGIES)
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("The format to render the solution in")
+                .default_value("text")
+                .value_parser(["text", "markdown", "json"])
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("objective")
+                .long("objective")
+                .help("The objective to minimize; anything but trips uses uniform-cost search")
+                .default_value("trips")
+                .value_parser(["trips", "time", "moves"])
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("max-crossings")
+                .long("max-crossings")
+                .help("Finds the solution with the fewest crossings, reporting the problem as unsolvable if it takes more than K")
+                .value_name("K")
+                .value_parser(clap::value_parser!(usize))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("max-nodes")
+                .long("max-nodes")
+                .help("Gives up after exploring this many states")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("timeout-ms")
+                .long("timeout-ms")
+                .help("Gives up after searching for this many milliseconds")
+                .value_name("MILLIS")
+                .value_parser(clap::value_parser!(u64))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("The seed for the random strategy; a time-based seed is used if omitted")
+                .value_name("SEED")
+                .value_parser(clap::value_parser!(u64))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .help("Explains why the search passed over the branches off the path to the solution")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("dead-ends")
+                .long("dead-ends")
+                .help("Lists the explored states without any applicable actions, which often reveal overly restrictive rules")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("save")
+                .long("save")
+                .help("Saves the progress of the search to this file if --max-nodes or --timeout-ms stops it")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("load")
+                .long("load")
+                .help("Resumes the search saved to this file by --save; pass the same problem arguments")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("count")
+                .long("count")
+                .help("Counts the distinct solutions with the minimal number of trips")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .help("Prints statistics about the solution, such as its cost by every objective")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("compare")
+                .long("compare")
+                .help("Compares breadth-first, depth-first, greedy and A* search on the problem")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .help("Prints every distinct solution with the minimal number of trips")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("anytime")
+                .long("anytime")
+                .help("Prints every improving solution while searching for the best one")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("symmetry")
+                .long("symmetry")
+                .help("Collapses mirror-image states, if the problem is symmetric")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("memoize")
+                .long("memoize")
+                .help("Caches the actions of every state explored, speeding up the ida-star strategy at the cost of memory")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("optimal")
+                .long("optimal")
+                .help("Finds the plan of minimal cost by the problem's natural objective, e.g. the time for Bridge and Torch")
+                .conflicts_with_all(["objective", "strategy"])
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("reverse")
+                .long("reverse")
+                .help("Prints the solution from the goal back to the start")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("interactive")
+                .long("interactive")
+                .help("Reveals the solution one step at a time, whenever Enter is pressed")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("combine")
+                .long("combine")
+                .help("Shows every crossing followed by a return as a single round trip")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("emoji")
+                .long("emoji")
+                .help("Renders the river scene using emoji")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("unicode")
+                .long("unicode")
+                .help("Renders the river using box-drawing and water glyphs")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("rtl")
+                .long("rtl")
+                .help("Mirrors the river scene, showing the starting bank on the right")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Prints a one-line summary of the outcome instead of the solution and every state explored")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("heartbeat")
+                .long("heartbeat")
+                .help("Prints a summary line every this many states explored, instead of every state explored")
+                .value_name("N")
+                .value_parser(parse_nonzero_usize)
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .help("Disables colored output")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .subcommands(registry::problems().iter().map(ProblemInfo::command))
         .subcommands([
-            Command::new("humans-and-zombies")
-                .about("The Humans and Zombies problem")
-                .arg(
-                    Arg::new("humans")
-                        .short('H')
-                        .long("humans")
-                        .help("The number of humans on the river bank")
-                        .default_value("3")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
-                )
-                .arg(
-                    Arg::new("zombies")
-                        .short('Z')
-                        .long("zombies")
-                        .help("The number of zombies on the river bank")
-                        .default_value("3")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
-                )
-                .arg(
-                    Arg::new("boat")
-                        .short('B')
-                        .long("boat")
-                        .help("The capacity of the boat")
-                        .default_value("2")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
-                ),
-            Command::new("bridge-and-torch")
-                .about("The Bridge and Torch problem")
-                .arg(
-                    Arg::new("bridge")
-                        .short('B')
-                        .long("bridge")
-                        .help("The capacity of the bridge")
-                        .default_value("2")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
+            Command::new("list").about("Lists the problems that can be solved"),
+            Command::new("batch")
+                .about("Solves every instance listed in a file, one per line")
+                .long_about(
+                    "Solves every instance listed in a file, one per line. Every line holds the \
+                     arguments for one of the other subcommands, e.g. \
+                     `humans-and-zombies -H 5 -Z 5 -B 3`; empty lines and lines starting with \
+                     `#` are skipped.",
                 )
                 .arg(
-                    Arg::new("torch")
-                        .short('T')
-                        .long("torch")
-                        .help("The capacity of the torch, i.e. how long it will burn")
-                        .default_value("15")
-                        .value_name("MINUTES")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .help("The file listing the instances to solve")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
                         .num_args(1),
-                )
-                .arg(
-                    Arg::new("people")
-                        .short('P')
-                        .long("person")
-                        .help("The walking time of a person to add to the problem")
-                        .value_name("MINUTES")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .action(ArgAction::Append)
-                        .num_args(1..),
                 ),
-            Command::new("wolf-goat-cabbage")
-                .about("The Wolves, Goats and Cabbages problem")
-                .arg(
-                    Arg::new("farmers")
-                        .short('F')
-                        .long("farmers")
-                        .help("The number of farmers on the river bank")
-                        .default_value("1")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
-                )
-                .arg(
-                    Arg::new("wolves")
-                        .short('W')
-                        .long("wolves")
-                        .help("The number of wolves on the river bank")
-                        .default_value("1")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
-                )
-                .arg(
-                    Arg::new("goats")
-                        .short('G')
-                        .long("goats")
-                        .help("The number of goats on the river bank")
-                        .default_value("1")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
+            Command::new("replay")
+                .about("Checks a plan stored as JSON and prints it if valid")
+                .long_about(
+                    "Checks a plan stored as JSON, as written by `--format json`, by re-applying \
+                     every action from the initial state of the problem given by the remaining \
+                     arguments, e.g. `replay --input plan.json humans-and-zombies -H 5 -Z 5 -B 3`. \
+                     The plan is printed if valid; otherwise, the first illegal step is reported.",
                 )
                 .arg(
-                    Arg::new("cabbages")
-                        .short('C')
-                        .long("cabbages")
-                        .help("The number of cabbages on the river bank")
-                        .default_value("1")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .help("The file holding the plan to check")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .required(true)
                         .num_args(1),
                 )
                 .arg(
-                    Arg::new("boat")
-                        .short('B')
-                        .long("boat")
-                        .help("The capacity of the boat")
-                        .default_value("2")
-                        .value_name("COUNT")
-                        .value_parser(parse_nonzero_u8)
-                        .allow_negative_numbers(false)
-                        .num_args(1),
+                    Arg::new("problem")
+                        .help("The subcommand and arguments describing the problem")
+                        .value_name("PROBLEM")
+                        .required(true)
+                        .num_args(1..)
+                        .trailing_var_arg(true)
+                        .allow_hyphen_values(true),
                 ),
         ]);
-    command.get_matches()
+
+    #[cfg(feature = "indicatif")]
+    let command = command.arg(
+        Arg::new("progress")
+            .long("progress")
+            .help("Shows the number of explored states and the size of the frontier while searching, instead of every state explored")
+            .action(ArgAction::SetTrue)
+            .global(true),
+    );
+
+    command
 }
 
-/// Value parser for [`get_matches`] that attempts to read an [`u8`] value.
-fn parse_nonzero_u8(value: &str) -> Result<u8, String> {
+/// Value parser for [`get_matches`] that attempts to read a positive [`usize`] value.
+fn parse_nonzero_usize(value: &str) -> Result<usize, String> {
     let value = value.parse().map_err(|e| format!("{e:?}"))?;
     if value == 0 {
         Err(String::from("value must be positive"))
@@ -196,85 +1487,3 @@ fn parse_nonzero_u8(value: &str) -> Result<u8, String> {
         Ok(value)
     }
 }
-
-/// Builds the initial state for the Humans and Zombies problem.
-fn humans_and_zombies(matches: &ArgMatches) -> humans_and_zombies::WorldState {
-    use humans_and_zombies::{Boat, RiverBank, RiverBankState, WorldState};
-
-    let humans = matches
-        .get_one::<u8>("humans")
-        .cloned()
-        .expect("value is required");
-    let zombies = matches
-        .get_one::<u8>("zombies")
-        .cloned()
-        .expect("value is required");
-    let boat = matches
-        .get_one::<u8>("boat")
-        .cloned()
-        .expect("value is required");
-
-    let left = RiverBankState::new(humans, zombies);
-    let right = RiverBankState::new(0, 0);
-    let boat = Boat::new(boat, RiverBank::Left);
-    WorldState::new(left, right, boat)
-}
-
-/// Builds the initial state for the Bridge and Torch problem.
-fn bridge_and_torch(matches: &ArgMatches) -> bridge_and_torch::WorldState {
-    use bridge_and_torch::{Person, RiverSide, RiverSideState, Torch, WorldState};
-
-    let bridge = matches
-        .get_one::<u8>("bridge")
-        .cloned()
-        .expect("value is required");
-    let torch = matches
-        .get_one::<u8>("torch")
-        .cloned()
-        .expect("value is required");
-    let people = matches.get_many::<u8>("people").map_or(
-        vec![
-            Person::new(1),
-            Person::new(2),
-            Person::new(5),
-            Person::new(8),
-        ],
-        |values| values.into_iter().cloned().map(Person::new).collect_vec(),
-    );
-
-    let left = RiverSideState::new(people);
-    let right = RiverSideState::new(vec![]);
-    let torch = Torch::new(torch, RiverSide::Left);
-    WorldState::new(left, right, torch, 0, bridge)
-}
-
-/// Builds the initial state for the Humans and Zombies problem.
-fn wolf_goat_cabbage(matches: &ArgMatches) -> wolf_goat_cabbage::WorldState {
-    use wolf_goat_cabbage::{Boat, RiverBank, RiverBankState, WorldState};
-
-    let farmers = matches
-        .get_one::<u8>("farmers")
-        .cloned()
-        .expect("value is required");
-    let wolves = matches
-        .get_one::<u8>("wolves")
-        .cloned()
-        .expect("value is required");
-    let goats = matches
-        .get_one::<u8>("goats")
-        .cloned()
-        .expect("value is required");
-    let cabbages = matches
-        .get_one::<u8>("cabbages")
-        .cloned()
-        .expect("value is required");
-    let boat = matches
-        .get_one::<u8>("boat")
-        .cloned()
-        .expect("value is required");
-
-    let left = RiverBankState::new(farmers, wolves, goats, cabbages);
-    let right = RiverBankState::new(0, 0, 0, 0);
-    let boat = Boat::new(boat, RiverBank::Left);
-    WorldState::new(0, left, right, boat)
-}
@@ -0,0 +1,95 @@
+use crate::plan::Plan;
+use crate::pretty_print::PrettyPrintAction;
+use std::fmt::Write;
+
+/// A state that can be rendered as a row of a Markdown table.
+pub trait MarkdownState {
+    /// Gets the headings of the columns describing a state.
+    ///
+    /// The default implementation describes the two river banks.
+    fn headings(&self) -> Vec<String> {
+        vec![String::from("Left bank"), String::from("Right bank")]
+    }
+
+    /// Gets the cells describing this state, one for every heading.
+    fn cells(&self) -> Vec<String>;
+}
+
+/// An action that can be rendered as a cell of a Markdown table.
+pub trait MarkdownAction<S>: PrettyPrintAction<S> {
+    /// Describes the action, given the state after the action was applied.
+    ///
+    /// The default implementation uses the pretty-printed action without the
+    /// surrounding whitespace used to align it with the states.
+    fn cell(&self, state: &S) -> String {
+        self.pretty_print(state).trim().into()
+    }
+}
+
+/// Formats a plan as a GitHub-flavored Markdown table, with one row per state
+/// along with the step number and the action that lead to it.
+pub fn format_plan_markdown<S, A>(plan: &Plan<S, A>) -> String
+where
+    S: MarkdownState,
+    A: MarkdownAction<S>,
+{
+    let mut headings = vec![String::from("Step"), String::from("Action")];
+    headings.extend(plan.initial().headings());
+
+    let mut buffer = String::new();
+    write_row(&mut buffer, &headings);
+    write_row(&mut buffer, &vec![String::from("---"); headings.len()]);
+
+    let mut row = vec![String::from("0"), String::from("—")];
+    row.extend(plan.initial().cells());
+    write_row(&mut buffer, &row);
+
+    for (step, (action, state)) in plan.steps().iter().enumerate() {
+        let mut row = vec![(step + 1).to_string(), action.cell(state)];
+        row.extend(state.cells());
+        write_row(&mut buffer, &row);
+    }
+
+    buffer
+}
+
+/// Writes a table row, escaping the column separator as well as angle brackets,
+/// which would otherwise turn e.g. `<Alice:5>` into a link.
+pub(crate) fn write_row(buffer: &mut String, cells: &[String]) {
+    for cell in cells {
+        let cell = cell.replace('|', "\\|").replace('<', "\\<");
+        write!(buffer, "| {cell} ").expect("writing to a string cannot fail");
+    }
+    buffer.push_str("|\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn plan_is_rendered_as_table() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let table = format_plan_markdown(&plan);
+        let lines = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), plan.len() + 3);
+        assert_eq!(lines[0], "| Step | Action | Left bank | Right bank |");
+        assert_eq!(lines[1], "| --- | --- | --- | --- |");
+        assert_eq!(lines[2], "| 0 | — | 3 humans and 3 zombies | nobody |");
+        assert_eq!(
+            lines.last(),
+            Some(&"| 11 | → 2 zombies | nobody | 3 humans and 3 zombies |")
+        );
+    }
+
+    #[test]
+    fn separators_in_cells_are_escaped() {
+        let mut buffer = String::new();
+        write_row(&mut buffer, &[String::from("a|b"), String::from("<c>")]);
+        assert_eq!(buffer, "| a\\|b | \\<c> |\n");
+    }
+}
@@ -0,0 +1,119 @@
+//! Memoization of the actions applicable in a state, for searches expanding the same states
+//! over and over again.
+
+use crate::search::{Actions, State};
+use crate::visited::StateMap;
+use std::hash::Hash;
+
+/// A state along with the actions applicable in it.
+type Entry<S> = (S, Actions<<S as State>::Action>);
+
+/// A cache of the actions applicable in the states expanded during a search.
+///
+/// The actions only depend on the state they are applicable in, so they never have to be
+/// invalidated; like [`Visited`](crate::visited::Visited), entries are bucketed by
+/// [`State::unique_hash`] and compared for equality, so colliding hashes never mix up the actions
+/// of distinct states. Every state expanded is kept in memory, in exchange for calling
+/// [`State::get_actions`] only once per state.
+pub struct ActionCache<S: State> {
+    buckets: StateMap<S::Hash, Vec<Entry<S>>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<S> ActionCache<S>
+where
+    S: State + Clone + Eq,
+    S::Action: Clone,
+    S::Hash: Eq + Hash,
+{
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self {
+            buckets: StateMap::default(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Gets the actions applicable in the state, expanding it only if it was not before.
+    pub fn get_actions(&mut self, state: &S) -> Actions<S::Action> {
+        let bucket = self.buckets.entry(state.unique_hash()).or_default();
+        if let Some((_, actions)) = bucket.iter().find(|(s, _)| s == state) {
+            self.hits += 1;
+            return actions.clone();
+        }
+
+        self.misses += 1;
+        let actions = state.get_actions();
+        bucket.push((state.clone(), actions.clone()));
+        actions
+    }
+
+    /// Gets the number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Gets the number of states expanded, i.e. the number of states in the cache.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+impl<S> Default for ActionCache<S>
+where
+    S: State + Clone + Eq,
+    S::Action: Clone,
+    S::Hash: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::History;
+    use crate::problems::hanoi::{Hanoi, WorldState};
+    use crate::problems::Problem;
+    use crate::search::{ida_star, ida_star_memoized};
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn memoized_ida_star_finds_the_same_plan() {
+        let problem = Hanoi::new(4);
+        let cost = |_: &_, _: &WorldState| 1;
+
+        let expected = quietly(|| {
+            ida_star(
+                problem.initial(),
+                WorldState::is_goal,
+                cost,
+                WorldState::heuristic,
+                &mut History::new(),
+            )
+        });
+        let mut cache = ActionCache::new();
+        let outcome = quietly(|| {
+            ida_star_memoized(
+                problem.initial(),
+                WorldState::is_goal,
+                cost,
+                WorldState::heuristic,
+                &mut cache,
+                &mut History::new(),
+            )
+        });
+
+        let states = |outcome: crate::search::SearchOutcome<_, _>| {
+            let plan = outcome.into_plan().expect("problem is solvable");
+            plan.into_iter().map(|(_, s)| s).collect::<Vec<_>>()
+        };
+        assert_eq!(states(outcome), states(expected));
+        assert!(cache.hits() > cache.misses());
+        // Four disks can be stacked onto three pegs in 3^4 ways.
+        assert!(cache.misses() <= 81);
+    }
+}
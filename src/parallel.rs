@@ -0,0 +1,102 @@
+use crate::history::{History, Lineage};
+use crate::search::{Action, SearchOutcome, State};
+use crate::visited::Visited;
+use rayon::prelude::*;
+use std::rc::Rc;
+
+/// Searches the state space for a plan using breadth-first search, expanding the
+/// states of each depth level in parallel using rayon, and recording every explored state
+/// in the specified [`History`].
+///
+/// Only the expansion of states (i.e. obtaining and applying their actions) happens in parallel.
+/// The resulting states are deduplicated sequentially in the order of the level they were
+/// generated from, so the returned plan is deterministic and identical to the one found by
+/// the sequential breadth-first search.
+pub fn parallel_search<S, A>(initial_state: S, history: &mut History<S, A>) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Send + Sync,
+    A: Action<State = S> + Clone + Send,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let mut observed = Visited::new();
+    let root = history.create_root(initial_state);
+    observed.insert_entry(Rc::clone(&root));
+    let mut level = vec![root];
+
+    let mut states_explored = 0;
+    while !level.is_empty() {
+        // Goals are tested in the same order the sequential search would pop them.
+        for lineage in &level {
+            states_explored += 1;
+            if lineage.state.is_goal() {
+                history.set_goal(lineage);
+                return SearchOutcome::Solved(history.backtrack(lineage).collect());
+            }
+        }
+
+        let expansions = expand_level(&level);
+
+        let mut next_level = Vec::new();
+        for (parent, expansions) in level.iter().zip(expansions) {
+            for (action, state) in expansions {
+                if !observed.revisit(&state) {
+                    let child = history.create_entry(action, state, parent);
+                    observed.insert_entry(Rc::clone(&child));
+                    next_level.push(child);
+                }
+            }
+        }
+
+        level = next_level;
+    }
+
+    SearchOutcome::Unsolvable { states_explored }
+}
+
+/// Expands every lineage of a level into its successor states on the rayon thread pool.
+/// The expansions are returned in the order of the lineages.
+fn expand_level<S, A>(level: &[Rc<Lineage<S, A>>]) -> Vec<Vec<(A, S)>>
+where
+    S: State<Action = A> + Send + Sync,
+    A: Action<State = S> + Send,
+{
+    // The lineages are reference counted and stay on this thread; only their states are shared.
+    let states: Vec<&S> = level.iter().map(|lineage| &lineage.state).collect();
+    states
+        .par_iter()
+        .map(|state| {
+            state
+                .get_actions()
+                .into_iter()
+                .map(|action| {
+                    let new_state = action.apply(state);
+                    (action, new_state)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::HumansAndZombies;
+    use crate::problems::Problem;
+    use crate::search::solve_with_history;
+
+    #[test]
+    fn parallel_search_matches_sequential_search() {
+        let initial = HumansAndZombies::new(20, 20, 4).initial();
+
+        let sequential = solve_with_history(initial.clone(), &mut History::new())
+            .into_plan()
+            .expect("problem is solvable");
+        let parallel = parallel_search(initial, &mut History::new())
+            .into_plan()
+            .expect("problem is solvable");
+
+        let sequential = sequential.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+        let parallel = parallel.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::search::Action;
+use std::iter::{once, Chain, Map, Once};
+use std::vec;
+
+/// A plan, i.e. a sequence of actions leading from an initial state to a goal state.
+#[derive(Debug, Clone)]
+pub struct Plan<S, A> {
+    /// The initial state.
+    initial: S,
+    /// The actions taken, each with the state it lead to.
+    steps: Vec<(A, S)>,
+}
+
+impl<S, A> Plan<S, A> {
+    /// Creates a new, empty plan starting at the specified state.
+    pub const fn new(initial: S) -> Self {
+        Self {
+            initial,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Appends an action and the state it leads to.
+    pub fn push(&mut self, action: A, state: S) {
+        self.steps.push((action, state));
+    }
+
+    /// Gets the initial state.
+    pub fn initial(&self) -> &S {
+        &self.initial
+    }
+
+    /// Gets the final state, i.e. the goal state.
+    pub fn goal(&self) -> &S {
+        self.steps.last().map_or(&self.initial, |(_, state)| state)
+    }
+
+    /// Gets the actions taken, each with the state it lead to.
+    pub fn steps(&self) -> &[(A, S)] {
+        &self.steps
+    }
+
+    /// Gets the number of actions in the plan.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Determines whether this plan has no actions, i.e. the initial state is the goal.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<S, A> Plan<S, A>
+where
+    S: Clone + Eq,
+    A: Action<State = S>,
+{
+    /// Reverses the plan using the [`Action::inverse`] of every action, leading from the goal
+    /// state back to the initial state through the same states.
+    ///
+    /// Returns `None` if an action cannot be undone exactly, i.e. its inverse is not applicable
+    /// or does not lead back to the state the action was applied in.
+    pub fn reversed(&self) -> Option<Self> {
+        let mut reversed = Self::new(self.goal().clone());
+        for (index, (action, state)) in self.steps.iter().enumerate().rev() {
+            let previous = match index {
+                0 => &self.initial,
+                _ => &self.steps[index - 1].1,
+            };
+
+            let inverse = action.inverse();
+            if !inverse.is_applicable(state) || inverse.apply(state) != *previous {
+                return None;
+            }
+
+            reversed.push(inverse, previous.clone());
+        }
+        Some(reversed)
+    }
+}
+
+/// A step of a [compressed](Plan::compress) plan.
+#[derive(Debug, Clone)]
+pub enum CompressedStep<S, A> {
+    /// A single action, along with the state it leads to.
+    Single((A, S)),
+    /// A crossing followed by the return from the other side, each along with the state
+    /// it leads to.
+    RoundTrip([(A, S); 2]),
+}
+
+impl<S, A> CompressedStep<S, A> {
+    /// Gets the actions of this step, each with the state it leads to.
+    pub fn steps(&self) -> &[(A, S)] {
+        match self {
+            CompressedStep::Single(step) => std::slice::from_ref(step),
+            CompressedStep::RoundTrip(steps) => steps,
+        }
+    }
+
+    /// Gets the state this step leads to.
+    pub fn state(&self) -> &S {
+        let (_, state) = self.steps().last().expect("every step has an action");
+        state
+    }
+}
+
+impl<S, A> Plan<S, A>
+where
+    S: Clone,
+    A: Action<State = S> + Clone,
+{
+    /// Groups every crossing that is immediately followed by a return, i.e. an action for
+    /// which [`Action::is_return`] holds, into a single round trip. All other actions,
+    /// e.g. the final crossing or every action of a problem without crossings, are kept
+    /// as they are.
+    ///
+    /// The actions of the compressed steps, taken in order, are exactly those of the plan.
+    pub fn compress(&self) -> Vec<CompressedStep<S, A>> {
+        let mut compressed = Vec::new();
+        let mut previous = &self.initial;
+        let mut index = 0;
+        while let Some(step @ (action, state)) = self.steps.get(index) {
+            match self.steps.get(index + 1) {
+                Some(back @ (returning, next))
+                    if !action.is_return(previous) && returning.is_return(state) =>
+                {
+                    compressed.push(CompressedStep::RoundTrip([step.clone(), back.clone()]));
+                    previous = next;
+                    index += 2;
+                }
+                _ => {
+                    compressed.push(CompressedStep::Single(step.clone()));
+                    previous = state;
+                    index += 1;
+                }
+            }
+        }
+        compressed
+    }
+}
+
+impl<S, A> FromIterator<(Option<A>, S)> for Plan<S, A> {
+    /// Builds a plan from a path as produced by [`History::backtrack`](crate::history::History::backtrack).
+    /// The first entry is the initial state and must be the only one without an action.
+    fn from_iter<T: IntoIterator<Item = (Option<A>, S)>>(iter: T) -> Self {
+        let mut path = iter.into_iter();
+        let (_, initial) = path
+            .next()
+            .expect("a path contains at least the initial state");
+
+        let mut plan = Self::new(initial);
+        for (action, state) in path {
+            plan.push(action.expect("only the initial state has no action"), state);
+        }
+        plan
+    }
+}
+
+impl<S, A> IntoIterator for Plan<S, A> {
+    type Item = (Option<A>, S);
+    type IntoIter =
+        Chain<Once<(Option<A>, S)>, Map<vec::IntoIter<(A, S)>, fn((A, S)) -> (Option<A>, S)>>;
+
+    /// Iterates the plan as a path, starting with the initial state (which has no action).
+    fn into_iter(self) -> Self::IntoIter {
+        let steps: fn((A, S)) -> (Option<A>, S) = |(action, state)| (Some(action), state);
+        once((None, self.initial)).chain(self.steps.into_iter().map(steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::hanoi::Hanoi;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::problems::Problem;
+    use crate::search::{search, State};
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn reversed_plan_leads_back_to_initial_state() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let reversed = plan.reversed().expect("actions can be undone");
+
+        assert_eq!(reversed.len(), plan.len());
+        assert_eq!(reversed.initial(), plan.goal());
+        assert_eq!(reversed.goal(), plan.initial());
+        assert!(reversed.initial().is_goal());
+
+        let mut state = reversed.initial().clone();
+        for (action, next) in reversed.steps() {
+            assert!(action.is_applicable(&state));
+            state = action.apply(&state);
+            assert_eq!(&state, next);
+        }
+    }
+
+    #[test]
+    fn compressed_plan_expands_to_the_original() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let compressed = plan.compress();
+
+        let round_trips = compressed
+            .iter()
+            .filter(|step| matches!(step, CompressedStep::RoundTrip(_)))
+            .count();
+        assert_eq!(round_trips, 5);
+        assert_eq!(compressed.len(), 6);
+
+        let expanded = compressed
+            .iter()
+            .flat_map(CompressedStep::steps)
+            .map(|(action, state)| (action.humans, action.zombies, state.clone()))
+            .collect::<Vec<_>>();
+        let original = plan
+            .steps()
+            .iter()
+            .map(|(action, state)| (action.humans, action.zombies, state.clone()))
+            .collect::<Vec<_>>();
+        assert!(expanded == original);
+    }
+
+    #[test]
+    fn plans_without_crossings_are_not_compressed() {
+        let plan = quietly(|| search(Hanoi::new(3).initial())).expect("problem is solvable");
+        let compressed = plan.compress();
+        assert_eq!(compressed.len(), plan.len());
+        assert!(compressed
+            .iter()
+            .all(|step| matches!(step, CompressedStep::Single(_))));
+    }
+}
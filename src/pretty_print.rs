@@ -1,9 +1,406 @@
+use crate::plan::{CompressedStep, Plan};
+use crate::search::{search, Action, State};
+use crate::verbosity::quietly;
+#[cfg(feature = "color")]
+use colored::Colorize;
+use std::fmt::{Debug, Write};
+use std::hash::Hash;
+use std::io::{self, BufRead};
+
 pub trait PrettyPrintState {
     /// Pretty-prints a state.
     fn pretty_print(&self) -> String;
+
+    /// Pretty-prints a state using the symbols of the specified style.
+    ///
+    /// The default implementation ignores the style.
+    fn pretty_print_styled(&self, _style: &RenderStyle) -> String {
+        self.pretty_print()
+    }
 }
 
 pub trait PrettyPrintAction<S> {
     /// Pretty-prints an action.
     fn pretty_print(&self, state: &S) -> String;
+
+    /// Pretty-prints an action using the symbols of the specified style.
+    ///
+    /// The default implementation ignores the style.
+    fn pretty_print_styled(&self, state: &S, _style: &RenderStyle) -> String {
+        self.pretty_print(state)
+    }
+}
+
+/// The symbols used to render the river scene.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RenderStyle {
+    /// The symbol of a human.
+    pub human: String,
+    /// The symbol of a zombie.
+    pub zombie: String,
+    /// The symbol of a farmer, or [`None`] to spell it out.
+    pub farmer: Option<String>,
+    /// The symbol of a wolf, or [`None`] to spell it out.
+    pub wolf: Option<String>,
+    /// The symbol of a goat, or [`None`] to spell it out.
+    pub goat: Option<String>,
+    /// The symbol of a cabbage, or [`None`] to spell it out.
+    pub cabbage: Option<String>,
+    /// The symbol of the boat.
+    pub boat: String,
+    /// The symbol of the water; the river is three of them wide.
+    pub water: String,
+    /// The symbol of the river banks, enclosing the river on either side.
+    pub bank: String,
+    /// Whether crossings are drawn within the river, between its banks, rather than starting
+    /// where the river does.
+    pub crossings_in_river: bool,
+    /// Whether the river scene is mirrored, showing the left bank on the right and crossings
+    /// towards the left bank as moving rightward.
+    pub right_to_left: bool,
+    /// Whether actions and prompts are highlighted using colors, i.e. ANSI escape codes.
+    /// Nothing is highlighted without the `color` feature.
+    pub colors: bool,
+}
+
+impl RenderStyle {
+    /// Creates a style rendering everything as emoji.
+    pub fn emoji() -> Self {
+        Self {
+            human: "🧑".into(),
+            zombie: "🧟".into(),
+            farmer: Some("👨‍🌾".into()),
+            wolf: Some("🐺".into()),
+            goat: Some("🐐".into()),
+            cabbage: Some("🥬".into()),
+            boat: "🚣".into(),
+            water: "🌊".into(),
+            bank: "|".into(),
+            crossings_in_river: false,
+            right_to_left: false,
+            colors: false,
+        }
+    }
+
+    /// Creates a style rendering people as letters, but the river using box-drawing and
+    /// water glyphs, e.g. `║🚣～～～║`.
+    pub fn unicode() -> Self {
+        Self::default().with_unicode_river()
+    }
+
+    /// Replaces the symbols of the river with box-drawing and water glyphs, drawing crossings
+    /// within the river.
+    pub fn with_unicode_river(self) -> Self {
+        Self {
+            boat: "🚣".into(),
+            water: "～".into(),
+            bank: "║".into(),
+            crossings_in_river: true,
+            ..self
+        }
+    }
+
+    /// Renders the river with the boat at the left or right bank, which is drawn on the other
+    /// side if the scene is [mirrored](Self::right_to_left).
+    pub fn river(&self, boat_left: bool) -> String {
+        let (bank, water) = (&self.bank, self.water.repeat(3));
+        if boat_left != self.right_to_left {
+            format!("{bank}{}{water}{bank}", self.boat)
+        } else {
+            format!("{bank}{water}{}{bank}", self.boat)
+        }
+    }
+
+    /// Renders a crossing of the specified load towards the left or right bank, pointing the
+    /// other way if the scene is [mirrored](Self::right_to_left).
+    ///
+    /// If crossings are drawn [within the river](Self::crossings_in_river), the load is padded
+    /// to the width of the river so that its banks line up with those of the states, unless it
+    /// does not fit.
+    pub fn crossing(&self, towards_left: bool, load: &str) -> String {
+        let crossing = if towards_left != self.right_to_left {
+            format!("← {load}")
+        } else {
+            format!("{load} →")
+        };
+        if !self.crossings_in_river {
+            return crossing;
+        }
+
+        let width = display_width(&self.boat) + 3 * display_width(&self.water);
+        let padding = width.saturating_sub(display_width(&crossing));
+        let (bank, padding) = (&self.bank, " ".repeat(padding));
+        if self.right_to_left {
+            format!("{bank}{padding}{crossing}{bank}")
+        } else {
+            format!("{bank}{crossing}{padding}{bank}")
+        }
+    }
+
+    /// Highlights an action in yellow if [colors](Self::colors) are used.
+    pub fn highlight(&self, action: String) -> String {
+        #[cfg(feature = "color")]
+        if self.colors {
+            return action.yellow().to_string();
+        }
+        action
+    }
+
+    /// Dims a hint if [colors](Self::colors) are used.
+    pub fn dim(&self, hint: &str) -> String {
+        #[cfg(feature = "color")]
+        if self.colors {
+            return hint.dimmed().to_string();
+        }
+        hint.to_string()
+    }
+}
+
+impl Default for RenderStyle {
+    fn default() -> Self {
+        Self {
+            human: "H".into(),
+            zombie: "Z".into(),
+            farmer: None,
+            wolf: None,
+            goat: None,
+            cabbage: None,
+            boat: "B".into(),
+            water: "~".into(),
+            bank: "|".into(),
+            crossings_in_river: false,
+            right_to_left: false,
+            colors: false,
+        }
+    }
+}
+
+/// Estimates the number of terminal columns the specified text takes up.
+///
+/// Emoji, other pictographs and fullwidth forms take up two columns, while joiners and variation selectors,
+/// as well as every character joined to the previous one, take up none.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut joined = false;
+    for c in text.chars() {
+        let code = c as u32;
+        width += match code {
+            _ if joined => 0,
+            0x200D | 0xFE00..=0xFE0F | 0x0300..=0x036F => 0,
+            0x1F300..=0x1FAFF | 0x2600..=0x27BF | 0x1100..=0x115F | 0x2E80..=0xA4CF => 2,
+            0xFF01..=0xFF60 | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        };
+        joined = code == 0x200D;
+    }
+    width
+}
+
+/// Pads the specified text with spaces on the left to take up at least `width` columns.
+pub fn pad_left(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{text}", " ".repeat(padding))
+}
+
+/// Formats a plan as one line per state, each preceded by the action that lead to it.
+pub fn format_plan<S, A>(plan: &Plan<S, A>) -> String
+where
+    S: PrettyPrintState,
+    A: PrettyPrintAction<S>,
+{
+    format_plan_styled(plan, &RenderStyle::default())
+}
+
+/// Formats a plan like [`format_plan`], using the symbols of the specified style and
+/// highlighting the actions if it uses [colors](RenderStyle::colors).
+pub fn format_plan_styled<S, A>(plan: &Plan<S, A>, style: &RenderStyle) -> String
+where
+    S: PrettyPrintState,
+    A: PrettyPrintAction<S>,
+{
+    let mut buffer = String::new();
+    writeln!(buffer, "  {}", plan.initial().pretty_print_styled(style))
+        .expect("writing to a string cannot fail");
+    for (action, state) in plan.steps() {
+        writeln!(
+            buffer,
+            "  {}",
+            style.highlight(action.pretty_print_styled(state, style))
+        )
+        .expect("writing to a string cannot fail");
+        writeln!(buffer, "  {}", state.pretty_print_styled(style))
+            .expect("writing to a string cannot fail");
+    }
+    buffer
+}
+
+/// Formats a plan like [`format_plan_styled`], but [compressed](Plan::compress): every round
+/// trip is shown as its two actions, bracketed on the left, followed by the state it leads to.
+pub fn format_plan_combined<S, A>(plan: &Plan<S, A>, style: &RenderStyle) -> String
+where
+    S: PrettyPrintState + Clone,
+    A: PrettyPrintAction<S> + Action<State = S> + Clone,
+{
+    let mut buffer = String::new();
+    writeln!(buffer, "  {}", plan.initial().pretty_print_styled(style))
+        .expect("writing to a string cannot fail");
+    for step in plan.compress() {
+        let brackets: &[&str] = match step {
+            CompressedStep::Single(_) => &[" "],
+            CompressedStep::RoundTrip(_) => &["┌", "└"],
+        };
+        for (bracket, (action, state)) in brackets.iter().zip(step.steps()) {
+            writeln!(
+                buffer,
+                "{bracket} {}",
+                style.highlight(action.pretty_print_styled(state, style))
+            )
+            .expect("writing to a string cannot fail");
+        }
+        writeln!(buffer, "  {}", step.state().pretty_print_styled(style))
+            .expect("writing to a string cannot fail");
+    }
+    buffer
+}
+
+/// Walks through a plan one step at a time, printing the initial state and then revealing
+/// the next action and the state it leads to whenever a line is read from the `input`,
+/// e.g. when Enter is pressed.
+///
+/// If `redraw` is set, the terminal is cleared before every step, which is then shown
+/// along with the state it started from. Once the input ends, e.g. when Ctrl-D is pressed,
+/// the remaining steps are printed all at once.
+pub fn step_through<S, A>(
+    plan: &Plan<S, A>,
+    style: &RenderStyle,
+    mut input: impl BufRead,
+    mut output: impl io::Write,
+    redraw: bool,
+) -> io::Result<()>
+where
+    S: PrettyPrintState,
+    A: PrettyPrintAction<S>,
+{
+    writeln!(output, "  {}", plan.initial().pretty_print_styled(style))?;
+
+    let mut previous = plan.initial();
+    let mut interactive = true;
+    for (index, (action, state)) in plan.steps().iter().enumerate() {
+        if interactive {
+            write!(
+                output,
+                "{}",
+                style.dim("Press Enter for the next step, or Ctrl-D for all of them.")
+            )?;
+            output.flush()?;
+
+            // Reading fails e.g. on input that is not UTF-8, which is as good as no input.
+            let mut line = String::new();
+            interactive = input.read_line(&mut line).unwrap_or(0) > 0;
+            if !interactive {
+                writeln!(output)?;
+            }
+        }
+
+        if interactive && redraw {
+            // Clear the screen and move the cursor to the top left corner.
+            write!(output, "\x1b[2J\x1b[H")?;
+            writeln!(output, "Step {} of {}:\n", index + 1, plan.len())?;
+            writeln!(output, "  {}", previous.pretty_print_styled(style))?;
+        }
+
+        writeln!(
+            output,
+            "  {}",
+            style.highlight(action.pretty_print_styled(state, style))
+        )?;
+        writeln!(output, "  {}", state.pretty_print_styled(style))?;
+        previous = state;
+    }
+    Ok(())
+}
+
+/// Searches the state space for a plan and formats it like [`format_plan`].
+///
+/// Unlike the command-line application, nothing is printed; returns [`None`]
+/// if no plan was found.
+pub fn solve_to_string<S, A>(initial_state: S) -> Option<String>
+where
+    S: State<Action = A> + Clone + Eq + Debug + PrettyPrintState,
+    A: Action<State = S> + Clone + Debug + PrettyPrintAction<S>,
+    S::Hash: Eq + Hash,
+{
+    quietly(|| search(initial_state)).map(|plan| format_plan(&plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+
+    #[test]
+    fn solve_to_string_renders_every_step() {
+        let solution = solve_to_string(WorldState::default()).expect("problem is solvable");
+        let lines = solution.lines().collect::<Vec<_>>();
+
+        // The initial state, followed by 11 actions and the states they lead to.
+        assert_eq!(lines.len(), 23);
+        assert_eq!(lines[0], "  HHH ZZZ |B~~~|");
+        assert_eq!(lines[22], "          |~~~B| HHH ZZZ");
+    }
+
+    #[test]
+    fn remaining_steps_are_printed_once_input_ends() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let mut output = Vec::new();
+        step_through(
+            &plan,
+            &RenderStyle::default(),
+            "\n\n".as_bytes(),
+            &mut output,
+            false,
+        )
+        .expect("writing to a vector cannot fail");
+
+        let output = String::from_utf8(output).expect("output is UTF-8");
+        let prompts = output.matches("Press Enter").count();
+        assert_eq!(prompts, 3);
+        assert_eq!(output.lines().count(), 23 + 1);
+        assert_eq!(output.lines().last(), Some("          |~~~B| HHH ZZZ"));
+    }
+
+    #[test]
+    fn round_trips_are_bracketed() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let combined = format_plan_combined(&plan, &RenderStyle::default());
+        let lines = combined.lines().collect::<Vec<_>>();
+
+        // The initial state, five round trips of two actions each followed by the state they
+        // lead to, and the final crossing followed by the goal state.
+        assert_eq!(lines.len(), 1 + 5 * 3 + 2);
+        assert!(lines[1].starts_with('┌') && lines[2].starts_with('└'));
+        assert!(lines[16].starts_with("  "));
+        assert_eq!(lines[17], "          |~~~B| HHH ZZZ");
+    }
+
+    #[test]
+    fn emoji_take_up_two_columns() {
+        assert_eq!(display_width("HHH"), 3);
+        assert_eq!(display_width("🧑🧑 🧟"), 7);
+        assert_eq!(display_width("👨‍🌾"), 2);
+        assert_eq!(pad_left("🧟", 4), "  🧟");
+    }
+
+    #[test]
+    fn unicode_crossings_fill_the_river() {
+        let style = RenderStyle::unicode();
+        assert_eq!(style.river(true), "║🚣～～～║");
+        assert_eq!(display_width(&style.river(true)), 10);
+
+        let crossing = style.crossing(true, "ZZ");
+        assert_eq!(crossing, "║← ZZ    ║");
+        assert_eq!(display_width(&crossing), 10);
+        assert_eq!(style.crossing(false, "HH ZZ"), "║HH ZZ → ║");
+        assert_eq!(RenderStyle::default().crossing(false, "HH"), "HH →");
+    }
 }
@@ -1,3 +1,70 @@
+use crate::plan::Plan;
+use crate::search::State;
+use crate::strategies::Objective;
+use std::cmp::Ordering;
+
 pub mod bridge_and_torch;
+pub mod generic;
+pub mod hanoi;
 pub mod humans_and_zombies;
+#[cfg(feature = "compact")]
+pub mod humans_and_zombies_compact;
+pub mod jealous_husbands;
+pub mod missionaries_cannibals;
+pub mod predator_prey;
+pub mod water_jugs;
 pub mod wolf_goat_cabbage;
+
+/// A problem, i.e. a configuration from which an initial world state can be built.
+pub trait Problem {
+    /// The type of world state of this problem.
+    type State: State;
+
+    /// Builds the initial world state.
+    fn initial(&self) -> Self::State;
+
+    /// Tests whether the specified world state is a goal state.
+    ///
+    /// By default, this delegates to [`State::is_goal`].
+    fn is_goal(&self, state: &Self::State) -> bool {
+        state.is_goal()
+    }
+
+    /// Gets the objectives the cost of a plan can be measured by in this problem.
+    ///
+    /// By default, only the number of trips is supported.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips]
+    }
+
+    /// Gets the objective the cost of a plan is naturally measured by in this problem, i.e. the
+    /// one minimized when asking for the optimal plan; it must be one of the
+    /// [objectives](Problem::objectives), measured by [`Action::cost`](crate::search::Action::cost).
+    ///
+    /// By default, this is the number of trips.
+    fn natural_objective(&self) -> Objective {
+        Objective::Trips
+    }
+
+    /// Derives problem-specific statistics from a plan, as pairs of a name and a value.
+    ///
+    /// By default, there are none.
+    fn statistics(
+        &self,
+        _plan: &Plan<Self::State, <Self::State as State>::Action>,
+    ) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Compares two plans of equal length by a problem-specific preference, the preferred plan
+    /// ordering first.
+    ///
+    /// By default, all plans are equally preferred.
+    fn compare_plans(
+        &self,
+        _a: &Plan<Self::State, <Self::State as State>::Action>,
+        _b: &Plan<Self::State, <Self::State as State>::Action>,
+    ) -> Ordering {
+        Ordering::Equal
+    }
+}
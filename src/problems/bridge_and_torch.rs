@@ -1,10 +1,43 @@
-use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
-use crate::search::{Action, State};
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::plan::Plan;
+use crate::pretty_print::{display_width, pad_left, PrettyPrintAction, PrettyPrintState};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
 use itertools::Itertools;
-use std::fmt::{Debug, Formatter};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// The Bridge and Torch problem.
+#[derive(Debug, Clone)]
+pub struct BridgeAndTorch {
+    /// The capacity of the bridge, i.e. how many people it can hold.
+    pub bridge: u8,
+    /// The time the torch will burn.
+    pub torch: u8,
+    /// The people on the left river side.
+    pub people: Vec<Person>,
+    /// The time the torch is topped up to whenever it reaches a river side, if any.
+    pub refuel: Option<u8>,
+    /// How many people may cross back to the left river side at once, if it differs
+    /// from the capacity of the bridge.
+    pub return_capacity: Option<u8>,
+    /// Pairs of person IDs `(a, b)` where person `b` must not cross to the
+    /// right river side before person `a` has arrived there.
+    pub before: Vec<(u32, u32)>,
+}
 
 /// Describes the world state.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+///
+/// Two states are considered equal regardless of the time elapsed to reach them:
+/// which moves remain possible depends on the remaining torch time alone, which
+/// already accounts for the torch being refuelled.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldState {
     /// The current time.
     pub time: u8,
@@ -14,11 +47,21 @@ pub struct WorldState {
     pub right: RiverSideState,
     /// The torch.
     pub torch: Torch,
-    /// The capacity of the bridge, i.e. how many people it can hold.
-    pub bridge_capacity: u8,
+    /// How many people may cross from the left to the right river side at once.
+    pub forward_capacity: u8,
+    /// How many people may cross from the right back to the left river side at once.
+    pub return_capacity: u8,
+    /// The time the torch is topped up to after every crossing, if any.
+    pub refuel_on_arrival: Option<u8>,
+    /// Pairs of person IDs `(a, b)` where person `b` must not cross to the
+    /// right river side before person `a` has arrived there.
+    pub before: Arc<[(u32, u32)]>,
+    /// The sorted IDs of the people constrained by [`WorldState::before`]
+    /// that have arrived on the right river side at least once.
+    pub arrived: Vec<u32>,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum RiverSide {
     /// The left river side.
     Left,
@@ -26,7 +69,7 @@ pub enum RiverSide {
     Right,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Torch {
     /// The location of the torch.
     pub side: RiverSide,
@@ -34,29 +77,144 @@ pub struct Torch {
     pub remaining_time: u8,
 }
 
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+/// A person, identified by their ID such that people with the same
+/// walking time can still be told apart.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Person {
     /// The time it takes for the person to cross the bridge.
     pub walking_time: u8,
+    /// The ID of the person.
+    pub id: u32,
+    /// The name of the person, if any.
+    pub name: Option<Arc<str>>,
 }
 
 /// Describes the state on a river side.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+///
+/// The people are kept sorted by their walking time, such that the order
+/// in which people arrived on this side does not affect equality.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RiverSideState {
     /// The people on this side.
     pub people: Vec<Person>,
 }
 
 /// An action to apply.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldAction {
     /// The people to move.
     pub people: Vec<Person>,
+    /// Whether this action undoes a crossing, turning back the clock
+    /// rather than burning the torch.
+    pub rewind: bool,
+}
+
+impl BridgeAndTorch {
+    /// Creates a new problem from the bridge capacity, the torch time and the
+    /// walking times of the people. The people are assigned consecutive IDs.
+    pub fn new(bridge: u8, torch: u8, walking_times: Vec<u8>) -> Self {
+        let people = walking_times
+            .into_iter()
+            .zip(0..)
+            .map(|(walking_time, id)| Person::new(id, walking_time))
+            .collect();
+        Self::with_people(bridge, torch, people)
+    }
+
+    /// Creates a new problem from the bridge capacity, the torch time and the people.
+    pub const fn with_people(bridge: u8, torch: u8, people: Vec<Person>) -> Self {
+        Self {
+            bridge,
+            torch,
+            people,
+            refuel: None,
+            return_capacity: None,
+            before: Vec::new(),
+        }
+    }
+}
+
+impl Default for BridgeAndTorch {
+    fn default() -> Self {
+        Self::new(2, 15, vec![1, 2, 5, 8])
+    }
+}
+
+impl Problem for BridgeAndTorch {
+    type State = WorldState;
+
+    /// Builds the initial world state with everyone on the left river side.
+    fn initial(&self) -> WorldState {
+        let left = RiverSideState::new(self.people.clone());
+        let right = RiverSideState::new(vec![]);
+        let torch = Torch::new(self.torch, RiverSide::Left);
+        WorldState {
+            refuel_on_arrival: self.refuel,
+            return_capacity: self.return_capacity.unwrap_or(self.bridge),
+            before: self.before.as_slice().into(),
+            ..WorldState::new(left, right, torch, 0, self.bridge)
+        }
+    }
+
+    /// Supports minimizing the number of trips, the time taken and the number of people moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Time, Objective::Moves]
+    }
+
+    /// Measures plans by the time it takes everyone to cross.
+    fn natural_objective(&self) -> Objective {
+        Objective::Time
+    }
+
+    /// Reports the person-minutes and the critical crossing of the plan.
+    fn statistics(&self, plan: &Plan<WorldState, WorldAction>) -> Vec<(String, String)> {
+        let mut statistics = vec![(
+            String::from("person-minutes"),
+            person_minutes(plan).to_string(),
+        )];
+
+        if let Some((step, action)) = critical_crossing(plan) {
+            let walking_time = action.walking_time();
+            statistics.push((
+                String::from("critical crossing"),
+                format!(
+                    "step {step}, {:?} taking {walking_time} minute{}",
+                    action.people,
+                    if walking_time == 1 { "" } else { "s" }
+                ),
+            ));
+        }
+
+        statistics
+    }
+}
+
+/// Gets the time spent walking summed up over every single person crossing in the plan,
+/// i.e. the effort of the plan rather than its duration.
+///
+/// Everyone crossing together walks as long as the slowest of them.
+pub fn person_minutes(plan: &Plan<WorldState, WorldAction>) -> u32 {
+    plan.steps()
+        .iter()
+        .map(|(action, _)| action.people.len() as u32 * action.walking_time() as u32)
+        .sum()
+}
+
+/// Finds the slowest crossing of the plan along with its step number, starting at 1.
+///
+/// If several crossings are equally slow, the first one is reported.
+pub fn critical_crossing(plan: &Plan<WorldState, WorldAction>) -> Option<(usize, &WorldAction)> {
+    plan.steps()
+        .iter()
+        .zip(1..)
+        .min_by_key(|((action, _), _)| Reverse(action.walking_time()))
+        .map(|((action, _), step)| (step, action))
 }
 
 impl WorldState {
-    /// Creates a new problem state from the left and right river side states.
-    pub const fn new(
+    /// Creates a new problem state from the left and right river side states,
+    /// with the bridge holding as many people in either direction.
+    pub fn new(
         left: RiverSideState,
         right: RiverSideState,
         torch: Torch,
@@ -68,10 +226,25 @@ impl WorldState {
             right,
             torch,
             time,
-            bridge_capacity,
+            forward_capacity: bridge_capacity,
+            return_capacity: bridge_capacity,
+            refuel_on_arrival: None,
+            before: Arc::new([]),
+            arrived: Vec::new(),
         }
     }
 
+    /// Determines whether the specified people may cross to the right river side
+    /// without violating any ordering constraint. People crossing together with
+    /// the person they must follow are considered to arrive in time.
+    fn may_cross_forward(&self, people: &[Person]) -> bool {
+        self.before.iter().all(|(a, b)| {
+            !people.iter().any(|p| p.id == *b)
+                || self.arrived.contains(a)
+                || people.iter().any(|p| p.id == *a)
+        })
+    }
+
     /// Unpacks the world state into a (mutable) tuple of "this river side" (i.e.
     /// the side that the torch is currently at) and "the opposite river side".
     pub fn here_there_mut(&mut self) -> (&mut RiverSideState, &mut RiverSideState) {
@@ -81,6 +254,14 @@ impl WorldState {
         }
     }
 
+    /// Gets how many people may cross from the river side the torch is at.
+    pub fn capacity(&self) -> u8 {
+        match self.torch.side {
+            RiverSide::Left => self.forward_capacity,
+            RiverSide::Right => self.return_capacity,
+        }
+    }
+
     /// Gets the river side the torch is at.
     pub fn torch_side(&self) -> &RiverSideState {
         match self.torch.side {
@@ -88,19 +269,75 @@ impl WorldState {
             RiverSide::Right => &self.right,
         }
     }
+
+    /// Gets a lower bound on the time it takes to get everyone to the right river side.
+    ///
+    /// Every crossing takes at least as long as the fastest person, and the slowest person
+    /// still on the left has to cross at least once. The number of crossings follows from
+    /// the bridge capacity: every round trip gets at most one person less across than it holds.
+    pub fn lower_bound(&self) -> u32 {
+        let Some(slowest) = self.left.people.iter().map(|p| p.walking_time).max() else {
+            return 0;
+        };
+
+        let fastest = self
+            .left
+            .people
+            .iter()
+            .chain(&self.right.people)
+            .map(|p| p.walking_time)
+            .min()
+            .unwrap_or(slowest);
+
+        // If the torch is on the right, someone has to bring it back first.
+        let (returns, waiting) = match self.torch.side {
+            RiverSide::Left => (0, self.left.people.len()),
+            RiverSide::Right => (1, self.left.people.len() + 1),
+        };
+
+        let capacity = usize::from(self.forward_capacity.max(2));
+        let forward = if waiting <= capacity {
+            1
+        } else {
+            (waiting - capacity).div_ceil(capacity - 1) + 1
+        };
+        let crossings = returns + 2 * forward - 1;
+
+        slowest as u32 + (crossings as u32 - 1) * fastest as u32
+    }
 }
 
 impl Default for WorldState {
     fn default() -> Self {
-        let left = RiverSideState::new(vec![
-            Person::new(1),
-            Person::new(2),
-            Person::new(5),
-            Person::new(8),
-        ]);
-        let right = RiverSideState::new(vec![]);
-        let torch = Torch::new(15, RiverSide::Left);
-        WorldState::new(left, right, torch, 0, 2)
+        BridgeAndTorch::default().initial()
+    }
+}
+
+impl PartialEq for WorldState {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left
+            && self.right == other.right
+            && self.torch == other.torch
+            && self.forward_capacity == other.forward_capacity
+            && self.return_capacity == other.return_capacity
+            && self.refuel_on_arrival == other.refuel_on_arrival
+            && self.before == other.before
+            && self.arrived == other.arrived
+    }
+}
+
+impl Eq for WorldState {}
+
+impl Hash for WorldState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.left.hash(state);
+        self.right.hash(state);
+        self.torch.hash(state);
+        self.forward_capacity.hash(state);
+        self.return_capacity.hash(state);
+        self.refuel_on_arrival.hash(state);
+        self.before.hash(state);
+        self.arrived.hash(state);
     }
 }
 
@@ -115,9 +352,21 @@ impl Debug for WorldState {
 }
 
 impl Person {
-    /// Creates a new person from the number of minutes it takes to cross the bridge.
-    pub const fn new(walking_time: u8) -> Self {
-        Self { walking_time }
+    /// Creates a new person from their ID and the number of minutes it takes to cross the bridge.
+    pub const fn new(id: u32, walking_time: u8) -> Self {
+        Self {
+            walking_time,
+            id,
+            name: None,
+        }
+    }
+
+    /// Creates a new named person from their ID and the number of minutes it takes to cross the bridge.
+    pub fn named(id: u32, name: &str, walking_time: u8) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::new(id, walking_time)
+        }
     }
 }
 
@@ -133,19 +382,55 @@ impl Torch {
 
 impl RiverSideState {
     /// Creates a new river side state from the people.
-    pub const fn new(people: Vec<Person>) -> Self {
+    pub fn new(mut people: Vec<Person>) -> Self {
+        people.sort();
         Self { people }
     }
 
+    /// Adds a person to this side, keeping the people sorted.
+    pub fn insert(&mut self, person: Person) {
+        let index = self.people.partition_point(|p| *p <= person);
+        self.people.insert(index, person);
+    }
+
     /// Determines whether this river side is empty, i.e. contains no people.
     pub fn is_empty(&self) -> bool {
         self.people.is_empty()
     }
+
+    /// Gets the people on this side in the order they are presented in, i.e. by walking time,
+    /// then by name and ID.
+    ///
+    /// Unlike the order of [`people`](Self::people), which determines the identity of the side,
+    /// this does not depend on how the side was constructed.
+    pub fn sorted(&self) -> Vec<&Person> {
+        sorted(&self.people)
+    }
 }
 
 impl Debug for Person {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "<{}>", self.walking_time)
+        match &self.name {
+            Some(name) => write!(f, "<{}:{}>", name, self.walking_time),
+            None => write!(f, "<{}>", self.walking_time),
+        }
+    }
+}
+
+impl Display for Person {
+    /// Formats the person as their name, if any, and their walking time, e.g. `Alice (5 min)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} ({} min)", name, self.walking_time),
+            None => write!(f, "{} min", self.walking_time),
+        }
+    }
+}
+
+impl Display for RiverSideState {
+    /// Formats the people on this side as a list sorted by walking time, e.g. `1 min, 5 min`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sorted().iter().join(", "))
     }
 }
 
@@ -158,7 +443,10 @@ impl Debug for RiverSideState {
 impl WorldAction {
     pub fn new(people: Vec<Person>) -> Self {
         debug_assert!(!people.is_empty());
-        Self { people }
+        Self {
+            people,
+            rewind: false,
+        }
     }
 
     pub fn walking_time(&self) -> u8 {
@@ -198,10 +486,25 @@ impl State for WorldState {
         self.left.is_empty()
     }
 
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // Nobody is on both river sides, and nobody arrived on the right before the person
+        // they must follow.
+        let both = self
+            .left
+            .people
+            .iter()
+            .any(|p| self.right.people.iter().any(|q| q.id == p.id));
+        let ordered = self.before.iter().all(|(a, b)| {
+            !self.right.people.iter().any(|p| p.id == *b) || self.arrived.contains(a)
+        });
+        !both && ordered
+    }
+
     /// Expands the world state into new (applicable) actions.
     /// If this state cannot be expanded, an empty vector is returned.
-    fn get_actions(&self) -> Vec<WorldAction> {
-        let mut actions = Vec::with_capacity(5);
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let mut actions = Actions::with_capacity(5);
 
         let side = self.torch_side();
 
@@ -214,18 +517,15 @@ impl State for WorldState {
         // be the pathological case of having exactly one person in the puzzle.
         // For simplicity and symmetry reasons, we still emit all options regardless.
         //
-        // We simplify the code by trying any unique permutation of people ranging
-        // from one person to the highest number of people. Unique permutations, here,
-        // means that out of the people combination [1, 1, 5] minutes each, only [1, 5]
-        // would be produced as the outcome for trying either [1] person is the same.
-        for c in 1..=self.bridge_capacity {
-            for people in side
-                .people
-                .iter()
-                .permutations(c as _)
-                .map(|v| v.into_iter().sorted().cloned().collect_vec())
-                .unique()
-            {
+        // We simplify the code by trying every group of people ranging from one person
+        // to as many as the bridge holds in the direction of the crossing. Since the people
+        // on a side are kept sorted and distinct, every combination is a unique group, i.e.
+        // the people [A, B] and [B, A] are only tried once without having to generate and
+        // discard all their orderings. A bridge holding more people than there are simply
+        // lets everyone cross at once.
+        let capacity = usize::from(self.capacity()).min(side.people.len());
+        for c in 1..=capacity {
+            for people in side.people.iter().cloned().combinations(c) {
                 let action = WorldAction::new(people);
                 if action.is_applicable(self) {
                     actions.push(action);
@@ -242,17 +542,47 @@ impl State for WorldState {
         // on the left side of the bridge and by the torch. Just the
         // torch location is not enough as multiple paths could lead
         // to the same people/torch position but different remaining times.
+        // With refuelling, the torch is topped up after every crossing,
+        // while the elapsed time no longer matters at all.
         HashState {
             left: self.left.people.clone(),
             torch: self.torch,
+            arrived: self.arrived.clone(),
         }
     }
+
+    /// Estimates the remaining time as the walking time of the slowest person
+    /// still on the left river side, since they have to cross at least once.
+    fn heuristic(&self) -> u32 {
+        self.left
+            .people
+            .iter()
+            .map(|p| p.walking_time as u32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Bounds the remaining time by [`WorldState::lower_bound`] and the remaining moves by the
+    /// number of people still on the left river side. The remaining trips are not bounded.
+    fn admissible_heuristic(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Trips => 0,
+            Objective::Time => self.lower_bound(),
+            Objective::Moves => self.left.people.len() as u32,
+        }
+    }
+
+    /// Counts the people.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![(self.left.people.len() + self.right.people.len()) as u32]
+    }
 }
 
 #[derive(Eq, PartialEq, Hash)]
 pub struct HashState {
     left: Vec<Person>,
     torch: Torch,
+    arrived: Vec<u32>,
 }
 
 impl Action for WorldAction {
@@ -260,14 +590,44 @@ impl Action for WorldAction {
 
     /// Tests whether an action is applicable in the given (usually current) world state.
     fn is_applicable(&self, state: &Self::State) -> bool {
-        // We can only cross if the torch holds long enough.
-        state.torch.remaining_time >= self.walking_time()
+        // Someone must cross, everyone crossing must be on the torch side, and cross only once.
+        let here = &state.torch_side().people;
+        let present = self.people.iter().enumerate().all(|(i, person)| {
+            here.iter().any(|p| p.id == person.id)
+                && !self.people[..i].iter().any(|p| p.id == person.id)
+        });
+        if self.people.is_empty() || !present {
+            return false;
+        }
+
+        // Undoing a crossing requires the crossing to have happened.
+        if self.rewind {
+            let walking_time = self.walking_time();
+            return state.time >= walking_time
+                && state
+                    .torch
+                    .remaining_time
+                    .checked_add(walking_time)
+                    .is_some();
+        }
+
+        // We can only cross if the torch holds long enough, and
+        // refuelling must not let the clock run over.
+        let walking_time = self.walking_time();
+        if state.torch.remaining_time < walking_time
+            || state.time.checked_add(walking_time).is_none()
+        {
+            return false;
+        }
+
+        // Crossing to the right must respect the required order of arrival.
+        state.torch.side == RiverSide::Right || state.may_cross_forward(&self.people)
     }
 
     /// Applies the specified action to the specified world state,
     /// returning the new state after the action was applied.
-    fn apply(&self, state: &Self::State) -> Self::State {
-        let mut state = state.clone();
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
         let (here, there) = state.here_there_mut();
 
         // Move each person from here to there.
@@ -275,70 +635,567 @@ impl Action for WorldAction {
             here.people.remove(
                 here.people
                     .iter()
-                    .position(|x| *x == *person)
+                    .position(|x| x.id == person.id)
                     .expect("person not found"),
             );
-            there.people.push(person.clone());
+            there.insert(person.clone());
         }
+        debug_assert_conserved(before, &state);
 
         let walking_time = self.walking_time();
+        if self.rewind {
+            state.time -= walking_time;
+            state.torch = Torch::new(
+                state.torch.remaining_time + walking_time,
+                state.torch.side.switch(),
+            );
+            debug_assert_valid(before, &state);
+            return state;
+        }
+
+        // Remember who arrived on the right, as far as the ordering constraints are concerned.
+        if state.torch.side == RiverSide::Left {
+            for person in self.people.iter() {
+                let constrained = state.before.iter().any(|(a, _)| *a == person.id);
+                if let (true, Err(index)) = (constrained, state.arrived.binary_search(&person.id)) {
+                    state.arrived.insert(index, person.id);
+                }
+            }
+        }
+
+        // The torch is topped up as soon as it arrives, ready for the next crossing.
+        let mut remaining_time = state.torch.remaining_time - walking_time;
+        if let Some(max) = state.refuel_on_arrival {
+            remaining_time = remaining_time.max(max);
+        }
         state.time += walking_time;
-        state.torch = Torch::new(
-            state.torch.remaining_time - walking_time,
-            state.torch.side.switch(),
-        );
+        state.torch = Torch::new(remaining_time, state.torch.side.switch());
+        debug_assert_valid(before, &state);
         state
     }
+
+    /// Gets the action undoing this one, i.e. the same people crossing back in time.
+    ///
+    /// Neither refuelling nor the arrivals recorded for ordering constraints can be undone,
+    /// so the inverse is only exact for problems using neither.
+    fn inverse(&self) -> Self {
+        Self {
+            people: self.people.clone(),
+            rewind: !self.rewind,
+        }
+    }
+
+    /// Determines whether this action brings the torch back, i.e. whether it is on the right.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.torch.side == RiverSide::Right
+    }
+
+    /// Gets the cost of this action, where the time is the walking time
+    /// of the slowest person and moves count every person on the bridge.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Trips => 1,
+            Objective::Time => self.walking_time() as u32,
+            Objective::Moves => self.people.len() as u32,
+        }
+    }
+
+    /// Describes the people walking towards the side the torch arrived at, undoing a crossing
+    /// or not.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        ActionSummary::crossing(
+            state.torch.side == RiverSide::Left,
+            sorted(&self.people)
+                .into_iter()
+                .map(Person::to_string)
+                .collect(),
+        )
+    }
 }
 
 impl PrettyPrintState for WorldState {
-    /// Pretty-prints a world state.
+    /// Pretty-prints a world state as the people on either side of the bridge,
+    /// with the torch at the end of the bridge it is at.
     fn pretty_print(&self) -> String {
-        format!(
-            "At {} minute{}: {} on the left, {} on the right (torch: {} minute{})",
-            self.time,
-            if self.time == 1 { "" } else { "s" },
-            if self.left.is_empty() {
-                "nobody".into()
-            } else {
-                format!("{:?}", self.left)
-            },
-            if self.right.is_empty() {
-                "nobody".into()
-            } else {
-                format!("{:?}", self.right)
-            },
-            self.torch.remaining_time,
-            if self.torch.remaining_time == 1 {
-                ""
-            } else {
-                "s"
-            },
-        )
+        // Left side, right-aligned to fit everyone.
+        let mut buffer = format!(
+            "{}:  {} ",
+            format_clock(self.time, self.torch.remaining_time),
+            pad_left(&format_people(&self.left.people), self.side_width())
+        );
+
+        // Bridge.
+        buffer.push_str(match self.torch.side {
+            RiverSide::Left => "|T===|",
+            RiverSide::Right => "|===T|",
+        });
+        buffer.push(' ');
+
+        // Right side.
+        buffer.push_str(&format_people(&self.right.people));
+        buffer.trim_end().into()
     }
 }
 
 impl PrettyPrintAction<WorldState> for WorldAction {
-    /// Pretty-prints an action
+    /// Pretty-prints an action as the people on the bridge, walking in the direction
+    /// of the crossing.
     fn pretty_print(&self, state: &WorldState) -> String {
         let walking_time = self.walking_time();
 
+        // The state is the one after the action was applied, so we rewind the clock to the
+        // moment the crossing started; undoing a crossing turned the clock back instead.
+        let clock = if self.rewind {
+            format_clock(
+                state.time + walking_time,
+                state.torch.remaining_time.saturating_sub(walking_time),
+            ) + ":"
+        } else if let Some(max) = state.refuel_on_arrival {
+            // The torch was topped up on arrival, so how long it had left when the crossing
+            // started is not known here; only the time is shown, padded like the states.
+            let start = state.time - walking_time;
+            let width = (start as u16 + max as u16).to_string().len();
+            let time = format!("t={start:>width$}:");
+            format!("{time:<0$}", format_clock(start, max).len() + 1)
+        } else {
+            format_clock(
+                state.time - walking_time,
+                state.torch.remaining_time + walking_time,
+            ) + ":"
+        };
+
+        let people = format_people(&self.people);
+        let minutes = self.minutes();
+
         // Note the conditions here are flipped as this represent the state
         // after the action was applied.
-        match state.torch.side {
-            RiverSide::Right => format!(
-                " → {:?} cross forward, taking {} minute{}",
-                self.people,
-                walking_time,
-                if walking_time == 1 { "" } else { "s" },
+        let crossing = match (self.rewind, state.torch.side) {
+            (true, RiverSide::Left) => format!("← {people} undo their crossing, {minutes}"),
+            (true, RiverSide::Right) => format!("{people} → undo their return, {minutes}"),
+            (false, RiverSide::Right) => format!("{people} → cross forward, {minutes}"),
+            (false, RiverSide::Left) => format!(
+                "← {people} return{}, {minutes}",
+                if self.people.len() == 1 { "s" } else { "" },
             ),
-            RiverSide::Left => format!(
-                " ← {:?} return{}, taking {} minute{}",
-                self.people,
+        };
+
+        format!("{clock}  {}{crossing}", " ".repeat(state.side_width() + 2))
+    }
+}
+
+impl WorldState {
+    /// Gets the number of columns needed to list everyone on a single river side.
+    fn side_width(&self) -> usize {
+        let everyone = self
+            .left
+            .people
+            .iter()
+            .chain(&self.right.people)
+            .cloned()
+            .collect::<Vec<_>>();
+        display_width(&format_people(&everyone))
+    }
+}
+
+/// Formats people as a space-separated list, sorted by walking time.
+fn format_people(people: &[Person]) -> String {
+    sorted(people)
+        .iter()
+        .map(|person| format!("{person:?}"))
+        .join(" ")
+}
+
+/// Sorts people for presentation, by walking time, then by name and ID.
+fn sorted(people: &[Person]) -> Vec<&Person> {
+    people
+        .iter()
+        .sorted_by(|a, b| (a.walking_time, &a.name, a.id).cmp(&(b.walking_time, &b.name, b.id)))
+        .collect()
+}
+
+impl WorldAction {
+    /// Describes who crosses in which direction and how long it takes,
+    /// given the state after the action was applied.
+    fn describe(&self, state: &WorldState) -> String {
+        let people = sorted(&self.people).iter().join(", ");
+        let minutes = self.minutes();
+
+        // Note the conditions here are flipped as this represent the state
+        // after the action was applied.
+        match (self.rewind, state.torch.side) {
+            (true, RiverSide::Left) => format!("← {people} undo their crossing, {minutes}"),
+            (true, RiverSide::Right) => format!("→ {people} undo their return, {minutes}"),
+            (false, RiverSide::Right) => format!("→ {people} cross forward, {minutes}"),
+            (false, RiverSide::Left) => format!(
+                "← {people} return{}, {minutes}",
                 if self.people.len() == 1 { "s" } else { "" },
-                walking_time,
-                if walking_time == 1 { "" } else { "s" },
             ),
         }
     }
+
+    /// Describes how long the crossing takes.
+    fn minutes(&self) -> String {
+        let walking_time = self.walking_time();
+        format!(
+            "taking {} minute{}",
+            walking_time,
+            if walking_time == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Formats the elapsed time and the remaining torch time.
+///
+/// Since the elapsed and remaining time always add up to the torch's initial
+/// capacity, padding both to its width keeps all lines of a plan aligned.
+/// This no longer holds when the torch is refuelled.
+fn format_clock(time: u8, remaining_time: u8) -> String {
+    let width = (time as u16 + remaining_time as u16).to_string().len();
+    format!("t={time:>width$} (remaining torch: {remaining_time:>width$})")
+}
+
+impl MarkdownState for WorldState {
+    fn headings(&self) -> Vec<String> {
+        vec![
+            String::from("Left bank"),
+            String::from("Right bank"),
+            String::from("Time"),
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let render = |side: &RiverSideState| match side.is_empty() {
+            true => String::from("nobody"),
+            false => side.to_string(),
+        };
+        vec![
+            render(&self.left),
+            render(&self.right),
+            self.time.to_string(),
+        ]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {
+    fn cell(&self, state: &WorldState) -> String {
+        self.describe(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anytime::branch_and_bound;
+    use crate::history::History;
+    use crate::search::{ida_star, solve, uniform_cost_search};
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn pretty_print_shows_clock() {
+        let state = WorldState::default();
+        let action = WorldAction::new(vec![Person::new(0, 1), Person::new(1, 2)]);
+        let next = action.apply(&state);
+
+        assert_eq!(
+            state.pretty_print(),
+            "t= 0 (remaining torch: 15):  <1> <2> <5> <8> |T===|"
+        );
+        assert_eq!(
+            action.pretty_print(&next),
+            "t= 0 (remaining torch: 15):                   <1> <2> → cross forward, taking 2 minutes"
+        );
+        assert_eq!(
+            next.pretty_print(),
+            "t= 2 (remaining torch: 13):          <5> <8> |===T| <1> <2>"
+        );
+    }
+
+    #[test]
+    fn absent_people_cannot_cross() {
+        let state = WorldState::default();
+        let next = WorldAction::new(vec![Person::new(0, 1)]).apply(&state);
+
+        // Person 0 is on the right now, with the torch; person 1 is left behind.
+        assert!(!WorldAction::new(vec![Person::new(1, 2)]).is_applicable(&next));
+        assert!(!WorldAction::new(vec![Person::new(0, 1), Person::new(1, 2)]).is_applicable(&next));
+        assert!(!WorldAction::new(vec![Person::new(0, 1), Person::new(0, 1)]).is_applicable(&next));
+        assert!(WorldAction::new(vec![Person::new(0, 1)]).is_applicable(&next));
+    }
+
+    #[test]
+    fn sides_list_people_by_walking_time() {
+        let people = vec![
+            Person::named(0, "Carol", 5),
+            Person::new(1, 1),
+            Person::named(2, "Alice", 5),
+        ];
+        let side = RiverSideState {
+            people: people.clone(),
+        };
+
+        assert_eq!(side.to_string(), "1 min, Alice (5 min), Carol (5 min)");
+        assert_eq!(format_people(&side.people), "<1> <Alice:5> <Carol:5>");
+
+        // Sorting for presentation leaves the side untouched.
+        assert_eq!(side.people, people);
+    }
+
+    #[test]
+    fn inverse_undoes_action() {
+        let plan = solve(WorldState::default())
+            .into_plan()
+            .expect("problem is solvable");
+        for (_, state) in plan {
+            for action in state.get_actions() {
+                let next = action.apply(&state);
+                assert!(action.inverse().is_applicable(&next));
+
+                let previous = action.inverse().apply(&next);
+                assert_eq!(previous, state);
+                assert_eq!(previous.time, state.time);
+            }
+        }
+    }
+
+    #[test]
+    fn refuelling_makes_long_walk_solvable() {
+        let problem = BridgeAndTorch::new(2, 10, vec![1, 2, 5, 8]);
+        assert!(solve(problem.initial()).into_plan().is_none());
+
+        let problem = BridgeAndTorch {
+            refuel: Some(10),
+            ..problem
+        };
+        let plan = solve(problem.initial())
+            .into_plan()
+            .expect("problem is solvable when refuelling");
+        assert!(plan.goal().is_goal());
+    }
+
+    #[test]
+    fn refuelled_torch_is_not_mistaken_for_the_initial_one() {
+        // Once 5 crosses and returns, the torch is back on the left with the same people,
+        // but refuelled to 10 rather than burning for 5, and so lets 5 and 8 cross together.
+        let problem = BridgeAndTorch {
+            refuel: Some(10),
+            ..BridgeAndTorch::new(2, 5, vec![5, 8])
+        };
+        let plan = solve(problem.initial())
+            .into_plan()
+            .expect("problem is solvable when refuelling");
+        assert_eq!(plan.len(), 3);
+        assert!(plan
+            .steps()
+            .iter()
+            .all(|(_, state)| state.torch.remaining_time == 10));
+    }
+
+    #[test]
+    fn people_with_equal_walking_times_are_distinguished() {
+        let problem = BridgeAndTorch::with_people(
+            2,
+            15,
+            vec![Person::named(0, "Alice", 5), Person::named(1, "Bob", 5)],
+        );
+        let state = problem.initial();
+
+        let action = WorldAction::new(vec![Person::named(1, "Bob", 5)]);
+        let next = action.apply(&state);
+        assert_eq!(format!("{:?}", next.left), "[<Alice:5>]");
+        assert_eq!(format!("{:?}", next.right), "[<Bob:5>]");
+
+        // Either person crossing alone is a distinct move.
+        assert_eq!(state.get_actions().len(), 3);
+    }
+
+    #[test]
+    fn ordering_constraint_is_respected() {
+        // The slowest person must arrive before the fastest one may cross.
+        let problem = BridgeAndTorch {
+            before: vec![(3, 0)],
+            ..BridgeAndTorch::new(2, 30, vec![1, 2, 5, 8])
+        };
+        let plan = solve(problem.initial())
+            .into_plan()
+            .expect("problem is solvable");
+
+        let first_crossing = plan
+            .steps()
+            .iter()
+            .position(|(action, _)| action.people.iter().any(|p| p.id == 0))
+            .expect("the fastest person crosses");
+        assert!(plan.steps()[..=first_crossing]
+            .iter()
+            .any(|(_, state)| state.right.people.iter().any(|p| p.id == 3)));
+
+        // The classic solution has the two fastest people cross first.
+        let problem = BridgeAndTorch {
+            torch: 15,
+            ..problem
+        };
+        assert!(solve(problem.initial()).into_plan().is_none());
+    }
+
+    #[test]
+    fn wide_bridge_finds_minimum_time() {
+        let cost = |a: &WorldAction, _: &WorldState| a.cost(Objective::Time);
+        let fastest = |problem: BridgeAndTorch| {
+            let plan = quietly(|| {
+                uniform_cost_search(
+                    problem.initial(),
+                    WorldState::is_goal,
+                    cost,
+                    &mut History::new(),
+                )
+            })
+            .into_plan()
+            .expect("problem is solvable");
+            plan.goal().time
+        };
+
+        // Every group of up to three people is tried exactly once.
+        let problem = BridgeAndTorch::new(3, 255, vec![1, 3, 6, 8, 12]);
+        assert_eq!(problem.initial().get_actions().len(), 5 + 10 + 10);
+
+        // The three fastest cross, the fastest returns for the two slowest.
+        assert_eq!(fastest(problem), 6 + 1 + 12);
+
+        // A bridge holding more people than there are lets everyone cross at once.
+        assert_eq!(fastest(BridgeAndTorch::new(10, 255, vec![1, 2, 5])), 5);
+    }
+
+    #[test]
+    fn return_capacity_limits_crossings_back() {
+        let cost = |a: &WorldAction, _: &WorldState| a.cost(Objective::Time);
+        let fastest = |problem: &BridgeAndTorch| {
+            let mut history = History::new();
+            let plan = quietly(|| {
+                uniform_cost_search(problem.initial(), WorldState::is_goal, cost, &mut history)
+            })
+            .into_plan()
+            .expect("problem is solvable");
+            (plan, history.len())
+        };
+
+        // With three people on the right, only one of them may bring the torch back.
+        let uncapped = BridgeAndTorch::new(3, 255, vec![1, 2, 5, 8, 10]);
+        let capped = BridgeAndTorch {
+            return_capacity: Some(1),
+            ..uncapped.clone()
+        };
+        let state = WorldAction::new(capped.people[..3].to_vec()).apply(&capped.initial());
+        assert_eq!(state.get_actions().len(), 3);
+
+        // Sending only the fastest of a group back is never slower and leaves the others
+        // where they need to end up, so the cap prunes the search but never changes the
+        // optimal time; nor does it with a limited or refuelled torch, or an order of arrival.
+        let (plan, capped_explored) = fastest(&capped);
+        let (optimal, uncapped_explored) = fastest(&uncapped);
+        assert_eq!(plan.goal().time, optimal.goal().time);
+        assert!(plan.steps().iter().all(
+            |(action, state)| state.torch.side == RiverSide::Right || action.people.len() == 1
+        ));
+        assert!(capped_explored < uncapped_explored);
+    }
+
+    #[test]
+    fn statistics_report_effort_and_critical_crossing() {
+        let problem = BridgeAndTorch::default();
+        let cost = |a: &WorldAction, _: &WorldState| a.cost(Objective::Time);
+        let plan = quietly(|| {
+            uniform_cost_search(
+                problem.initial(),
+                WorldState::is_goal,
+                cost,
+                &mut History::new(),
+            )
+        })
+        .into_plan()
+        .expect("problem is solvable");
+
+        // Two crossings of the fastest pair, one of the slowest pair and two returns.
+        assert_eq!(person_minutes(&plan), 2 * 2 + 2 * 2 + 2 * 8 + 1 + 2);
+
+        let (step, action) = critical_crossing(&plan).expect("plan is not empty");
+        assert_eq!(step, 3);
+        assert_eq!(action.walking_time(), 8);
+
+        let statistics = problem.statistics(&plan);
+        assert_eq!(statistics[0], ("person-minutes".into(), "27".into()));
+        assert_eq!(
+            statistics[1],
+            (
+                "critical crossing".into(),
+                "step 3, [<5>, <8>] taking 8 minutes".into()
+            )
+        );
+    }
+
+    #[test]
+    fn ida_star_finds_optimal_time_keeping_only_the_plan() {
+        let problem = BridgeAndTorch::new(2, 255, vec![1, 2, 5, 8, 10]);
+        let cost = |a: &WorldAction, _: &WorldState| a.cost(Objective::Time);
+        let total = |plan: &Plan<WorldState, WorldAction>| {
+            plan.steps().iter().map(|(a, s)| cost(a, s)).sum::<u32>()
+        };
+
+        let mut uniform = History::new();
+        let expected = quietly(|| {
+            uniform_cost_search(problem.initial(), WorldState::is_goal, cost, &mut uniform)
+        })
+        .into_plan()
+        .expect("problem is solvable");
+
+        let mut iterative = History::new();
+        let plan = quietly(|| {
+            ida_star(
+                problem.initial(),
+                WorldState::is_goal,
+                cost,
+                WorldState::heuristic,
+                &mut iterative,
+            )
+        })
+        .into_plan()
+        .expect("problem is solvable");
+
+        assert_eq!(total(&plan), total(&expected));
+        assert!(plan.goal().is_goal());
+        assert_eq!(iterative.len(), plan.len() + 1);
+        assert!(iterative.len() < uniform.len());
+    }
+
+    #[test]
+    fn branch_and_bound_finds_optimal_time_with_fewer_states() {
+        let problem = BridgeAndTorch::new(2, 255, vec![1, 2, 5, 8, 10, 12, 15]);
+        let cost = |a: &WorldAction, _: &WorldState| a.cost(Objective::Time);
+
+        let mut uniform = History::new();
+        let expected = quietly(|| {
+            uniform_cost_search(problem.initial(), WorldState::is_goal, cost, &mut uniform)
+        })
+        .into_plan()
+        .expect("problem is solvable");
+
+        let mut bounded = History::new();
+        let plan = quietly(|| {
+            branch_and_bound(
+                problem.initial(),
+                WorldState::is_goal,
+                cost,
+                WorldState::lower_bound,
+                usize::MAX,
+                &mut bounded,
+                |_, _| {},
+            )
+        })
+        .into_plan()
+        .expect("problem is solvable");
+
+        assert_eq!(plan.goal().time, expected.goal().time);
+        for (_, state) in expected.clone() {
+            assert!(state.lower_bound() <= (expected.goal().time - state.time) as u32);
+        }
+        assert!(bounded.len() < uniform.len());
+    }
 }
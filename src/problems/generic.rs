@@ -0,0 +1,542 @@
+//! A crossing puzzle defined by a [`Spec`] rather than in code, e.g. read from a JSON file.
+
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::humans_and_zombies::{Boat, RiverBank};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The description of a crossing puzzle: kinds of entities that have to cross the river,
+/// a boat taking a limited number of them at once, and pairs of kinds that cannot be
+/// left unattended together.
+///
+/// As JSON, the Wolf, Goat and Cabbage problem reads:
+///
+/// ```json
+/// {
+///   "entities": [["farmer", 1], ["wolf", 1], ["goat", 1], ["cabbage", 1]],
+///   "boat": 2,
+///   "forbidden": [["wolf", "goat"], ["goat", "cabbage"]],
+///   "guardian": "farmer"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spec {
+    /// The kinds of entities, each with the number of them on the left river bank.
+    pub entities: Vec<(String, u8)>,
+    /// The capacity of the boat, i.e. how many entities it holds at once.
+    pub boat: u8,
+    /// The pairs of kinds that cannot be together on a river bank or in the boat
+    /// unless a guardian is there as well.
+    #[serde(default)]
+    pub forbidden: Vec<(String, String)>,
+    /// The kind of the entities attending the others, one of which has to row the boat.
+    /// Without guardians, anyone may row the boat, but the forbidden pairs are never
+    /// allowed together.
+    #[serde(default)]
+    pub guardian: Option<String>,
+}
+
+/// A crossing puzzle following a [`Spec`].
+#[derive(Debug, Clone)]
+pub struct Generic {
+    /// The rules of the puzzle, with every kind identified by its index.
+    rules: Arc<Rules>,
+}
+
+/// The rules of a [`Spec`], with every kind identified by its index.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+struct Rules {
+    /// The names of the kinds.
+    names: Vec<String>,
+    /// The number of entities of every kind.
+    counts: Vec<u8>,
+    /// The capacity of the boat.
+    boat: u8,
+    /// The pairs of kinds that cannot be left unattended together.
+    forbidden: Vec<(usize, usize)>,
+    /// The kind attending the others and rowing the boat, if any.
+    guardian: Option<usize>,
+}
+
+/// Describes the world state.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The number of entities of every kind on the left river bank.
+    pub left: Vec<u8>,
+    /// The number of entities of every kind on the right river bank.
+    pub right: Vec<u8>,
+    /// The boat.
+    pub boat: Boat,
+    /// The rules of the puzzle.
+    rules: Arc<Rules>,
+}
+
+/// An action to apply.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorldAction {
+    /// The number of entities of every kind in the boat.
+    pub counts: Vec<u8>,
+}
+
+impl Spec {
+    /// Parses a spec from JSON.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid spec: {e}"))
+    }
+
+    /// Creates the spec of a farmer bringing items across, where every item would eat the
+    /// next one; the boat holds the farmer and one item.
+    pub fn food_chain(items: &[&str]) -> Self {
+        let entities = std::iter::once("farmer")
+            .chain(items.iter().copied())
+            .map(|name| (name.to_string(), 1))
+            .collect();
+        let forbidden = items
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect();
+        Self {
+            entities,
+            boat: 2,
+            forbidden,
+            guardian: Some(String::from("farmer")),
+        }
+    }
+
+    /// Creates the spec of the Wolf, Goat and Cabbage problem.
+    pub fn wolf_goat_cabbage() -> Self {
+        Self::food_chain(&["wolf", "goat", "cabbage"])
+    }
+
+    /// Creates the spec of the Fox, Goose and Beans problem.
+    pub fn fox_goose_beans() -> Self {
+        Self::food_chain(&["fox", "goose", "beans"])
+    }
+}
+
+impl Generic {
+    /// Creates a new problem following the spec.
+    ///
+    /// Fails if the spec refers to unknown kinds, names a kind twice or has no room
+    /// in the boat.
+    pub fn new(spec: &Spec) -> Result<Self, String> {
+        let names = spec
+            .entities
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect_vec();
+        if let Some(name) = names.iter().duplicates().next() {
+            return Err(format!("The kind {name} is listed more than once."));
+        }
+
+        let kind = |name: &str| {
+            names
+                .iter()
+                .position(|known| known == name)
+                .ok_or_else(|| format!("Unknown kind: {name}"))
+        };
+        let forbidden = spec
+            .forbidden
+            .iter()
+            .map(|(a, b)| Ok((kind(a)?, kind(b)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let guardian = spec.guardian.as_deref().map(kind).transpose()?;
+
+        if spec.boat == 0 {
+            return Err(String::from("The boat must hold at least one entity."));
+        }
+
+        Ok(Self {
+            rules: Arc::new(Rules {
+                names,
+                counts: spec.entities.iter().map(|(_, count)| *count).collect(),
+                boat: spec.boat,
+                forbidden,
+                guardian,
+            }),
+        })
+    }
+}
+
+impl Default for Generic {
+    fn default() -> Self {
+        Self::new(&Spec::wolf_goat_cabbage()).expect("spec is valid")
+    }
+}
+
+impl Problem for Generic {
+    type State = WorldState;
+
+    /// Builds the initial world state with everyone on the left river bank.
+    fn initial(&self) -> WorldState {
+        WorldState {
+            left: self.rules.counts.clone(),
+            right: vec![0; self.rules.counts.len()],
+            boat: Boat::new(self.rules.boat, RiverBank::Left),
+            rules: Arc::clone(&self.rules),
+        }
+    }
+
+    /// Supports minimizing the number of trips and the number of entities moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Moves]
+    }
+}
+
+impl Rules {
+    /// Determines whether the specified numbers of entities of every kind may be together,
+    /// i.e. whether a guardian attends them or no forbidden pair is among them.
+    fn is_safe(&self, counts: &[u8]) -> bool {
+        if self.guardian.is_some_and(|guardian| counts[guardian] > 0) {
+            return true;
+        }
+
+        self.forbidden
+            .iter()
+            .all(|&(a, b)| counts[a] == 0 || counts[b] == 0)
+    }
+
+    /// Renders the specified numbers of entities of every kind, e.g. `farmer, 2×goat`.
+    fn render(&self, counts: &[u8]) -> String {
+        let present = counts
+            .iter()
+            .zip(&self.names)
+            .filter(|(count, _)| **count > 0)
+            .map(|(count, name)| match count {
+                1 => name.clone(),
+                _ => format!("{count}×{name}"),
+            })
+            .join(", ");
+
+        if present.is_empty() {
+            String::from("empty")
+        } else {
+            present
+        }
+    }
+}
+
+impl WorldState {
+    /// Unpacks the world state into a tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there(&self) -> (&[u8], &[u8]) {
+        match self.boat.bank {
+            RiverBank::Left => (&self.left, &self.right),
+            RiverBank::Right => (&self.right, &self.left),
+        }
+    }
+
+    /// Unpacks the world state into a (mutable) tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there_mut(&mut self) -> (&mut Vec<u8>, &mut Vec<u8>) {
+        match self.boat.bank {
+            RiverBank::Left => (&mut self.left, &mut self.right),
+            RiverBank::Right => (&mut self.right, &mut self.left),
+        }
+    }
+
+    /// Renders a river bank, marking it if the boat is at it.
+    fn render_bank(&self, bank: RiverBank) -> String {
+        let counts = match bank {
+            RiverBank::Left => &self.left,
+            RiverBank::Right => &self.right,
+        };
+
+        let entities = self.rules.render(counts);
+        if self.boat.bank == bank {
+            format!("{entities} (boat)")
+        } else {
+            entities
+        }
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        Generic::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ left: {{ {} }}, right: {{ {} }}, boat: {:?} }}",
+            self.rules.render(&self.left),
+            self.rules.render(&self.right),
+            self.boat
+        )
+    }
+}
+
+impl WorldAction {
+    pub const fn new(counts: Vec<u8>) -> Self {
+        Self { counts }
+    }
+
+    /// Gets the number of entities in the boat.
+    pub fn len(&self) -> u32 {
+        self.counts.iter().map(|&count| count as u32).sum()
+    }
+
+    /// Determines whether nobody is in the boat.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Debug for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.counts)
+    }
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = (Vec<u8>, RiverBank);
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // Everyone is on the right river bank.
+        self.left.iter().all(|&count| count == 0)
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // Nobody appeared or vanished, and nobody is left unattended with the wrong company.
+        let conserved = (self.left.iter().zip(&self.right))
+            .zip(&self.rules.counts)
+            .all(|((left, right), count)| left + right == *count);
+        conserved && self.rules.is_safe(&self.left) && self.rules.is_safe(&self.right)
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let (here, _) = self.here_there();
+
+        // Every way to pick some of the entities here, as far as the boat holds them.
+        let capacity = self.boat.capacity;
+        let loads = here
+            .iter()
+            .map(|&available| 0..=available.min(capacity))
+            .multi_cartesian_product();
+
+        let mut actions = Actions::new();
+        for load in loads {
+            let action = WorldAction::new(load);
+            if action.is_applicable(self) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        (self.left.clone(), self.boat.bank)
+    }
+
+    /// Estimates the remaining cost as the number of entities still on the left river bank.
+    fn heuristic(&self) -> u32 {
+        self.left.iter().map(|&count| count as u32).sum()
+    }
+
+    /// Counts the entities of every kind on both river banks.
+    fn total_entities(&self) -> Vec<u32> {
+        self.left
+            .iter()
+            .zip(&self.right)
+            .map(|(&left, &right)| left as u32 + right as u32)
+            .collect()
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        let (here, there) = state.here_there();
+        let rules = &state.rules;
+
+        // Someone has to row the boat, but it can carry no more than its capacity.
+        if self.is_empty() || self.len() > state.boat.capacity as u32 {
+            return false;
+        }
+
+        // If there are guardians, one of them has to row the boat.
+        if rules
+            .guardian
+            .is_some_and(|guardian| self.counts[guardian] == 0)
+        {
+            return false;
+        }
+
+        // We cannot move more entities than there are on the current bank.
+        if self.counts.len() != here.len()
+            || self
+                .counts
+                .iter()
+                .zip(here)
+                .any(|(moved, &present)| *moved > present)
+        {
+            return false;
+        }
+
+        // Nobody may be left unattended with the wrong company, neither in the boat
+        // nor on either bank after the action.
+        let new_here = here
+            .iter()
+            .zip(&self.counts)
+            .map(|(h, m)| h - m)
+            .collect_vec();
+        let new_there = there
+            .iter()
+            .zip(&self.counts)
+            .map(|(t, m)| t + m)
+            .collect_vec();
+        rules.is_safe(&self.counts) && rules.is_safe(&new_here) && rules.is_safe(&new_there)
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        let (here, there) = state.here_there_mut();
+        for (kind, &count) in self.counts.iter().enumerate() {
+            here[kind] -= count;
+            there[kind] += count;
+        }
+        state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the same entities crossing back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the right bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == RiverBank::Right
+    }
+
+    /// Gets the cost of this action, where moves count every entity in the boat.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Moves => self.len(),
+            _ => 1,
+        }
+    }
+
+    /// Describes the entities crossing towards the bank the boat arrived at.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        let names = state.rules.names.iter().map(String::as_str);
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            ActionSummary::repeated(self.counts.iter().copied().zip(names)),
+        )
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        format!(
+            "left bank: {}; right bank: {}",
+            self.render_bank(RiverBank::Left),
+            self.render_bank(RiverBank::Right)
+        )
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, state: &WorldState) -> String {
+        // Note the conditions here are flipped as this represent the state
+        // after the action was applied.
+        let who = state.rules.render(&self.counts);
+        match state.boat.bank {
+            RiverBank::Right => format!(" → {who} cross forward"),
+            RiverBank::Left => format!(" ← {who} return"),
+        }
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.render_bank(RiverBank::Left),
+            self.render_bank(RiverBank::Right),
+        ]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn classic_puzzles_take_seven_crossings() {
+        for spec in [Spec::wolf_goat_cabbage(), Spec::fox_goose_beans()] {
+            let problem = Generic::new(&spec).expect("spec is valid");
+            let plan = search(problem.initial()).expect("problem is solvable");
+            assert_eq!(plan.len(), 7);
+        }
+    }
+
+    #[test]
+    fn anyone_rows_without_guardians() {
+        let spec = Spec {
+            entities: vec![(String::from("goat"), 3)],
+            boat: 2,
+            forbidden: Vec::new(),
+            guardian: None,
+        };
+        let problem = Generic::new(&spec).expect("spec is valid");
+        let plan = search(problem.initial()).expect("problem is solvable");
+        assert_eq!(plan.len(), 3);
+
+        // Without guardians, a forbidden pair may never be together.
+        let spec = Spec {
+            entities: vec![(String::from("wolf"), 1), (String::from("goat"), 1)],
+            forbidden: vec![(String::from("wolf"), String::from("goat"))],
+            ..spec
+        };
+        let problem = Generic::new(&spec).expect("spec is valid");
+        assert!(!problem.initial().is_valid());
+    }
+
+    #[test]
+    fn spec_is_read_from_json() {
+        let json = r#"{
+            "entities": [["farmer", 1], ["wolf", 1], ["goat", 1], ["cabbage", 1]],
+            "boat": 2,
+            "forbidden": [["wolf", "goat"], ["goat", "cabbage"]],
+            "guardian": "farmer"
+        }"#;
+        assert_eq!(Spec::from_json(json), Ok(Spec::wolf_goat_cabbage()));
+
+        let unknown = Spec {
+            guardian: Some(String::from("shepherd")),
+            ..Spec::wolf_goat_cabbage()
+        };
+        assert!(Generic::new(&unknown).is_err());
+    }
+}
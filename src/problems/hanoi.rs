@@ -0,0 +1,273 @@
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The number of pegs.
+pub const PEGS: usize = 3;
+
+/// The maximum number of disks, limited by the width of the hash.
+pub const MAX_DISKS: u8 = 32;
+
+/// The Tower of Hanoi.
+///
+/// Not a river crossing at all, but a demonstration of the search being domain-agnostic:
+/// with `n` disks, the optimal solution takes `2ⁿ - 1` moves.
+#[derive(Debug, Clone)]
+pub struct Hanoi {
+    /// The number of disks, initially stacked on the first peg.
+    pub disks: u8,
+}
+
+/// Describes the world state.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The disks on each peg from bottom to top, where larger numbers are larger disks.
+    pub pegs: [Vec<u8>; PEGS],
+}
+
+/// An action to apply, i.e. moving the top disk of one peg onto another.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WorldAction {
+    /// The index of the peg to take the disk from.
+    pub from: usize,
+    /// The index of the peg to put the disk on.
+    pub to: usize,
+}
+
+impl Hanoi {
+    /// Creates a new problem from the number of disks.
+    pub const fn new(disks: u8) -> Self {
+        debug_assert!(disks <= MAX_DISKS);
+        Self { disks }
+    }
+}
+
+impl Default for Hanoi {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl Problem for Hanoi {
+    type State = WorldState;
+
+    /// Builds the initial world state with all disks on the first peg.
+    fn initial(&self) -> WorldState {
+        WorldState::new([(1..=self.disks).rev().collect(), Vec::new(), Vec::new()])
+    }
+}
+
+impl WorldState {
+    /// Creates a new problem state from the disks on each peg.
+    pub const fn new(pegs: [Vec<u8>; PEGS]) -> Self {
+        Self { pegs }
+    }
+
+    /// Gets the total number of disks.
+    fn disks(&self) -> usize {
+        self.pegs.iter().map(Vec::len).sum()
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        Hanoi::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.pegs)
+    }
+}
+
+impl WorldAction {
+    pub const fn new(from: usize, to: usize) -> Self {
+        Self { from, to }
+    }
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = u64;
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // All disks are on the last peg.
+        self.pegs[..PEGS - 1].iter().all(Vec::is_empty)
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // No disk lies on a smaller one.
+        self.pegs
+            .iter()
+            .all(|peg| peg.windows(2).all(|pair| pair[0] > pair[1]))
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        (0..PEGS)
+            .cartesian_product(0..PEGS)
+            .map(|(from, to)| WorldAction::new(from, to))
+            .filter(|action| action.is_applicable(self))
+            .collect()
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        // Every disk is on exactly one peg, which takes two bits to encode.
+        self.pegs
+            .iter()
+            .enumerate()
+            .flat_map(|(peg, disks)| disks.iter().map(move |&disk| (peg, disk)))
+            .fold(0, |hash, (peg, disk)| {
+                hash | (peg as u64) << (2 * (disk as u64 - 1))
+            })
+    }
+
+    /// Estimates the remaining cost as the number of disks not yet on the last peg.
+    fn heuristic(&self) -> u32 {
+        self.pegs[..PEGS - 1].iter().map(Vec::len).sum::<usize>() as u32
+    }
+
+    /// Bounds the remaining moves by the [heuristic](Self::heuristic), as every disk not yet on
+    /// the last peg has to be moved at least once.
+    fn admissible_heuristic(&self, _objective: Objective) -> u32 {
+        self.heuristic()
+    }
+
+    /// Counts the disks.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![self.disks() as u32]
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        if self.from >= PEGS || self.to >= PEGS || self.from == self.to {
+            return false;
+        }
+
+        // A disk may only be put on an empty peg or a larger disk.
+        match (state.pegs[self.from].last(), state.pegs[self.to].last()) {
+            (Some(disk), Some(top)) => disk < top,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        let disk = state.pegs[self.from].pop().expect("peg is not empty");
+        state.pegs[self.to].push(disk);
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one, i.e. moving the disk back.
+    fn inverse(&self) -> Self {
+        WorldAction::new(self.to, self.from)
+    }
+
+    /// Describes the disk moved from one peg to another.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        // The state is the one after the action was applied.
+        let disk = state.pegs[self.to].last().expect("disk was moved here");
+        ActionSummary::new(
+            format!("peg {} to peg {}", self.from + 1, self.to + 1),
+            vec![format!("disk {disk}")],
+        )
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        let width = self.disks().to_string().len();
+        let peg_width = self.disks() * (width + 1);
+        let line = self
+            .pegs
+            .iter()
+            .map(|disks| {
+                let disks = disks.iter().map(|disk| format!("{disk:>width$}")).join(" ");
+                format!("|{disks:<peg_width$}")
+            })
+            .join(" ");
+        line.trim_end().into()
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, state: &WorldState) -> String {
+        // The state is the one after the action was applied.
+        let disk = state.pegs[self.to].last().expect("disk was moved here");
+        format!(
+            " → move disk {disk} from peg {} to peg {}",
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn headings(&self) -> Vec<String> {
+        (1..=PEGS).map(|peg| format!("Peg {peg}")).collect()
+    }
+
+    fn cells(&self) -> Vec<String> {
+        self.pegs
+            .iter()
+            .map(|disks| match disks.is_empty() {
+                true => String::from("empty"),
+                false => disks.iter().join(" "),
+            })
+            .collect()
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn breadth_first_search_finds_optimal_solution() {
+        for disks in 1..=6 {
+            let plan = search(Hanoi::new(disks).initial()).expect("problem is solvable");
+            assert_eq!(plan.len(), (1 << disks) - 1);
+        }
+    }
+
+    #[test]
+    fn larger_disk_may_not_be_put_on_smaller_one() {
+        let state = WorldState::new([vec![3], vec![2], vec![1]]);
+        assert!(!WorldAction::new(0, 1).is_applicable(&state));
+        assert!(WorldAction::new(1, 0).is_applicable(&state));
+        assert!(WorldAction::new(2, 1).is_applicable(&state));
+        assert!(!WorldAction::new(1, 1).is_applicable(&state));
+    }
+
+    #[test]
+    fn larger_disk_on_smaller_one_is_invalid() {
+        assert!(WorldState::new([vec![3, 2], vec![1], vec![]]).is_valid());
+        assert!(!WorldState::new([vec![2, 3], vec![1], vec![]]).is_valid());
+    }
+}
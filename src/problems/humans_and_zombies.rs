@@ -1,9 +1,43 @@
-use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
-use crate::search::{Action, State};
-use std::fmt::{Debug, Formatter};
+use crate::heuristics::crossings_remaining;
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::plan::Plan;
+use crate::pretty_print::{
+    display_width, pad_left, PrettyPrintAction, PrettyPrintState, RenderStyle,
+};
+use crate::problems::Problem;
+use crate::random::Rng;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, search, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use crate::verbosity::quietly;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+
+/// The Humans and Zombies problem.
+#[derive(Debug, Clone)]
+pub struct HumansAndZombies {
+    /// The number of humans on the starting river bank.
+    pub humans: u8,
+    /// The number of zombies on the starting river bank.
+    pub zombies: u8,
+    /// The river bank everyone starts on, along with the boat.
+    pub start: RiverBank,
+    /// The capacity of the boat.
+    pub boat: u8,
+    /// Whether a human must be on the boat to row it.
+    pub require_rower: bool,
+    /// Whether the boat may drift across with nobody aboard.
+    pub allow_empty_crossing: bool,
+    /// The rule deciding whether the humans on a river bank are safe.
+    pub safety: SafetyRule,
+    /// How long a crossing takes.
+    pub rowing: RowingCost,
+}
 
 /// Describes the world state.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct WorldState {
     /// The left river bank.
     pub left: RiverBankState,
@@ -11,9 +45,41 @@ pub struct WorldState {
     pub right: RiverBankState,
     /// The boat.
     pub boat: Boat,
+    /// The river bank everyone has to get to.
+    pub target: RiverBank,
+    /// Whether a human must be on the boat to row it.
+    pub require_human_rower: bool,
+    /// Whether the boat may drift across with nobody aboard.
+    pub allow_empty_crossing: bool,
+    /// The rule deciding whether the humans on a river bank are safe.
+    pub safety: SafetyRule,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+/// Decides whether the humans on a river bank are safe from the zombies.
+/// A bank without humans is always safe.
+#[derive(
+    Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub enum SafetyRule {
+    /// Zombies must not outnumber the humans.
+    #[default]
+    StrictMajority,
+    /// Zombies may outnumber the humans by at most the specified number.
+    Margin(u8),
+}
+
+/// Determines how long a crossing takes, which is what [`Objective::Time`] minimizes.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum RowingCost {
+    /// Every crossing takes the same time, so there is no time to minimize other than the trips.
+    #[default]
+    Constant,
+    /// Every crossing takes one unit of time, plus one unit per entity aboard, as heavier boats
+    /// row slower.
+    Linear,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum RiverBank {
     /// The left river bank.
     Left,
@@ -21,7 +87,7 @@ pub enum RiverBank {
     Right,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Boat {
     /// The capacity of the boat.
     pub capacity: u8,
@@ -30,7 +96,7 @@ pub struct Boat {
 }
 
 /// Describes the state on a river bank.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RiverBankState {
     /// The number of humans on this bank.
     pub humans: u8,
@@ -39,7 +105,7 @@ pub struct RiverBankState {
 }
 
 /// An action to apply.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldAction {
     /// How many humans to move.
     pub humans: u8,
@@ -47,10 +113,105 @@ pub struct WorldAction {
     pub zombies: u8,
 }
 
+impl HumansAndZombies {
+    /// Creates a new problem from the number of humans and zombies and the boat capacity.
+    pub const fn new(humans: u8, zombies: u8, boat: u8) -> Self {
+        Self {
+            humans,
+            zombies,
+            boat,
+            start: RiverBank::Left,
+            require_rower: false,
+            allow_empty_crossing: false,
+            safety: SafetyRule::StrictMajority,
+            rowing: RowingCost::Constant,
+        }
+    }
+}
+
+impl Default for HumansAndZombies {
+    fn default() -> Self {
+        Self::new(3, 3, 2)
+    }
+}
+
+impl Problem for HumansAndZombies {
+    type State = WorldState;
+
+    /// Builds the initial world state with everyone and the boat on the starting river bank.
+    fn initial(&self) -> WorldState {
+        let everyone = RiverBankState::new(self.humans, self.zombies);
+        let nobody = RiverBankState::new(0, 0);
+        let (left, right) = match self.start {
+            RiverBank::Left => (everyone, nobody),
+            RiverBank::Right => (nobody, everyone),
+        };
+        let boat = Boat::new(self.boat, self.start);
+        let mut state = WorldState::new(left, right, boat);
+        state.target = self.start.switch_bank();
+        state.require_human_rower = self.require_rower;
+        state.allow_empty_crossing = self.allow_empty_crossing;
+        state.safety = self.safety;
+        state
+    }
+
+    /// Supports minimizing the number of trips and the number of entities moved, as well as
+    /// the time taken if crossings take longer the more entities are aboard.
+    fn objectives(&self) -> &'static [Objective] {
+        match self.rowing {
+            RowingCost::Constant => &[Objective::Trips, Objective::Moves],
+            RowingCost::Linear => &[Objective::Trips, Objective::Time, Objective::Moves],
+        }
+    }
+
+    /// Measures plans by the number of trips, or by the time taken if crossings take longer
+    /// the more entities are aboard.
+    fn natural_objective(&self) -> Objective {
+        match self.rowing {
+            RowingCost::Constant => Objective::Trips,
+            RowingCost::Linear => Objective::Time,
+        }
+    }
+
+    /// Prefers the plan keeping the humans safer, i.e. the one the zombies come less close to
+    /// outnumbering the humans in; see [`closeness`].
+    fn compare_plans(
+        &self,
+        a: &Plan<WorldState, WorldAction>,
+        b: &Plan<WorldState, WorldAction>,
+    ) -> Ordering {
+        closeness(a).cmp(&closeness(b))
+    }
+}
+
+/// Gets how close the zombies come to outnumbering the humans throughout the plan, as the
+/// largest number of zombies beyond the humans sharing a river bank with them; e.g. `0` if
+/// the zombies at most match the humans somewhere, or `-1` if they always fall short by one.
+///
+/// Banks without humans put nobody at risk and are ignored; [`None`] if no human ever shares
+/// a river bank with a zombie, which is the safest plan of all.
+pub fn closeness(plan: &Plan<WorldState, WorldAction>) -> Option<i16> {
+    let states = std::iter::once(plan.initial()).chain(plan.steps().iter().map(|(_, s)| s));
+    states
+        .flat_map(|state| [&state.left, &state.right])
+        .filter(|bank| bank.humans > 0 && bank.zombies > 0)
+        .map(|bank| bank.zombies as i16 - bank.humans as i16)
+        .max()
+}
+
 impl WorldState {
-    /// Creates a new problem state from the left and right river bank states.
+    /// Creates a new problem state from the left and right river bank states,
+    /// with everyone having to get to the right river bank.
     pub const fn new(left: RiverBankState, right: RiverBankState, boat: Boat) -> Self {
-        Self { left, right, boat }
+        Self {
+            left,
+            right,
+            boat,
+            target: RiverBank::Right,
+            require_human_rower: false,
+            allow_empty_crossing: false,
+            safety: SafetyRule::StrictMajority,
+        }
     }
 
     /// Unpacks the world state into a tuple of "this river bank" (i.e.
@@ -73,19 +234,98 @@ impl WorldState {
 
     /// Gets the river bank the boat is at.
     pub fn boat_bank(&self) -> &RiverBankState {
-        match self.boat.bank {
+        self.bank(self.boat.bank)
+    }
+
+    /// Gets the state of the specified river bank.
+    pub fn bank(&self, bank: RiverBank) -> &RiverBankState {
+        match bank {
             RiverBank::Left => &self.left,
             RiverBank::Right => &self.right,
         }
     }
+
+    /// Gets the river bank everyone has to leave, i.e. the one opposite of the target.
+    fn origin(&self) -> &RiverBankState {
+        self.bank(self.target.switch_bank())
+    }
+
+    /// Gets the total number of humans or zombies, whichever is higher.
+    /// This determines the width required to render a river bank.
+    fn max_population(&self) -> usize {
+        let humans = self.left.humans as usize + self.right.humans as usize;
+        let zombies = self.left.zombies as usize + self.right.zombies as usize;
+        humans.max(zombies)
+    }
+
+    /// Gets every load the boat can carry from the bank it is at, whether it is safe or not.
+    ///
+    /// The loads are listed in a fixed order that breaks ties between equally good plans:
+    /// larger groups come first, and groups of the same size are ordered by the number of
+    /// humans aboard, fewest first; if allowed, the empty boat drifting across comes last.
+    /// Since a breadth-first search keeps the first way it finds to reach a state, it always
+    /// returns the same canonical plan.
+    fn loads(&self) -> Actions<WorldAction> {
+        let mut loads = Actions::with_capacity(5);
+
+        let bank = self.boat_bank();
+        let most = self
+            .boat
+            .capacity
+            .min(bank.humans.saturating_add(bank.zombies));
+
+        for size in (1..=most).rev() {
+            for h in 0..=size.min(bank.humans) {
+                // Whoever is not human on the boat is a zombie.
+                let z = size - h;
+                if z <= bank.zombies {
+                    loads.push(WorldAction::new(h, z));
+                }
+            }
+        }
+
+        if self.allow_empty_crossing {
+            loads.push(WorldAction::empty());
+        }
+
+        loads
+    }
+
+    /// Gets the number of columns required to render a river bank in the specified style.
+    fn bank_width(&self, style: &RenderStyle) -> usize {
+        let symbol = display_width(&style.human).max(display_width(&style.zombie));
+        2 * symbol * self.max_population() + 1
+    }
 }
 
 impl Default for WorldState {
     fn default() -> Self {
-        let left = RiverBankState::new(3, 3);
-        let right = RiverBankState::new(0, 0);
-        let boat = Boat::new(2, RiverBank::Left);
-        WorldState::new(left, right, boat)
+        HumansAndZombies::default().initial()
+    }
+}
+
+/// Generates a random initial world state with at most `max_population` humans and zombies each
+/// that is guaranteed to be solvable, together with the length of its optimal plan.
+///
+/// Instances are drawn until [`search`] finds a plan for one of them,
+/// so the same `seed` always produces the same instance.
+pub fn random_solvable(seed: u64, max_population: u8) -> (WorldState, usize) {
+    assert!(max_population > 0, "population must be positive");
+
+    let mut rng = Rng::seed_from_u64(seed);
+    let max_population = max_population as usize;
+    loop {
+        let humans = 1 + rng.below(max_population) as u8;
+        let zombies = 1 + rng.below(max_population) as u8;
+
+        // A boat for one can never bring more than one across, since it has to be brought back,
+        // while large boats make for trivial instances.
+        let boat = 2 + rng.below(3) as u8;
+
+        let initial = HumansAndZombies::new(humans, zombies, boat).initial();
+        if let Some(plan) = quietly(|| search(initial.clone())) {
+            return (initial, plan.len());
+        }
     }
 }
 
@@ -99,6 +339,26 @@ impl Debug for WorldState {
     }
 }
 
+/// Formats the state as prose, naming the river bank the boat is at.
+///
+/// ```
+/// use river_crossing::problems::humans_and_zombies::WorldState;
+///
+/// assert_eq!(
+///     WorldState::default().to_string(),
+///     "left bank: 3 humans and 3 zombies; right bank: nobody; boat on the left"
+/// );
+/// ```
+impl Display for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "left bank: {}; right bank: {}; boat on the {}",
+            self.left, self.right, self.boat.bank
+        )
+    }
+}
+
 impl Boat {
     /// Creates a new river bank state from the number of humans and zombies.
     pub const fn new(capacity: u8, bank: RiverBank) -> Self {
@@ -130,11 +390,119 @@ impl Debug for RiverBankState {
     }
 }
 
+/// Formats the river bank as a list of its population.
+///
+/// ```
+/// use river_crossing::problems::humans_and_zombies::RiverBankState;
+///
+/// assert_eq!(RiverBankState::new(2, 1).to_string(), "2 humans and 1 zombie");
+/// assert_eq!(RiverBankState::new(0, 0).to_string(), "nobody");
+/// ```
+impl Display for RiverBankState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_list(self.humans, self.zombies))
+    }
+}
+
 impl WorldAction {
     pub fn new(humans: u8, zombies: u8) -> Self {
         debug_assert_ne!(zombies + humans, 0);
         Self { zombies, humans }
     }
+
+    /// Creates the action of the boat drifting across with nobody aboard, which is only
+    /// applicable if [empty crossings are allowed](WorldState::allow_empty_crossing).
+    pub const fn empty() -> Self {
+        Self {
+            humans: 0,
+            zombies: 0,
+        }
+    }
+
+    /// Determines whether nobody is aboard, i.e. whether the boat drifts across.
+    pub const fn is_empty(&self) -> bool {
+        self.humans == 0 && self.zombies == 0
+    }
+
+    /// Checks whether the action is applicable in the specified world state,
+    /// telling which rule it breaks if it is not.
+    fn check(&self, state: &WorldState) -> Result<(), Rejection> {
+        let (here, there) = state.here_there();
+
+        // The boat only crosses empty if it may drift; then, nobody has to row it.
+        if self.is_empty() {
+            return if state.allow_empty_crossing {
+                Ok(())
+            } else {
+                Err(Rejection::Empty)
+            };
+        }
+
+        // If required, a human must row the boat.
+        if state.require_human_rower && self.humans == 0 {
+            return Err(Rejection::NoRower);
+        }
+
+        // We cannot have more zombies than humans on the boat.
+        if self.humans > 0 && self.zombies > self.humans {
+            return Err(Rejection::OutnumberedInBoat);
+        }
+
+        // We cannot move more people than there are on the current bank.
+        if here.humans < self.humans || here.zombies < self.zombies {
+            return Err(Rejection::NotEnoughPeople(state.boat.bank));
+        }
+
+        // On either river bank, after the action, the humans must be safe.
+        let new_humans_here = here.humans - self.humans;
+        let new_zombies_here = here.zombies - self.zombies;
+        if !state.safety.is_safe(new_humans_here, new_zombies_here) {
+            return Err(Rejection::Outnumbered(state.boat.bank));
+        }
+
+        let new_humans_there = there.humans + self.humans;
+        let new_zombies_there = there.zombies + self.zombies;
+        if !state.safety.is_safe(new_humans_there, new_zombies_there) {
+            return Err(Rejection::Outnumbered(state.boat.bank.switch_bank()));
+        }
+
+        Ok(())
+    }
+}
+
+/// The rule an action breaks, as told by [`WorldAction::check`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Rejection {
+    /// Nobody would be aboard, though the boat may not drift across.
+    Empty,
+    /// Nobody would row the boat, though a human is required to.
+    NoRower,
+    /// The humans on the boat would be outnumbered.
+    OutnumberedInBoat,
+    /// There are fewer people on the specified river bank than would cross.
+    NotEnoughPeople(RiverBank),
+    /// The humans on the specified river bank would be outnumbered.
+    Outnumbered(RiverBank),
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::Empty => {
+                f.write_str("would leave the boat to drift across with nobody aboard")
+            }
+            Rejection::NoRower => f.write_str("would leave the boat without a human to row it"),
+            Rejection::OutnumberedInBoat => {
+                f.write_str("would leave zombies outnumbering humans in the boat")
+            }
+            Rejection::NotEnoughPeople(bank) => {
+                write!(f, "would take more people than there are on the {bank}")
+            }
+            Rejection::Outnumbered(bank) => {
+                write!(f, "would leave zombies outnumbering humans on the {bank}")
+            }
+        }
+    }
 }
 
 impl Debug for WorldAction {
@@ -143,6 +511,64 @@ impl Debug for WorldAction {
     }
 }
 
+/// Formats the action as a list of the people on the boat.
+///
+/// ```
+/// use river_crossing::problems::humans_and_zombies::WorldAction;
+///
+/// assert_eq!(WorldAction::new(1, 1).to_string(), "1 human and 1 zombie");
+/// ```
+impl Display for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_list(self.humans, self.zombies))
+    }
+}
+
+/// Makes a human-readable list of the provided numbers of humans and zombies.
+pub(crate) fn readable_list(humans: u8, zombies: u8) -> String {
+    readable_counts(&[(humans, "human", "humans"), (zombies, "zombie", "zombies")])
+}
+
+/// Makes a human-readable list of the provided counts with their singular and plural names.
+pub(crate) fn readable_counts(counts: &[(u8, &str, &str)]) -> String {
+    let parts = counts
+        .iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, singular, plural)| {
+            format!("{count} {}", if *count == 1 { singular } else { plural })
+        })
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return String::from("nobody");
+    }
+
+    parts.join(" and ")
+}
+
+impl SafetyRule {
+    /// Determines whether the specified number of humans is safe from the specified number of zombies.
+    pub const fn is_safe(&self, humans: u8, zombies: u8) -> bool {
+        if humans == 0 {
+            return true;
+        }
+
+        match self {
+            SafetyRule::StrictMajority => zombies <= humans,
+            SafetyRule::Margin(margin) => zombies as u16 <= humans as u16 + *margin as u16,
+        }
+    }
+}
+
+impl Display for RiverBank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiverBank::Left => f.write_str("left"),
+            RiverBank::Right => f.write_str("right"),
+        }
+    }
+}
+
 impl RiverBank {
     /// Switches from the left bank to the right and vice versa.
     pub fn switch_bank(&self) -> Self {
@@ -159,37 +585,35 @@ impl State for WorldState {
 
     /// Tests whether the specified world state is a goal state.
     fn is_goal(&self) -> bool {
-        // All zombies and all humans are on the right river bank.
-        self.left.is_empty()
+        // All zombies and all humans are on the target river bank.
+        self.origin().is_empty()
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // On neither river bank are the humans outnumbered.
+        self.safety.is_safe(self.left.humans, self.left.zombies)
+            && self.safety.is_safe(self.right.humans, self.right.zombies)
     }
 
     /// Expands the world state into new (applicable) actions.
     /// If this state cannot be expanded, an empty vector is returned.
-    fn get_actions(&self) -> Vec<WorldAction> {
-        let mut actions = Vec::with_capacity(5);
-
-        let bank = self.boat_bank();
-
-        for z in 0..=bank.zombies.min(self.boat.capacity) {
-            'h: for h in 0..=bank.humans.min(self.boat.capacity) {
-                // At least one person needs to be on the boat.
-                if h + z == 0 {
-                    continue;
-                }
-
-                // ... but never more than the boat can carry.
-                if h + z > self.boat.capacity {
-                    break 'h;
-                }
-
-                let action = WorldAction::new(h, z);
-                if action.is_applicable(self) {
-                    actions.push(action);
-                }
-            }
-        }
+    fn get_actions(&self) -> Actions<WorldAction> {
+        self.loads()
+            .into_iter()
+            .filter(|action| action.is_applicable(self))
+            .collect()
+    }
 
-        actions
+    /// Lists why every boat load is ruled out, e.g. because it would leave the humans
+    /// outnumbered on a river bank.
+    fn explain_dead_end(&self) -> Option<String> {
+        let reasons = self
+            .loads()
+            .into_iter()
+            .filter_map(|action| Some(format!("{action:?} {}", action.check(self).err()?)))
+            .collect::<Vec<_>>();
+        (!reasons.is_empty()).then(|| reasons.join("; "))
     }
 
     /// Gets the hash of this state.
@@ -201,97 +625,128 @@ impl State for WorldState {
         };
         (self.left.zombies as u32) << 16 | (self.left.humans as u32) << 8 | (boat as u32)
     }
-}
 
-impl Action for WorldAction {
-    type State = WorldState;
+    /// Swaps the river banks; both the safety rule and the rowing rule apply to either bank alike.
+    /// The target bank is kept, making the mirror image of the initial state a goal state.
+    fn mirror(&self) -> Option<Self> {
+        Some(Self {
+            left: self.right.clone(),
+            right: self.left.clone(),
+            boat: self.boat.switch_bank(),
+            ..self.clone()
+        })
+    }
 
-    /// Tests whether an action is applicable in the given (usually current) world state.
-    fn is_applicable(&self, state: &Self::State) -> bool {
-        let (here, there) = state.here_there();
+    /// Gets the smaller of the hashes of this state and its mirror image.
+    fn canonical_hash(&self) -> Self::Hash {
+        let mirror = self.mirror().expect("problem is symmetric");
+        self.unique_hash().min(mirror.unique_hash())
+    }
 
-        // We cannot have more zombies than humans on the boat.
-        if self.humans > 0 && self.zombies > self.humans {
-            return false;
-        }
+    /// Estimates the remaining cost as the number of people still on the starting river bank.
+    fn heuristic(&self) -> u32 {
+        let origin = self.origin();
+        origin.humans as u32 + origin.zombies as u32
+    }
 
-        // We cannot move more people than there are on the current bank.
-        if here.humans < self.humans || here.zombies < self.zombies {
-            return false;
+    /// Bounds the remaining trips by [`crossings_remaining`] and the remaining moves by the
+    /// people still on the starting river bank. Every trip takes one unit of time plus one for
+    /// everyone aboard, so the remaining time is bounded by both.
+    fn admissible_heuristic(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Trips => crossings_remaining(self),
+            Objective::Time => crossings_remaining(self) + self.heuristic(),
+            Objective::Moves => self.heuristic(),
         }
+    }
 
-        // On either river bank, after the action, zombies must not outnumber humans.
-        let new_humans_here = here.humans - self.humans;
-        let new_zombies_here = here.zombies - self.zombies;
-        let outnumber_here = new_humans_here > 0 && (new_zombies_here > new_humans_here);
-        if outnumber_here {
-            return false;
-        }
+    /// Counts the humans and the zombies.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![
+            self.left.humans as u32 + self.right.humans as u32,
+            self.left.zombies as u32 + self.right.zombies as u32,
+        ]
+    }
+}
 
-        let new_humans_there = there.humans + self.humans;
-        let new_zombies_there = there.zombies + self.zombies;
-        let outnumber_there = new_humans_there > 0 && (new_zombies_there > new_humans_there);
-        if outnumber_there {
-            return false;
-        }
+impl Action for WorldAction {
+    type State = WorldState;
 
-        true
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        self.check(state).is_ok()
     }
 
     /// Applies the specified action to the specified world state,
     /// returning the new state after the action was applied.
-    fn apply(&self, state: &Self::State) -> Self::State {
-        let mut state = state.clone();
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
         let (here, there) = state.here_there_mut();
         here.humans -= self.humans;
         here.zombies -= self.zombies;
         there.humans += self.humans;
         there.zombies += self.zombies;
         state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
         state
     }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the same people crossing back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the target bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == state.target
+    }
+
+    /// Gets the cost of this action, where moves count every entity on the boat and the time
+    /// follows the [linear rowing cost](RowingCost::Linear).
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Trips => 1,
+            Objective::Time => 1 + self.humans as u32 + self.zombies as u32,
+            Objective::Moves => self.humans as u32 + self.zombies as u32,
+        }
+    }
+
+    /// Describes the humans and zombies crossing towards the bank the boat arrived at.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            ActionSummary::repeated([(self.humans, "human"), (self.zombies, "zombie")]),
+        )
+    }
 }
 
 impl PrettyPrintState for WorldState {
     /// Pretty-prints a world state.
     fn pretty_print(&self) -> String {
-        let at_most = (self.left.humans + self.right.humans) as usize;
-
-        let mut buffer = String::new();
-
-        const HUMAN: &'static str = "H";
-        const ZOMBIE: &'static str = "Z";
+        self.pretty_print_styled(&RenderStyle::default())
+    }
 
-        // Left bank.
-        let mut bank = String::new();
-        bank.push_str(&HUMAN.repeat(self.left.humans as _));
-        bank.push(' ');
-        bank.push_str(&ZOMBIE.repeat(self.left.zombies as _));
-        let padding = if self.left.humans == 0 || self.left.zombies == 0 {
-            1
+    /// Pretty-prints a world state using the symbols of the specified style.
+    fn pretty_print_styled(&self, style: &RenderStyle) -> String {
+        let render = |bank: &RiverBankState| group(bank.humans, bank.zombies, style);
+        let (near, far) = if style.right_to_left {
+            (&self.right, &self.left)
         } else {
-            0
+            (&self.left, &self.right)
         };
-        buffer.push_str(&" ".repeat(
-            2 * at_most - self.left.humans as usize - self.left.zombies as usize + padding,
-        ));
-        buffer.push_str(&bank.trim());
 
-        // River bank.
-        if self.boat.bank == RiverBank::Left {
-            buffer.push_str(" |B~~~| ");
-        } else {
-            buffer.push_str(" |~~~B| ");
-        }
+        // Near bank, right-aligned to fit the largest possible population.
+        let mut buffer = pad_left(&render(near), self.bank_width(style));
 
-        // Right bank.
-        let mut bank = String::new();
-        bank.push_str(&" ".repeat(at_most - self.right.humans as usize));
-        bank.push_str(&HUMAN.repeat(self.right.humans as _));
-        bank.push(' ');
-        bank.push_str(&ZOMBIE.repeat(self.right.zombies as _));
-        buffer.push_str(&bank.trim());
+        // River bank.
+        buffer.push(' ');
+        buffer.push_str(&style.river(self.boat.bank == RiverBank::Left));
+        buffer.push(' ');
 
+        // Far bank.
+        buffer.push_str(&render(far));
         buffer.trim_end().into()
     }
 }
@@ -299,24 +754,65 @@ impl PrettyPrintState for WorldState {
 impl PrettyPrintAction<WorldState> for WorldAction {
     /// Pretty-prints an action
     fn pretty_print(&self, state: &WorldState) -> String {
-        let at_most = (state.left.humans + state.right.humans) as usize;
-        let mut buffer = String::from(" ".repeat(at_most * 2 + 3));
-        if state.boat.bank == RiverBank::Left {
-            buffer.push_str("← ");
-        }
-        buffer.push_str(&"H".repeat(self.humans as _));
-        if self.humans > 0 && self.zombies > 0 {
-            buffer.push(' ');
-        }
-        buffer.push_str(&"Z".repeat(self.zombies as _));
-        if state.boat.bank == RiverBank::Right {
-            buffer.push_str(" →");
-        }
+        self.pretty_print_styled(state, &RenderStyle::default())
+    }
+
+    /// Pretty-prints an action using the symbols of the specified style.
+    fn pretty_print_styled(&self, state: &WorldState, style: &RenderStyle) -> String {
+        // An empty boat drifting across is shown as the boat itself.
+        let load = if self.is_empty() {
+            style.boat.clone()
+        } else {
+            group(self.humans, self.zombies, style)
+        };
+        let crossing = style.crossing(state.boat.bank == RiverBank::Left, &load);
 
+        // Crossings within the river start at its bank rather than right after it, and
+        // end there if the scene is mirrored.
+        let inset = usize::from(!style.crossings_in_river);
+        let indent = if style.right_to_left {
+            let river = display_width(&style.river(true));
+            (state.bank_width(style) + 1 + river - inset).saturating_sub(display_width(&crossing))
+        } else {
+            state.bank_width(style) + 1 + inset
+        };
+        let mut buffer = " ".repeat(indent);
+        buffer.push_str(&crossing);
         buffer
     }
 }
 
+/// Renders a group of humans and zombies, with the zombies first if the scene is mirrored.
+fn group(humans: u8, zombies: u8, style: &RenderStyle) -> String {
+    let mut groups = [
+        style.human.repeat(humans as _),
+        style.zombie.repeat(zombies as _),
+    ];
+    if style.right_to_left {
+        groups.reverse();
+    }
+    groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        vec![self.left.to_string(), self.right.to_string()]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {
+    fn cell(&self, state: &WorldState) -> String {
+        match state.boat.bank {
+            RiverBank::Right => format!("→ {self}"),
+            RiverBank::Left => format!("← {self}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,4 +829,330 @@ mod tests {
 
         assert!(action.is_applicable(&state));
     }
+
+    #[test]
+    fn ties_are_broken_the_same_way_every_time() {
+        let plan = search(WorldState::default()).expect("solvable");
+        let loads = plan
+            .steps()
+            .iter()
+            .map(|(action, _)| (action.humans, action.zombies))
+            .collect::<Vec<_>>();
+
+        // Larger groups first, then fewer humans first.
+        assert_eq!(
+            loads,
+            [
+                (0, 2),
+                (0, 1),
+                (0, 2),
+                (0, 1),
+                (2, 0),
+                (1, 1),
+                (2, 0),
+                (0, 1),
+                (0, 2),
+                (0, 1),
+                (0, 2)
+            ]
+        );
+        let actions = WorldState::default().get_actions();
+        let loads = actions
+            .iter()
+            .map(|action| (action.humans, action.zombies))
+            .collect::<Vec<_>>();
+        assert_eq!(loads, [(0, 2), (1, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn dead_ends_are_explained() {
+        let state = WorldState::new(
+            RiverBankState::new(2, 2),
+            RiverBankState::new(1, 1),
+            Boat::new(1, RiverBank::Left),
+        );
+
+        assert!(state.get_actions().is_empty());
+        assert_eq!(
+            state.explain_dead_end().as_deref(),
+            Some(
+                "{ 0×H, 1×Z } would leave zombies outnumbering humans on the right; \
+                 { 1×H, 0×Z } would leave zombies outnumbering humans on the left"
+            )
+        );
+    }
+
+    #[test]
+    fn inverse_undoes_action() {
+        let plan = search(WorldState::default()).expect("solvable");
+        for (_, state) in plan {
+            for action in state.get_actions() {
+                let next = action.apply(&state);
+                assert!(action.inverse().is_applicable(&next));
+                assert_eq!(action.inverse().apply(&next), state);
+            }
+        }
+    }
+
+    #[test]
+    fn rower_requirement_changes_solution() {
+        let free = search(HumansAndZombies::new(3, 3, 3).initial()).expect("solvable");
+
+        let problem = HumansAndZombies {
+            require_rower: true,
+            ..HumansAndZombies::new(3, 3, 3)
+        };
+        let rowed = search(problem.initial()).expect("solvable");
+        assert!(rowed.len() > free.len());
+        assert!(rowed.steps().iter().all(|(action, _)| action.humans > 0));
+
+        let problem = HumansAndZombies {
+            require_rower: true,
+            ..HumansAndZombies::default()
+        };
+        assert!(search(problem.initial()).is_none());
+    }
+
+    #[test]
+    fn empty_crossings_only_switch_the_bank() {
+        let problem = HumansAndZombies {
+            allow_empty_crossing: true,
+            ..HumansAndZombies::new(4, 4, 2)
+        };
+        let state = problem.initial();
+        let actions = state.get_actions();
+        assert!(actions.last().is_some_and(WorldAction::is_empty));
+
+        let drifted = WorldAction::empty().apply(&state);
+        assert_eq!(drifted.boat.bank, RiverBank::Right);
+        assert_eq!((drifted.left, drifted.right), (state.left, state.right));
+        assert!(!WorldAction::empty().is_applicable(&WorldState::default()));
+
+        // Drifting back and forth leads to states observed before, so the search terminates,
+        // solving an instance that is unsolvable otherwise.
+        assert!(search(HumansAndZombies::new(4, 4, 2).initial()).is_none());
+        let plan = search(problem.initial()).expect("solvable");
+        assert!(plan.steps().iter().any(|(action, _)| action.is_empty()));
+    }
+
+    #[test]
+    fn safest_optimal_plan_is_preferred() {
+        use crate::search::search_preferred_optimal;
+
+        let problem = HumansAndZombies::new(5, 3, 3);
+        let first = search(problem.initial()).expect("solvable");
+        assert_eq!(closeness(&first), Some(0));
+
+        let safest = quietly(|| {
+            search_preferred_optimal(problem.initial(), |a, b| problem.compare_plans(a, b))
+        })
+        .expect("solvable");
+        assert_eq!(safest.len(), first.len());
+        assert_eq!(closeness(&safest), Some(-1));
+    }
+
+    #[test]
+    fn safety_margin_admits_more_solutions() {
+        let strict = HumansAndZombies::new(4, 4, 2);
+        assert!(search(strict.initial()).is_none());
+
+        let lenient = HumansAndZombies {
+            safety: SafetyRule::Margin(1),
+            ..strict
+        };
+        assert!(search(lenient.initial()).is_some());
+    }
+
+    #[test]
+    fn right_start_solves_in_as_many_trips() {
+        let left = search(HumansAndZombies::new(5, 5, 3).initial()).expect("solvable");
+
+        let problem = HumansAndZombies {
+            start: RiverBank::Right,
+            ..HumansAndZombies::new(5, 5, 3)
+        };
+        let right = search(problem.initial()).expect("solvable");
+
+        assert_eq!(right.len(), left.len());
+        assert_eq!(right.initial().boat.bank, RiverBank::Right);
+        assert!(right.goal().right.is_empty());
+        assert_eq!(right.goal().left, RiverBankState::new(5, 5));
+    }
+
+    #[test]
+    fn pretty_print_with_more_zombies_than_humans() {
+        let state = WorldState::new(
+            RiverBankState::new(1, 4),
+            RiverBankState::new(0, 0),
+            Boat::new(2, RiverBank::Left),
+        );
+
+        assert_eq!(state.pretty_print(), "   H ZZZZ |B~~~|");
+        assert_eq!(
+            WorldAction::new(0, 2).pretty_print(&state),
+            "           ← ZZ"
+        );
+    }
+
+    #[test]
+    fn searchers_solve_random_solvable_instances() {
+        use crate::history::History;
+        use crate::search::{greedy_best_first, random_search, solve_with_history};
+
+        for seed in 0..16 {
+            let (initial, length) = random_solvable(seed, 8);
+            assert_eq!(random_solvable(seed, 8).0, initial);
+
+            let (bfs, greedy, random) = quietly(|| {
+                (
+                    solve_with_history(initial.clone(), &mut History::new()).into_plan(),
+                    greedy_best_first(initial.clone(), &mut History::new()).into_plan(),
+                    random_search(initial.clone(), seed, 100_000, &mut History::new()).into_plan(),
+                )
+            });
+
+            assert_eq!(bfs.expect("instance is solvable").len(), length);
+            assert!(greedy.expect("instance is solvable").len() >= length);
+            assert!(random.expect("instance is solvable").len() >= length);
+        }
+    }
+
+    #[test]
+    fn linear_rowing_cost_prefers_lighter_boats() {
+        use crate::history::History;
+        use crate::plan::Plan;
+        use crate::search::uniform_cost_search;
+
+        let problem = HumansAndZombies {
+            rowing: RowingCost::Linear,
+            ..HumansAndZombies::new(3, 3, 4)
+        };
+        assert!(problem.objectives().contains(&Objective::Time));
+
+        let time = |action: &WorldAction, _: &WorldState| action.cost(Objective::Time);
+        let total = |plan: &Plan<WorldState, WorldAction>| {
+            plan.steps().iter().map(|(a, s)| time(a, s)).sum::<u32>()
+        };
+        let (fewest_trips, fastest) = quietly(|| {
+            (
+                search(problem.initial()).expect("problem is solvable"),
+                uniform_cost_search(
+                    problem.initial(),
+                    WorldState::is_goal,
+                    time,
+                    &mut History::new(),
+                )
+                .into_plan()
+                .expect("problem is solvable"),
+            )
+        });
+
+        // Both take three trips, but the fastest plan rows fewer entities back.
+        assert_eq!(fewest_trips.len(), fastest.len());
+        assert_eq!(total(&fewest_trips), 13);
+        assert_eq!(total(&fastest), 11);
+    }
+
+    #[test]
+    fn emoji_are_aligned() {
+        let style = RenderStyle::emoji();
+        let state = WorldState::default();
+        let next = WorldAction::new(0, 2).apply(&state);
+
+        assert_eq!(
+            state.pretty_print_styled(&style),
+            "🧑🧑🧑 🧟🧟🧟 |🚣🌊🌊🌊|"
+        );
+        assert_eq!(
+            next.pretty_print_styled(&style),
+            "    🧑🧑🧑 🧟 |🌊🌊🌊🚣| 🧟🧟"
+        );
+        assert_eq!(
+            WorldAction::new(0, 2).pretty_print_styled(&next, &style),
+            "               🧟🧟 →"
+        );
+    }
+
+    #[test]
+    fn unicode_river_keeps_the_banks_aligned() {
+        let style = RenderStyle::unicode();
+        let state = WorldState::default();
+        let next = WorldAction::new(0, 2).apply(&state);
+
+        assert_eq!(state.pretty_print_styled(&style), "HHH ZZZ ║🚣～～～║");
+        assert_eq!(
+            WorldAction::new(0, 2).pretty_print_styled(&next, &style),
+            "        ║ZZ →    ║"
+        );
+        assert_eq!(next.pretty_print_styled(&style), "  HHH Z ║～～～🚣║ ZZ");
+    }
+
+    #[test]
+    fn right_to_left_is_a_mirror_image() {
+        let state = WorldState::default();
+        let plan = quietly(|| crate::search::search(state)).expect("problem is solvable");
+        let mirror = |line: String| {
+            line.chars()
+                .rev()
+                .map(|c| match c {
+                    '←' => '→',
+                    '→' => '←',
+                    c => c,
+                })
+                .collect::<String>()
+        };
+
+        for unicode in [false, true] {
+            let style = if unicode {
+                RenderStyle::unicode()
+            } else {
+                RenderStyle::default()
+            };
+            let mirrored = RenderStyle {
+                right_to_left: true,
+                ..style.clone()
+            };
+
+            let mut lines = vec![(
+                plan.initial().pretty_print_styled(&style),
+                plan.initial().pretty_print_styled(&mirrored),
+            )];
+            for (action, state) in plan.steps() {
+                lines.push((
+                    action.pretty_print_styled(state, &style),
+                    action.pretty_print_styled(state, &mirrored),
+                ));
+                lines.push((
+                    state.pretty_print_styled(&style),
+                    state.pretty_print_styled(&mirrored),
+                ));
+            }
+
+            // Pad every line to the width of the scene, so that mirroring keeps it in place.
+            let river = display_width(&style.river(true));
+            let width = 2 * plan.initial().bank_width(&style) + 2 + river;
+            let pad = |line: String| {
+                let padding = width - display_width(&line);
+                format!("{line}{}", " ".repeat(padding))
+            };
+            for (line, mirrored) in lines {
+                assert_eq!(pad(mirrored), mirror(pad(line)));
+            }
+        }
+    }
+
+    #[test]
+    fn outnumbered_humans_are_invalid() {
+        let state = WorldState::new(
+            RiverBankState::new(1, 2),
+            RiverBankState::new(2, 1),
+            Boat::new(2, RiverBank::Left),
+        );
+        assert!(!state.is_valid());
+        assert!(WorldState::default().is_valid());
+
+        // Plans only pass through valid states.
+        let plan = search(WorldState::default()).expect("solvable");
+        assert!(plan.into_iter().all(|(_, state)| state.is_valid()));
+    }
 }
@@ -0,0 +1,280 @@
+//! A compact encoding of the Humans and Zombies world states into a single `u32`, along with
+//! a breadth-first search operating on the encoded states directly.
+
+use crate::plan::Plan;
+use crate::problems::humans_and_zombies::{
+    Boat, RiverBank, RiverBankState, SafetyRule, WorldAction, WorldState,
+};
+use std::collections::VecDeque;
+
+/// Encodes the world states of a single Humans and Zombies instance as `u32` values holding the
+/// number of humans on the left bank in the lowest byte, the number of zombies on the left bank
+/// in the next byte and, in the bit above, whether the boat is on the right bank.
+///
+/// Everything else, i.e. the population, the boat capacity and the rules, is the same for all
+/// states of an instance and kept by the encoding instead.
+#[derive(Debug, Clone)]
+pub struct Encoding {
+    humans: u8,
+    zombies: u8,
+    capacity: u8,
+    target: RiverBank,
+    require_human_rower: bool,
+    allow_empty_crossing: bool,
+    safety: SafetyRule,
+}
+
+/// The bit set if the boat is on the right bank.
+const BOAT_RIGHT: u32 = 1 << 16;
+
+impl Encoding {
+    /// Creates the encoding of the instance the specified state belongs to.
+    pub fn new(state: &WorldState) -> Self {
+        Self {
+            humans: state.left.humans + state.right.humans,
+            zombies: state.left.zombies + state.right.zombies,
+            capacity: state.boat.capacity,
+            target: state.target,
+            require_human_rower: state.require_human_rower,
+            allow_empty_crossing: state.allow_empty_crossing,
+            safety: state.safety,
+        }
+    }
+
+    /// Encodes a world state of this instance.
+    pub fn encode(&self, state: &WorldState) -> u32 {
+        debug_assert_eq!(state.left.humans + state.right.humans, self.humans);
+        debug_assert_eq!(state.left.zombies + state.right.zombies, self.zombies);
+        let boat = match state.boat.bank {
+            RiverBank::Left => 0,
+            RiverBank::Right => BOAT_RIGHT,
+        };
+        state.left.humans as u32 | (state.left.zombies as u32) << 8 | boat
+    }
+
+    /// Decodes a world state of this instance.
+    pub fn decode(&self, code: u32) -> WorldState {
+        let (humans, zombies) = left(code);
+        let bank = if code & BOAT_RIGHT == 0 {
+            RiverBank::Left
+        } else {
+            RiverBank::Right
+        };
+
+        WorldState {
+            left: RiverBankState::new(humans, zombies),
+            right: RiverBankState::new(self.humans - humans, self.zombies - zombies),
+            boat: Boat::new(self.capacity, bank),
+            target: self.target,
+            require_human_rower: self.require_human_rower,
+            allow_empty_crossing: self.allow_empty_crossing,
+            safety: self.safety,
+        }
+    }
+
+    /// Gets the numbers of humans and zombies on the bank the boat is at and on the other one.
+    fn here_there(&self, code: u32) -> ((u8, u8), (u8, u8)) {
+        let (humans, zombies) = left(code);
+        let right = (self.humans - humans, self.zombies - zombies);
+        if code & BOAT_RIGHT == 0 {
+            ((humans, zombies), right)
+        } else {
+            (right, (humans, zombies))
+        }
+    }
+
+    /// Determines whether everyone is on the target bank.
+    fn is_goal(&self, code: u32) -> bool {
+        let (humans, zombies) = left(code);
+        match self.target {
+            RiverBank::Left => humans == self.humans && zombies == self.zombies,
+            RiverBank::Right => humans == 0 && zombies == 0,
+        }
+    }
+
+    /// Moves the specified numbers of humans and zombies across, if that is allowed,
+    /// following the same rules as [`WorldAction::is_applicable`].
+    fn cross(&self, code: u32, humans: u8, zombies: u8) -> Option<u32> {
+        if humans == 0 && zombies == 0 {
+            return self.allow_empty_crossing.then_some(code ^ BOAT_RIGHT);
+        }
+
+        if self.require_human_rower && humans == 0 {
+            return None;
+        }
+
+        if humans > 0 && zombies > humans {
+            return None;
+        }
+
+        let ((humans_here, zombies_here), (humans_there, zombies_there)) = self.here_there(code);
+        if humans_here < humans || zombies_here < zombies {
+            return None;
+        }
+
+        if !self
+            .safety
+            .is_safe(humans_here - humans, zombies_here - zombies)
+            || !self
+                .safety
+                .is_safe(humans_there + humans, zombies_there + zombies)
+        {
+            return None;
+        }
+
+        let moved = humans as u32 | (zombies as u32) << 8;
+        Some(if code & BOAT_RIGHT == 0 {
+            (code - moved) | BOAT_RIGHT
+        } else {
+            (code + moved) & !BOAT_RIGHT
+        })
+    }
+
+    /// Gets the index of an encoded state into a table of all states of this instance.
+    fn index(&self, code: u32) -> usize {
+        let (humans, zombies) = left(code);
+        let boat = (code & BOAT_RIGHT != 0) as usize;
+        ((zombies as usize * (self.humans as usize + 1)) + humans as usize) * 2 + boat
+    }
+
+    /// Gets the number of states of this instance.
+    fn states(&self) -> usize {
+        (self.humans as usize + 1) * (self.zombies as usize + 1) * 2
+    }
+}
+
+/// Gets the numbers of humans and zombies on the left bank of an encoded state.
+fn left(code: u32) -> (u8, u8) {
+    (code as u8, (code >> 8) as u8)
+}
+
+/// Searches for the shortest plan using breadth-first search on the encoded states.
+///
+/// This explores the states and actions in the same order as [`search`](crate::search::search)
+/// and thus finds the very same plan, but keeps track of the visited states in a table indexed
+/// by their encoding rather than cloning and hashing them. Nothing is printed while searching.
+pub fn search_compact(initial_state: &WorldState) -> Option<Plan<WorldState, WorldAction>> {
+    let encoding = Encoding::new(initial_state);
+    let initial = encoding.encode(initial_state);
+
+    // Every discovered state other than the initial one, along with the state it was
+    // discovered from and the numbers of humans and zombies that crossed to get there.
+    let mut parents: Vec<Option<(u32, u8, u8)>> = vec![None; encoding.states()];
+    let mut observed = vec![false; encoding.states()];
+    observed[encoding.index(initial)] = true;
+
+    let mut fringe = VecDeque::from([initial]);
+    while let Some(code) = fringe.pop_front() {
+        if encoding.is_goal(code) {
+            return Some(backtrack(&encoding, initial_state, &parents, code));
+        }
+
+        // Larger groups first, fewest humans first within a size and the empty boat drifting
+        // across last, like the generic search.
+        let ((humans, zombies), _) = encoding.here_there(code);
+        let most = encoding.capacity.min(humans.saturating_add(zombies));
+        let loads = (1..=most)
+            .rev()
+            .flat_map(|size| (0..=size.min(humans)).map(move |h| (h, size - h)))
+            .filter(|&(_, z)| z <= zombies)
+            .chain(encoding.allow_empty_crossing.then_some((0, 0)));
+
+        for (h, z) in loads {
+            let Some(next) = encoding.cross(code, h, z) else {
+                continue;
+            };
+
+            let index = encoding.index(next);
+            if !observed[index] {
+                observed[index] = true;
+                parents[index] = Some((code, h, z));
+                fringe.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Builds the plan leading from the initial state to the encoded goal state.
+fn backtrack(
+    encoding: &Encoding,
+    initial_state: &WorldState,
+    parents: &[Option<(u32, u8, u8)>],
+    goal: u32,
+) -> Plan<WorldState, WorldAction> {
+    let mut steps = Vec::new();
+    let mut code = goal;
+    while let Some((parent, humans, zombies)) = parents[encoding.index(code)] {
+        steps.push((WorldAction { humans, zombies }, code));
+        code = parent;
+    }
+
+    let mut plan = Plan::new(initial_state.clone());
+    for (action, code) in steps.into_iter().rev() {
+        plan.push(action, encoding.decode(code));
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::HumansAndZombies;
+    use crate::problems::Problem;
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn states_survive_encoding() {
+        let problem = HumansAndZombies::new(5, 4, 3);
+        let encoding = Encoding::new(&problem.initial());
+        let plan = quietly(|| search(problem.initial())).expect("problem is solvable");
+        for (_, state) in plan.steps() {
+            assert!(encoding.decode(encoding.encode(state)) == *state);
+        }
+    }
+
+    #[test]
+    fn plans_are_identical_to_the_generic_search() {
+        let mut instances = Vec::new();
+        for humans in 1..=8 {
+            for zombies in 1..=8 {
+                for boat in 1..=4 {
+                    instances.push(HumansAndZombies::new(humans, zombies, boat));
+                }
+            }
+        }
+        instances.push(HumansAndZombies {
+            require_rower: true,
+            ..HumansAndZombies::new(4, 4, 3)
+        });
+        instances.push(HumansAndZombies {
+            safety: SafetyRule::Margin(1),
+            ..HumansAndZombies::new(5, 5, 2)
+        });
+        instances.push(HumansAndZombies {
+            allow_empty_crossing: true,
+            ..HumansAndZombies::new(4, 4, 2)
+        });
+        instances.push(HumansAndZombies {
+            start: RiverBank::Right,
+            ..HumansAndZombies::new(5, 5, 3)
+        });
+
+        for problem in instances {
+            let expected = quietly(|| search(problem.initial()));
+            let plan = search_compact(&problem.initial());
+
+            let steps = |plan: Option<Plan<WorldState, WorldAction>>| {
+                plan.map(|plan| {
+                    plan.steps()
+                        .iter()
+                        .map(|(action, state)| (action.humans, action.zombies, state.clone()))
+                        .collect::<Vec<_>>()
+                })
+            };
+            assert!(steps(plan) == steps(expected), "{problem:?}");
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::humans_and_zombies::{Boat, RiverBank};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The maximum number of couples, limited by the width of the bit sets.
+pub const MAX_COUPLES: u8 = 16;
+
+/// The Jealous Husbands problem.
+///
+/// A number of married couples must cross the river. No wife may be in the presence
+/// of another man, neither on a river bank nor in the boat, unless her husband is present.
+#[derive(Debug, Clone)]
+pub struct JealousHusbands {
+    /// The number of couples on the left river bank.
+    pub couples: u8,
+    /// The capacity of the boat.
+    pub boat: u8,
+}
+
+/// Describes the world state.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The left river bank.
+    pub left: RiverBankState,
+    /// The right river bank.
+    pub right: RiverBankState,
+    /// The boat.
+    pub boat: Boat,
+}
+
+/// Describes the state on a river bank, or in the boat.
+///
+/// Both husbands and wives are stored as bit sets indexed by their couple,
+/// i.e. bit `i` of either set refers to the `i`-th couple.
+#[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RiverBankState {
+    /// The husbands on this bank.
+    pub husbands: u16,
+    /// The wives on this bank.
+    pub wives: u16,
+}
+
+/// An action to apply.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorldAction {
+    /// The people to move.
+    pub people: RiverBankState,
+}
+
+impl JealousHusbands {
+    /// Creates a new problem from the number of couples and the boat capacity.
+    pub const fn new(couples: u8, boat: u8) -> Self {
+        debug_assert!(couples <= MAX_COUPLES);
+        Self { couples, boat }
+    }
+}
+
+impl Default for JealousHusbands {
+    fn default() -> Self {
+        Self::new(3, 2)
+    }
+}
+
+impl Problem for JealousHusbands {
+    type State = WorldState;
+
+    /// Builds the initial world state with all couples on the left river bank.
+    fn initial(&self) -> WorldState {
+        let everyone = ((1u32 << self.couples) - 1) as u16;
+        let left = RiverBankState::new(everyone, everyone);
+        let right = RiverBankState::default();
+        let boat = Boat::new(self.boat, RiverBank::Left);
+        WorldState::new(left, right, boat)
+    }
+
+    /// Supports minimizing the number of trips and the number of people moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Moves]
+    }
+}
+
+impl WorldState {
+    /// Creates a new problem state from the left and right river bank states.
+    pub const fn new(left: RiverBankState, right: RiverBankState, boat: Boat) -> Self {
+        Self { left, right, boat }
+    }
+
+    /// Unpacks the world state into a tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there(&self) -> (&RiverBankState, &RiverBankState) {
+        match self.boat.bank {
+            RiverBank::Left => (&self.left, &self.right),
+            RiverBank::Right => (&self.right, &self.left),
+        }
+    }
+
+    /// Unpacks the world state into a (mutable) tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there_mut(&mut self) -> (&mut RiverBankState, &mut RiverBankState) {
+        match self.boat.bank {
+            RiverBank::Left => (&mut self.left, &mut self.right),
+            RiverBank::Right => (&mut self.right, &mut self.left),
+        }
+    }
+
+    /// Gets the number of couples. This determines the width required to render a river bank.
+    fn couples(&self) -> usize {
+        (self.left.husbands | self.right.husbands | self.left.wives | self.right.wives).count_ones()
+            as usize
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        JealousHusbands::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ left: {:?}, right: {:?}, boat: {:?} }}",
+            self.left, self.right, self.boat
+        )
+    }
+}
+
+impl RiverBankState {
+    /// Creates a new river bank state from the bit sets of husbands and wives.
+    pub const fn new(husbands: u16, wives: u16) -> Self {
+        Self { husbands, wives }
+    }
+
+    /// Determines whether this river bank is empty, i.e. has neither husbands nor wives.
+    pub const fn is_empty(&self) -> bool {
+        self.husbands == 0 && self.wives == 0
+    }
+
+    /// Gets the number of people on this bank.
+    pub const fn len(&self) -> u32 {
+        self.husbands.count_ones() + self.wives.count_ones()
+    }
+
+    /// Determines whether every wife on this bank is either without men around
+    /// or accompanied by her husband.
+    pub const fn is_safe(&self) -> bool {
+        let unaccompanied_wives = self.wives & !self.husbands;
+        self.husbands == 0 || unaccompanied_wives == 0
+    }
+
+    /// Determines whether this bank contains everyone on the other one.
+    const fn contains(&self, other: &Self) -> bool {
+        self.husbands & other.husbands == other.husbands && self.wives & other.wives == other.wives
+    }
+
+    /// Renders the couples on this bank, husbands as upper case and wives as lower case letters.
+    fn render(&self) -> String {
+        let people = |set: u16, first: u8| {
+            (0..MAX_COUPLES)
+                .filter(|couple| set & (1 << couple) != 0)
+                .map(|couple| (first + couple) as char)
+                .collect::<String>()
+        };
+
+        format!(
+            "{} {}",
+            people(self.husbands, b'A'),
+            people(self.wives, b'a')
+        )
+        .trim()
+        .into()
+    }
+}
+
+impl Debug for RiverBankState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ {} }}", self.render())
+    }
+}
+
+impl WorldAction {
+    pub fn new(husbands: u16, wives: u16) -> Self {
+        debug_assert_ne!(husbands | wives, 0);
+        Self {
+            people: RiverBankState::new(husbands, wives),
+        }
+    }
+}
+
+impl Debug for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.people)
+    }
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = u64;
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // All couples are on the right river bank.
+        self.left.is_empty()
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // On neither river bank is a wife with another man without her husband.
+        self.left.is_safe() && self.right.is_safe()
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let (here, _) = self.here_there();
+
+        // Every person is a single bit set, husbands first.
+        let people = (0..MAX_COUPLES)
+            .filter(|couple| here.husbands & (1 << couple) != 0)
+            .map(|couple| RiverBankState::new(1 << couple, 0))
+            .chain(
+                (0..MAX_COUPLES)
+                    .filter(|couple| here.wives & (1 << couple) != 0)
+                    .map(|couple| RiverBankState::new(0, 1 << couple)),
+            )
+            .collect_vec();
+
+        let mut actions = Actions::new();
+        for size in 1..=self.boat.capacity as usize {
+            for group in people.iter().combinations(size) {
+                let husbands = group.iter().fold(0, |set, p| set | p.husbands);
+                let wives = group.iter().fold(0, |set, p| set | p.wives);
+                let action = WorldAction::new(husbands, wives);
+                if action.is_applicable(self) {
+                    actions.push(action);
+                }
+            }
+        }
+
+        actions
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        let boat = if self.boat.bank == RiverBank::Left {
+            0
+        } else {
+            1
+        };
+        (self.left.husbands as u64) << 32 | (self.left.wives as u64) << 16 | boat
+    }
+
+    /// Estimates the remaining cost as the number of people still on the left river bank.
+    fn heuristic(&self) -> u32 {
+        self.left.len()
+    }
+
+    /// Counts the husbands and the wives, counting anyone on both river banks twice.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![
+            self.left.husbands.count_ones() + self.right.husbands.count_ones(),
+            self.left.wives.count_ones() + self.right.wives.count_ones(),
+        ]
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        let (here, there) = state.here_there();
+
+        // Someone has to row the boat, but it can carry no more than its capacity.
+        if self.people.is_empty() || self.people.len() > state.boat.capacity as u32 {
+            return false;
+        }
+
+        // We cannot move people that are not on the current bank.
+        if !here.contains(&self.people) {
+            return false;
+        }
+
+        // No wife may be left with another man, neither in the boat nor on either bank.
+        let new_here = RiverBankState::new(
+            here.husbands & !self.people.husbands,
+            here.wives & !self.people.wives,
+        );
+        let new_there = RiverBankState::new(
+            there.husbands | self.people.husbands,
+            there.wives | self.people.wives,
+        );
+        self.people.is_safe() && new_here.is_safe() && new_there.is_safe()
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        let (here, there) = state.here_there_mut();
+        here.husbands &= !self.people.husbands;
+        here.wives &= !self.people.wives;
+        there.husbands |= self.people.husbands;
+        there.wives |= self.people.wives;
+        state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the same people crossing back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the right bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == RiverBank::Right
+    }
+
+    /// Gets the cost of this action, where moves count every person on the boat.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Moves => self.people.len(),
+            _ => 1,
+        }
+    }
+
+    /// Describes the husbands and wives crossing towards the bank the boat arrived at,
+    /// lettered as when rendering a bank.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        let people = |set: u16, role: &'static str, first: u8| {
+            (0..MAX_COUPLES)
+                .filter(move |couple| set & (1 << couple) != 0)
+                .map(move |couple| format!("{role} {}", (first + couple) as char))
+        };
+
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            people(self.people.husbands, "husband", b'A')
+                .chain(people(self.people.wives, "wife", b'a'))
+                .collect(),
+        )
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        let river = if self.boat.bank == RiverBank::Left {
+            "|B~~~|"
+        } else {
+            "|~~~B|"
+        };
+
+        let line = format!(
+            "{:>width$} {} {}",
+            self.left.render(),
+            river,
+            self.right.render(),
+            width = 2 * self.couples() + 1
+        );
+        line.trim_end().into()
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, state: &WorldState) -> String {
+        let mut buffer = " ".repeat(state.couples() * 2 + 3);
+        if state.boat.bank == RiverBank::Left {
+            buffer.push_str("← ");
+        }
+        buffer.push_str(&self.people.render());
+        if state.boat.bank == RiverBank::Right {
+            buffer.push_str(" →");
+        }
+
+        buffer
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        let render = |bank: &RiverBankState| match bank.is_empty() {
+            true => String::from("nobody"),
+            false => bank.render(),
+        };
+        vec![render(&self.left), render(&self.right)]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn three_couples_cross_with_small_boat() {
+        let plan = search(WorldState::default()).expect("problem is solvable");
+        assert_eq!(plan.len(), 11);
+        assert!(plan
+            .into_iter()
+            .all(|(_, state)| state.left.is_safe() && state.right.is_safe()));
+    }
+
+    #[test]
+    fn four_couples_require_larger_boat() {
+        assert!(search(JealousHusbands::new(4, 2).initial()).is_none());
+        assert!(search(JealousHusbands::new(4, 3).initial()).is_some());
+        assert!(search(JealousHusbands::new(5, 3).initial()).is_some());
+    }
+
+    #[test]
+    fn wife_may_not_be_left_with_another_man() {
+        let state = WorldState::default();
+
+        // Husband A leaves wife a behind with husbands B and C.
+        assert!(!WorldAction::new(0b001, 0).is_applicable(&state));
+
+        // Couple A crosses together.
+        assert!(WorldAction::new(0b001, 0b001).is_applicable(&state));
+
+        // Husband A and wife b share the boat.
+        assert!(!WorldAction::new(0b001, 0b010).is_applicable(&state));
+    }
+}
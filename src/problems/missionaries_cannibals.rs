@@ -0,0 +1,461 @@
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::humans_and_zombies::{readable_counts, Boat, RiverBank};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+
+/// The textbook Missionaries and Cannibals problem.
+///
+/// Unlike [`HumansAndZombies`](crate::problems::humans_and_zombies::HumansAndZombies),
+/// which can be configured to model other variants, this problem always uses the classic
+/// rules: someone has to row the boat, and the missionaries must never be outnumbered by
+/// the cannibals, neither on a river bank nor in the boat.
+#[derive(Debug, Clone)]
+pub struct MissionariesCannibals {
+    /// The number of missionaries on the left river bank.
+    pub missionaries: u8,
+    /// The number of cannibals on the left river bank.
+    pub cannibals: u8,
+    /// The capacity of the boat.
+    pub boat: u8,
+}
+
+/// Describes the world state.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The left river bank.
+    pub left: RiverBankState,
+    /// The right river bank.
+    pub right: RiverBankState,
+    /// The boat.
+    pub boat: Boat,
+}
+
+/// Describes the state on a river bank.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RiverBankState {
+    /// The number of missionaries on this bank.
+    pub missionaries: u8,
+    /// The number of cannibals on this bank.
+    pub cannibals: u8,
+}
+
+/// An action to apply.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorldAction {
+    /// How many missionaries to move.
+    pub missionaries: u8,
+    /// How many cannibals to move.
+    pub cannibals: u8,
+}
+
+impl MissionariesCannibals {
+    /// Creates a new problem from the number of missionaries and cannibals and the boat capacity.
+    pub const fn new(missionaries: u8, cannibals: u8, boat: u8) -> Self {
+        Self {
+            missionaries,
+            cannibals,
+            boat,
+        }
+    }
+}
+
+impl Default for MissionariesCannibals {
+    fn default() -> Self {
+        Self::new(3, 3, 2)
+    }
+}
+
+impl Problem for MissionariesCannibals {
+    type State = WorldState;
+
+    /// Builds the initial world state with everyone on the left river bank.
+    fn initial(&self) -> WorldState {
+        let left = RiverBankState::new(self.missionaries, self.cannibals);
+        let right = RiverBankState::new(0, 0);
+        let boat = Boat::new(self.boat, RiverBank::Left);
+        WorldState::new(left, right, boat)
+    }
+
+    /// Supports minimizing the number of trips and the number of people moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Moves]
+    }
+}
+
+impl WorldState {
+    /// Creates a new problem state from the left and right river bank states.
+    pub const fn new(left: RiverBankState, right: RiverBankState, boat: Boat) -> Self {
+        Self { left, right, boat }
+    }
+
+    /// Unpacks the world state into a tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there(&self) -> (&RiverBankState, &RiverBankState) {
+        match self.boat.bank {
+            RiverBank::Left => (&self.left, &self.right),
+            RiverBank::Right => (&self.right, &self.left),
+        }
+    }
+
+    /// Unpacks the world state into a (mutable) tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there_mut(&mut self) -> (&mut RiverBankState, &mut RiverBankState) {
+        match self.boat.bank {
+            RiverBank::Left => (&mut self.left, &mut self.right),
+            RiverBank::Right => (&mut self.right, &mut self.left),
+        }
+    }
+
+    /// Gets the total number of missionaries or cannibals, whichever is higher.
+    /// This determines the width required to render a river bank.
+    fn max_population(&self) -> usize {
+        let missionaries = self.left.missionaries as usize + self.right.missionaries as usize;
+        let cannibals = self.left.cannibals as usize + self.right.cannibals as usize;
+        missionaries.max(cannibals)
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        MissionariesCannibals::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ left: {:?}, right: {:?}, boat: {:?} }}",
+            self.left, self.right, self.boat
+        )
+    }
+}
+
+/// Formats the state as prose, naming the river bank the boat is at.
+///
+/// ```
+/// use river_crossing::problems::missionaries_cannibals::WorldState;
+///
+/// assert_eq!(
+///     WorldState::default().to_string(),
+///     "left bank: 3 missionaries and 3 cannibals; right bank: nobody; boat on the left"
+/// );
+/// ```
+impl Display for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "left bank: {}; right bank: {}; boat on the {}",
+            self.left, self.right, self.boat.bank
+        )
+    }
+}
+
+impl RiverBankState {
+    /// Creates a new river bank state from the number of missionaries and cannibals.
+    pub const fn new(missionaries: u8, cannibals: u8) -> Self {
+        Self {
+            missionaries,
+            cannibals,
+        }
+    }
+
+    /// Determines whether this river bank is empty, i.e. has neither
+    /// missionaries nor cannibals.
+    pub const fn is_empty(&self) -> bool {
+        self.missionaries == 0 && self.cannibals == 0
+    }
+}
+
+impl Debug for RiverBankState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ {}×M, {}×C }}", self.missionaries, self.cannibals)
+    }
+}
+
+/// Formats the river bank as a list of the people on it.
+///
+/// ```
+/// use river_crossing::problems::missionaries_cannibals::RiverBankState;
+///
+/// assert_eq!(RiverBankState::new(1, 2).to_string(), "1 missionary and 2 cannibals");
+/// ```
+impl Display for RiverBankState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_list(self.missionaries, self.cannibals))
+    }
+}
+
+impl WorldAction {
+    pub const fn new(missionaries: u8, cannibals: u8) -> Self {
+        Self {
+            missionaries,
+            cannibals,
+        }
+    }
+}
+
+impl Debug for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ {}×M, {}×C }}", self.missionaries, self.cannibals)
+    }
+}
+
+/// Formats the action as a list of the people on the boat.
+///
+/// ```
+/// use river_crossing::problems::missionaries_cannibals::WorldAction;
+///
+/// assert_eq!(WorldAction::new(0, 1).to_string(), "1 cannibal");
+/// ```
+impl Display for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_list(self.missionaries, self.cannibals))
+    }
+}
+
+/// Makes a human-readable list of the provided numbers of missionaries and cannibals.
+fn readable_list(missionaries: u8, cannibals: u8) -> String {
+    readable_counts(&[
+        (missionaries, "missionary", "missionaries"),
+        (cannibals, "cannibal", "cannibals"),
+    ])
+}
+
+/// Determines whether the missionaries are safe, i.e. not outnumbered by cannibals.
+const fn is_safe(missionaries: u8, cannibals: u8) -> bool {
+    missionaries == 0 || cannibals <= missionaries
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = u32;
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // All missionaries and cannibals are on the right river bank.
+        self.left.is_empty()
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // On neither river bank are the missionaries outnumbered.
+        is_safe(self.left.missionaries, self.left.cannibals)
+            && is_safe(self.right.missionaries, self.right.cannibals)
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let (here, _) = self.here_there();
+
+        let mut actions = Actions::new();
+        for c in 0..=here.cannibals.min(self.boat.capacity) {
+            for m in 0..=here.missionaries.min(self.boat.capacity - c) {
+                let action = WorldAction::new(m, c);
+                if action.is_applicable(self) {
+                    actions.push(action);
+                }
+            }
+        }
+
+        actions
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        let boat = if self.boat.bank == RiverBank::Left {
+            0
+        } else {
+            1
+        };
+        (self.left.cannibals as u32) << 16 | (self.left.missionaries as u32) << 8 | (boat as u32)
+    }
+
+    /// Estimates the remaining cost as the number of people still on the left river bank.
+    fn heuristic(&self) -> u32 {
+        self.left.missionaries as u32 + self.left.cannibals as u32
+    }
+
+    /// Counts the missionaries and the cannibals.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![
+            self.left.missionaries as u32 + self.right.missionaries as u32,
+            self.left.cannibals as u32 + self.right.cannibals as u32,
+        ]
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        let (here, there) = state.here_there();
+
+        // Someone has to row the boat, but it can carry no more than its capacity.
+        let people = self.missionaries as u16 + self.cannibals as u16;
+        if people == 0 || people > state.boat.capacity as u16 {
+            return false;
+        }
+
+        // We cannot move more people than there are on the current bank.
+        if here.missionaries < self.missionaries || here.cannibals < self.cannibals {
+            return false;
+        }
+
+        // Missionaries must not be outnumbered in the boat, nor on either bank after the action.
+        is_safe(self.missionaries, self.cannibals)
+            && is_safe(
+                here.missionaries - self.missionaries,
+                here.cannibals - self.cannibals,
+            )
+            && is_safe(
+                there.missionaries + self.missionaries,
+                there.cannibals + self.cannibals,
+            )
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        let (here, there) = state.here_there_mut();
+        here.missionaries -= self.missionaries;
+        here.cannibals -= self.cannibals;
+        there.missionaries += self.missionaries;
+        there.cannibals += self.cannibals;
+        state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the same people crossing back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the right bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == RiverBank::Right
+    }
+
+    /// Gets the cost of this action, where moves count every person on the boat.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Moves => self.missionaries as u32 + self.cannibals as u32,
+            _ => 1,
+        }
+    }
+
+    /// Describes the missionaries and cannibals crossing towards the bank the boat arrived at.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            ActionSummary::repeated([
+                (self.missionaries, "missionary"),
+                (self.cannibals, "cannibal"),
+            ]),
+        )
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        let at_most = self.max_population();
+
+        let render = |bank: &RiverBankState| {
+            let mut buffer = "M".repeat(bank.missionaries as _);
+            buffer.push(' ');
+            buffer.push_str(&"C".repeat(bank.cannibals as _));
+            buffer.trim().to_string()
+        };
+
+        let river = if self.boat.bank == RiverBank::Left {
+            "|B~~~|"
+        } else {
+            "|~~~B|"
+        };
+
+        let line = format!(
+            "{:>width$} {} {}",
+            render(&self.left),
+            river,
+            render(&self.right),
+            width = 2 * at_most + 1
+        );
+        line.trim_end().into()
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, state: &WorldState) -> String {
+        let at_most = state.max_population();
+        let mut buffer = " ".repeat(at_most * 2 + 3);
+        if state.boat.bank == RiverBank::Left {
+            buffer.push_str("← ");
+        }
+        buffer.push_str(&"M".repeat(self.missionaries as _));
+        if self.missionaries > 0 && self.cannibals > 0 {
+            buffer.push(' ');
+        }
+        buffer.push_str(&"C".repeat(self.cannibals as _));
+        if state.boat.bank == RiverBank::Right {
+            buffer.push_str(" →");
+        }
+
+        buffer
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        vec![self.left.to_string(), self.right.to_string()]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {
+    fn cell(&self, state: &WorldState) -> String {
+        match state.boat.bank {
+            RiverBank::Right => format!("→ {self}"),
+            RiverBank::Left => format!("← {self}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn classic_puzzle_takes_eleven_crossings() {
+        let plan = search(WorldState::default()).expect("problem is solvable");
+        assert_eq!(plan.len(), 11);
+        assert!(plan.steps().iter().all(|(action, _)| {
+            action.missionaries + action.cannibals > 0
+                && is_safe(action.missionaries, action.cannibals)
+        }));
+    }
+
+    #[test]
+    fn empty_boat_is_not_applicable() {
+        assert!(!WorldAction::new(0, 0).is_applicable(&WorldState::default()));
+    }
+
+    #[test]
+    fn four_of_each_with_small_boat_is_unsolvable() {
+        let initial = MissionariesCannibals::new(4, 4, 2).initial();
+        assert!(search(initial).is_none());
+    }
+}
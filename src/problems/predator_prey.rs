@@ -0,0 +1,460 @@
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::humans_and_zombies::{Boat, RiverBank};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The maximum number of items, limited by the width of the bit sets.
+pub const MAX_ITEMS: usize = 32;
+
+/// A data-driven predator/prey/bait crossing.
+///
+/// A farmer has to bring a number of items across the river, taking along as many as the
+/// boat holds besides the farmer. Some pairs of items cannot be left unattended together,
+/// e.g. because one would eat the other; these are described by the [`Constraints`].
+/// This covers the Fox, Goose and Beans problem as well as Wolf, Goat and Cabbage
+/// and any made-up variant thereof.
+#[derive(Debug, Clone)]
+pub struct PredatorPrey {
+    /// The names of the items on the left river bank.
+    pub items: Vec<Arc<str>>,
+    /// The pairs of items that cannot be left unattended together.
+    pub constraints: Constraints,
+    /// The capacity of the boat, including the farmer.
+    pub boat: u8,
+}
+
+/// The pairs of items that cannot be left unattended together, identified by their index.
+#[derive(Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Constraints {
+    forbidden: Vec<(u8, u8)>,
+}
+
+/// Describes the world state.
+///
+/// The farmer always travels with the boat, so the boat's bank is where the farmer is.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The items on the left river bank, as a bit set indexed by item.
+    pub left: u32,
+    /// The items on the right river bank, as a bit set indexed by item.
+    pub right: u32,
+    /// The boat.
+    pub boat: Boat,
+    /// The names of the items.
+    names: Arc<[Arc<str>]>,
+    /// The pairs of items that cannot be left unattended together.
+    constraints: Arc<Constraints>,
+}
+
+/// An action to apply.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorldAction {
+    /// The items the farmer takes along, as a bit set indexed by item.
+    pub items: u32,
+}
+
+impl PredatorPrey {
+    /// Creates a new problem from the names of the items, the pairs that
+    /// cannot be left unattended together and the boat capacity.
+    pub fn new(items: Vec<Arc<str>>, constraints: Constraints, boat: u8) -> Self {
+        debug_assert!(items.len() <= MAX_ITEMS);
+        Self {
+            items,
+            constraints,
+            boat,
+        }
+    }
+
+    /// Creates a food chain where every item would eat the next one.
+    pub fn food_chain(items: &[&str]) -> Self {
+        Self::new(
+            items.iter().map(|&name| name.into()).collect(),
+            Constraints::food_chain(items.len()),
+            2,
+        )
+    }
+
+    /// Creates the Fox, Goose and Beans problem.
+    pub fn fox_goose_beans() -> Self {
+        Self::food_chain(&["fox", "goose", "beans"])
+    }
+
+    /// Creates the Wolf, Goat and Cabbage problem.
+    pub fn wolf_goat_cabbage() -> Self {
+        Self::food_chain(&["wolf", "goat", "cabbage"])
+    }
+
+    /// Gets the index of the item with the specified name.
+    pub fn item(&self, name: &str) -> Option<u8> {
+        self.items
+            .iter()
+            .position(|item| item.as_ref() == name)
+            .map(|index| index as u8)
+    }
+}
+
+impl Default for PredatorPrey {
+    fn default() -> Self {
+        Self::fox_goose_beans()
+    }
+}
+
+impl Problem for PredatorPrey {
+    type State = WorldState;
+
+    /// Builds the initial world state with the farmer and all items on the left river bank.
+    fn initial(&self) -> WorldState {
+        let everyone = ((1u64 << self.items.len()) - 1) as u32;
+        WorldState {
+            left: everyone,
+            right: 0,
+            boat: Boat::new(self.boat, RiverBank::Left),
+            names: self.items.iter().cloned().collect(),
+            constraints: Arc::new(self.constraints.clone()),
+        }
+    }
+
+    /// Supports minimizing the number of trips and the number of entities moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Moves]
+    }
+}
+
+impl Constraints {
+    /// Creates constraints that allow any items to be left unattended together.
+    pub const fn new() -> Self {
+        Self {
+            forbidden: Vec::new(),
+        }
+    }
+
+    /// Creates constraints where every item cannot be left alone with the next one.
+    pub fn food_chain(items: usize) -> Self {
+        (1..items as u8).fold(Self::new(), |constraints, item| {
+            constraints.forbid(item - 1, item)
+        })
+    }
+
+    /// Forbids leaving the two items unattended together.
+    pub fn forbid(mut self, a: u8, b: u8) -> Self {
+        debug_assert!((a as usize) < MAX_ITEMS && (b as usize) < MAX_ITEMS);
+        self.forbidden.push((a, b));
+        self
+    }
+
+    /// Determines whether the specified set of items may be left unattended.
+    pub fn allows(&self, items: u32) -> bool {
+        self.forbidden
+            .iter()
+            .all(|&(a, b)| items & (1 << a) == 0 || items & (1 << b) == 0)
+    }
+}
+
+impl WorldState {
+    /// Unpacks the world state into a tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there(&self) -> (u32, u32) {
+        match self.boat.bank {
+            RiverBank::Left => (self.left, self.right),
+            RiverBank::Right => (self.right, self.left),
+        }
+    }
+
+    /// Unpacks the world state into a (mutable) tuple of "this river bank" (i.e.
+    /// the bank that the boat is currently at) and "the opposite river bank".
+    pub fn here_there_mut(&mut self) -> (&mut u32, &mut u32) {
+        match self.boat.bank {
+            RiverBank::Left => (&mut self.left, &mut self.right),
+            RiverBank::Right => (&mut self.right, &mut self.left),
+        }
+    }
+
+    /// Renders the items in the specified set by their names.
+    fn render(&self, items: u32) -> String {
+        self.names
+            .iter()
+            .enumerate()
+            .filter(|(item, _)| items & (1 << item) != 0)
+            .map(|(_, name)| name)
+            .join(", ")
+    }
+
+    /// Renders a river bank, including the farmer if the boat is at it.
+    fn render_bank(&self, bank: RiverBank) -> String {
+        let items = match bank {
+            RiverBank::Left => self.left,
+            RiverBank::Right => self.right,
+        };
+
+        match (self.boat.bank == bank, items) {
+            (true, 0) => String::from("farmer"),
+            (true, _) => format!("farmer, {}", self.render(items)),
+            (false, 0) => String::from("empty"),
+            (false, _) => self.render(items),
+        }
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        PredatorPrey::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ left: {{ {} }}, right: {{ {} }}, boat: {:?} }}",
+            self.render(self.left),
+            self.render(self.right),
+            self.boat
+        )
+    }
+}
+
+impl WorldAction {
+    pub const fn new(items: u32) -> Self {
+        Self { items }
+    }
+
+    /// Gets the number of items taken along.
+    pub const fn len(&self) -> u32 {
+        self.items.count_ones()
+    }
+
+    /// Determines whether the farmer crosses alone.
+    pub const fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+}
+
+impl Debug for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ {:#b} }}", self.items)
+    }
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = u64;
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // All items are on the right river bank, and so is the farmer.
+        self.left == 0 && self.boat.bank == RiverBank::Right
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // No item is on both river banks, and those on the bank without the farmer are safe.
+        let (_, there) = self.here_there();
+        (self.left & self.right) == 0 && self.constraints.allows(there)
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let (here, _) = self.here_there();
+        let items = (0..MAX_ITEMS)
+            .filter(|item| here & (1 << item) != 0)
+            .collect_vec();
+
+        // The farmer takes one seat in the boat.
+        let seats = self.boat.capacity.saturating_sub(1) as usize;
+
+        let mut actions = Actions::new();
+        for size in 0..=seats.min(items.len()) {
+            for group in items.iter().combinations(size) {
+                let action = WorldAction::new(group.into_iter().fold(0, |set, i| set | 1 << i));
+                if action.is_applicable(self) {
+                    actions.push(action);
+                }
+            }
+        }
+
+        actions
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        let boat = if self.boat.bank == RiverBank::Left {
+            0
+        } else {
+            1
+        };
+        (self.left as u64) << 1 | boat
+    }
+
+    /// Estimates the remaining cost as the number of items still on the left river bank.
+    fn heuristic(&self) -> u32 {
+        self.left.count_ones()
+    }
+
+    /// Counts the items, counting any item on both river banks twice.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![self.left.count_ones() + self.right.count_ones()]
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        let (here, _) = state.here_there();
+
+        // The farmer rows the boat, but it can carry no more than its capacity.
+        if self.len() + 1 > state.boat.capacity as u32 {
+            return false;
+        }
+
+        // We cannot move items that are not on the current bank.
+        if here & self.items != self.items {
+            return false;
+        }
+
+        // The farmer arrives at the opposite bank, so only the items left behind are unattended.
+        state.constraints.allows(here & !self.items)
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        let (here, there) = state.here_there_mut();
+        *here &= !self.items;
+        *there |= self.items;
+        state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the farmer taking the same items back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the right bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == RiverBank::Right
+    }
+
+    /// Gets the cost of this action, where moves count the farmer and every item on the boat.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Moves => 1 + self.len(),
+            _ => 1,
+        }
+    }
+
+    /// Describes the farmer and the items crossing towards the bank the boat arrived at.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        let items = state
+            .names
+            .iter()
+            .enumerate()
+            .filter(|(item, _)| self.items & (1 << item) != 0)
+            .map(|(_, name)| name.to_string());
+
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            std::iter::once(String::from("farmer"))
+                .chain(items)
+                .collect(),
+        )
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        format!(
+            "left bank: {}; right bank: {}",
+            self.render_bank(RiverBank::Left),
+            self.render_bank(RiverBank::Right)
+        )
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, state: &WorldState) -> String {
+        // Note the conditions here are flipped as this represent the state
+        // after the action was applied.
+        let who = if self.is_empty() {
+            String::from("farmer")
+        } else {
+            format!("farmer with {}", state.render(self.items))
+        };
+
+        match state.boat.bank {
+            RiverBank::Right => format!(" → {who} cross forward"),
+            RiverBank::Left => format!(" ← {who} return"),
+        }
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.render_bank(RiverBank::Left),
+            self.render_bank(RiverBank::Right),
+        ]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn classic_puzzles_take_seven_crossings() {
+        for problem in [
+            PredatorPrey::fox_goose_beans(),
+            PredatorPrey::wolf_goat_cabbage(),
+        ] {
+            let plan = search(problem.initial()).expect("problem is solvable");
+            assert_eq!(plan.len(), 7);
+        }
+    }
+
+    #[test]
+    fn forbidden_pair_may_not_be_left_alone() {
+        let state = WorldState::default();
+
+        // Taking the fox leaves the goose with the beans.
+        assert!(!WorldAction::new(0b001).is_applicable(&state));
+
+        // Taking the goose leaves the fox with the beans.
+        assert!(WorldAction::new(0b010).is_applicable(&state));
+    }
+
+    #[test]
+    fn made_up_variant_is_solvable() {
+        // No two items may be left alone together, so the boat must hold the farmer and two items.
+        let problem = PredatorPrey::new(
+            vec!["fox".into(), "goose".into(), "beans".into()],
+            Constraints::new().forbid(0, 1).forbid(1, 2).forbid(0, 2),
+            3,
+        );
+        let plan = search(problem.initial()).expect("problem is solvable");
+        assert_eq!(plan.len(), 3);
+
+        let unsolvable = PredatorPrey { boat: 2, ..problem };
+        assert!(search(unsolvable.initial()).is_none());
+    }
+}
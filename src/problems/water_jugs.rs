@@ -0,0 +1,265 @@
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::Problem;
+use crate::search::{debug_assert_valid, Action, ActionSummary, Actions, State};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The Water Jugs problem.
+#[derive(Debug, Clone)]
+pub struct WaterJugs {
+    /// The capacities of the jugs.
+    pub capacities: Vec<u8>,
+    /// The amount of water to measure.
+    pub target: u8,
+}
+
+/// Describes the world state.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The amount of water in each jug.
+    pub jugs: Vec<u8>,
+    /// The capacity of each jug.
+    pub caps: Vec<u8>,
+    /// The amount of water to measure.
+    pub target: u8,
+}
+
+/// An action to apply.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WorldAction {
+    /// Fills the jug with the specified index to the brim.
+    Fill(usize),
+    /// Empties the jug with the specified index.
+    Empty(usize),
+    /// Pours water from one jug into another until either the first
+    /// one is empty or the second one is full.
+    Pour {
+        /// The index of the jug to pour from.
+        from: usize,
+        /// The index of the jug to pour into.
+        to: usize,
+    },
+}
+
+impl WaterJugs {
+    /// Creates a new problem from the capacities of the jugs and the amount of water to measure.
+    pub const fn new(capacities: Vec<u8>, target: u8) -> Self {
+        Self { capacities, target }
+    }
+}
+
+impl Default for WaterJugs {
+    fn default() -> Self {
+        Self::new(vec![3, 5], 4)
+    }
+}
+
+impl Problem for WaterJugs {
+    type State = WorldState;
+
+    /// Builds the initial world state with all jugs empty.
+    fn initial(&self) -> WorldState {
+        WorldState::new(
+            vec![0; self.capacities.len()],
+            self.capacities.clone(),
+            self.target,
+        )
+    }
+}
+
+impl WorldState {
+    /// Creates a new problem state from the amount of water in and the capacity of each jug.
+    pub fn new(jugs: Vec<u8>, caps: Vec<u8>, target: u8) -> Self {
+        debug_assert_eq!(jugs.len(), caps.len());
+        Self { jugs, caps, target }
+    }
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        WaterJugs::default().initial()
+    }
+}
+
+impl Debug for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ jugs: {:?}, target: {} }}", self.jugs, self.target)
+    }
+}
+
+impl State for WorldState {
+    type Action = WorldAction;
+    type Hash = Vec<u8>;
+
+    /// Tests whether the specified world state is a goal state.
+    fn is_goal(&self) -> bool {
+        // Any jug holds exactly the amount of water to measure.
+        self.jugs.contains(&self.target)
+    }
+
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // No jug holds more water than it can.
+        self.jugs.len() == self.caps.len()
+            && self
+                .jugs
+                .iter()
+                .zip(&self.caps)
+                .all(|(jug, cap)| jug <= cap)
+    }
+
+    /// Expands the world state into new (applicable) actions.
+    /// If this state cannot be expanded, an empty vector is returned.
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let indexes = 0..self.jugs.len();
+        indexes
+            .clone()
+            .map(WorldAction::Fill)
+            .chain(indexes.clone().map(WorldAction::Empty))
+            .chain(
+                indexes
+                    .clone()
+                    .cartesian_product(indexes)
+                    .map(|(from, to)| WorldAction::Pour { from, to }),
+            )
+            .filter(|action| action.is_applicable(self))
+            .collect()
+    }
+
+    /// Gets the hash of this state.
+    fn unique_hash(&self) -> Self::Hash {
+        // The capacities and the target never change.
+        self.jugs.clone()
+    }
+}
+
+impl Action for WorldAction {
+    type State = WorldState;
+
+    /// Tests whether an action is applicable in the given (usually current) world state.
+    fn is_applicable(&self, state: &Self::State) -> bool {
+        // Only actions that change the amount of water in a jug are applicable.
+        let jugs = state.jugs.len();
+        match *self {
+            WorldAction::Fill(jug) => jug < jugs && state.jugs[jug] < state.caps[jug],
+            WorldAction::Empty(jug) => jug < jugs && state.jugs[jug] > 0,
+            WorldAction::Pour { from, to } => {
+                from < jugs
+                    && to < jugs
+                    && from != to
+                    && state.jugs[from] > 0
+                    && state.jugs[to] < state.caps[to]
+            }
+        }
+    }
+
+    /// Applies the specified action to the specified world state,
+    /// returning the new state after the action was applied.
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
+        match *self {
+            WorldAction::Fill(jug) => state.jugs[jug] = state.caps[jug],
+            WorldAction::Empty(jug) => state.jugs[jug] = 0,
+            WorldAction::Pour { from, to } => {
+                let amount = state.jugs[from].min(state.caps[to] - state.jugs[to]);
+                state.jugs[from] -= amount;
+                state.jugs[to] += amount;
+            }
+        }
+        debug_assert_valid(before, &state);
+        state
+    }
+
+    /// Gets the action undoing this one, as far as possible.
+    ///
+    /// Filling and emptying a jug lose the amount of water it held, and pouring back
+    /// only restores the previous state if the first jug was emptied, so the result
+    /// is not an exact inverse in general.
+    fn inverse(&self) -> Self {
+        match *self {
+            WorldAction::Fill(jug) => WorldAction::Empty(jug),
+            WorldAction::Empty(jug) => WorldAction::Fill(jug),
+            WorldAction::Pour { from, to } => WorldAction::Pour { from: to, to: from },
+        }
+    }
+
+    /// Describes the water flowing from the tap, between the jugs or down the drain.
+    fn describe(&self, _state: &Self::State) -> ActionSummary {
+        let direction = match self {
+            WorldAction::Fill(jug) => format!("tap to jug {}", jug + 1),
+            WorldAction::Empty(jug) => format!("jug {} to drain", jug + 1),
+            WorldAction::Pour { from, to } => format!("jug {} to jug {}", from + 1, to + 1),
+        };
+        ActionSummary::new(direction, vec![String::from("water")])
+    }
+}
+
+impl PrettyPrintState for WorldState {
+    /// Pretty-prints a world state.
+    fn pretty_print(&self) -> String {
+        self.jugs
+            .iter()
+            .zip(&self.caps)
+            .map(|(water, capacity)| format!("[{water}/{capacity}]"))
+            .join(" ")
+    }
+}
+
+impl PrettyPrintAction<WorldState> for WorldAction {
+    /// Pretty-prints an action
+    fn pretty_print(&self, _state: &WorldState) -> String {
+        match self {
+            WorldAction::Fill(jug) => format!("fill jug {}", jug + 1),
+            WorldAction::Empty(jug) => format!("empty jug {}", jug + 1),
+            WorldAction::Pour { from, to } => format!("pour jug {} into jug {}", from + 1, to + 1),
+        }
+    }
+}
+
+impl MarkdownState for WorldState {
+    fn headings(&self) -> Vec<String> {
+        (1..=self.jugs.len())
+            .map(|jug| format!("Jug {jug}"))
+            .collect()
+    }
+
+    fn cells(&self) -> Vec<String> {
+        self.jugs
+            .iter()
+            .zip(&self.caps)
+            .map(|(water, capacity)| format!("{water}/{capacity}"))
+            .collect()
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{search, solve, SearchOutcome};
+
+    #[test]
+    fn classic_puzzle_is_solved() {
+        let plan = search(WorldState::default()).expect("problem is solvable");
+        assert_eq!(plan.len(), 6);
+        assert_eq!(plan.goal().pretty_print(), "[3/3] [4/5]");
+    }
+
+    #[test]
+    fn unmeasurable_target_is_unsolvable() {
+        // Only even amounts can be measured with jugs of even capacities.
+        let initial = WaterJugs::new(vec![2, 4], 3).initial();
+        assert!(matches!(solve(initial), SearchOutcome::Unsolvable { .. }));
+    }
+
+    #[test]
+    fn overflowing_jug_is_invalid() {
+        let mut state = WaterJugs::new(vec![3, 5], 4).initial();
+        assert!(state.is_valid());
+        state.jugs[0] = 4;
+        assert!(!state.is_valid());
+    }
+}
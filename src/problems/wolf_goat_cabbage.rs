@@ -1,10 +1,34 @@
-use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
-use crate::search::{Action, State};
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState, RenderStyle};
+use crate::problems::Problem;
+use crate::search::{
+    debug_assert_conserved, debug_assert_valid, Action, ActionSummary, Actions, State,
+};
+use crate::strategies::Objective;
 use itertools::Itertools;
-use std::fmt::{Debug, Formatter};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// The Wolf, Goat and Cabbage problem.
+#[derive(Debug, Clone)]
+pub struct WolfGoatCabbage {
+    /// The number of farmers on the left river bank.
+    pub farmers: u8,
+    /// The number of wolves on the left river bank.
+    pub wolves: u8,
+    /// The number of goats on the left river bank.
+    pub goats: u8,
+    /// The number of cabbages on the left river bank.
+    pub cabbages: u8,
+    /// The capacity of the boat.
+    pub boat: u8,
+}
 
 /// Describes the world state.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+///
+/// Two states are equal if their river banks and boat are equal, regardless of their plan depth.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldState {
     /// The plan depth.
     pub plan_depth: usize,
@@ -16,7 +40,7 @@ pub struct WorldState {
     pub boat: Boat,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum RiverBank {
     /// The left river bank.
     Left,
@@ -24,7 +48,7 @@ pub enum RiverBank {
     Right,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Boat {
     /// The capacity of the boat.
     pub capacity: u8,
@@ -33,7 +57,7 @@ pub struct Boat {
 }
 
 /// Describes the state on a river bank.
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RiverBankState {
     /// The number of farmers on this bank.
     pub farmers: u8,
@@ -46,7 +70,7 @@ pub struct RiverBankState {
 }
 
 /// An action to apply.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldAction {
     /// How many farmers to move.
     pub farmers: u8,
@@ -58,6 +82,43 @@ pub struct WorldAction {
     pub cabbages: u8,
 }
 
+impl WolfGoatCabbage {
+    /// Creates a new problem from the number of farmers, wolves, goats and cabbages
+    /// and the boat capacity.
+    pub const fn new(farmers: u8, wolves: u8, goats: u8, cabbages: u8, boat: u8) -> Self {
+        Self {
+            farmers,
+            wolves,
+            goats,
+            cabbages,
+            boat,
+        }
+    }
+}
+
+impl Default for WolfGoatCabbage {
+    fn default() -> Self {
+        Self::new(1, 1, 1, 1, 2)
+    }
+}
+
+impl Problem for WolfGoatCabbage {
+    type State = WorldState;
+
+    /// Builds the initial world state with everyone on the left river bank.
+    fn initial(&self) -> WorldState {
+        let left = RiverBankState::new(self.farmers, self.wolves, self.goats, self.cabbages);
+        let right = RiverBankState::new(0, 0, 0, 0);
+        let boat = Boat::new(self.boat, RiverBank::Left);
+        WorldState::new(0, left, right, boat)
+    }
+
+    /// Supports minimizing the number of trips and the number of entities moved.
+    fn objectives(&self) -> &'static [Objective] {
+        &[Objective::Trips, Objective::Moves]
+    }
+}
+
 impl WorldState {
     /// Creates a new problem state from the left and right river bank states.
     pub const fn new(
@@ -103,10 +164,23 @@ impl WorldState {
 
 impl Default for WorldState {
     fn default() -> Self {
-        let left = RiverBankState::new(1, 1, 1, 1);
-        let right = RiverBankState::new(0, 0, 0, 0);
-        let boat = Boat::new(2, RiverBank::Left);
-        WorldState::new(0, left, right, boat)
+        WolfGoatCabbage::default().initial()
+    }
+}
+
+impl PartialEq for WorldState {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.right == other.right && self.boat == other.boat
+    }
+}
+
+impl Eq for WorldState {}
+
+impl Hash for WorldState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.left.hash(state);
+        self.right.hash(state);
+        self.boat.hash(state);
     }
 }
 
@@ -120,6 +194,26 @@ impl Debug for WorldState {
     }
 }
 
+/// Formats the state as prose, naming the river bank the boat is at.
+///
+/// ```
+/// use river_crossing::problems::wolf_goat_cabbage::WorldState;
+///
+/// assert_eq!(
+///     WorldState::default().to_string(),
+///     "left bank: farmer, wolf, goat and cabbage; right bank: empty; boat on the left"
+/// );
+/// ```
+impl Display for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "left bank: {}; right bank: {}; boat on the {}",
+            self.left, self.right, self.boat.bank
+        )
+    }
+}
+
 impl Boat {
     /// Creates a new river bank state from the number of humans and zombies.
     pub const fn new(capacity: u8, bank: RiverBank) -> Self {
@@ -159,6 +253,19 @@ impl Debug for RiverBankState {
     }
 }
 
+/// Formats the river bank as a list of what is on it.
+///
+/// ```
+/// use river_crossing::problems::wolf_goat_cabbage::RiverBankState;
+///
+/// assert_eq!(RiverBankState::new(1, 0, 1, 0).to_string(), "farmer and goat");
+/// ```
+impl Display for RiverBankState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_bank(self, &RenderStyle::default()))
+    }
+}
+
 impl WorldAction {
     pub const fn new(farmers: u8, wolves: u8, goats: u8, cabbages: u8) -> Self {
         Self {
@@ -188,6 +295,28 @@ impl Debug for WorldAction {
     }
 }
 
+/// Formats the action as a list of what is on the boat.
+///
+/// ```
+/// use river_crossing::problems::wolf_goat_cabbage::WorldAction;
+///
+/// assert_eq!(WorldAction::new(1, 1, 0, 0).to_string(), "farmer and wolf");
+/// ```
+impl Display for WorldAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&readable_action(self, &RenderStyle::default()))
+    }
+}
+
+impl Display for RiverBank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiverBank::Left => f.write_str("left"),
+            RiverBank::Right => f.write_str("right"),
+        }
+    }
+}
+
 impl RiverBank {
     /// Switches from the left bank to the right and vice versa.
     pub fn switch_bank(&self) -> Self {
@@ -200,7 +329,7 @@ impl RiverBank {
 
 impl State for WorldState {
     type Action = WorldAction;
-    type Hash = usize;
+    type Hash = u64;
 
     /// Tests whether the specified world state is a goal state.
     fn is_goal(&self) -> bool {
@@ -208,10 +337,18 @@ impl State for WorldState {
         self.left.is_empty()
     }
 
+    /// Tests whether the specified world state is legal in itself.
+    fn is_valid(&self) -> bool {
+        // On neither river bank are wolves and goats, or goats and cabbages, left unattended.
+        [&self.left, &self.right].iter().all(|bank| {
+            bank.farmers > 0 || bank.goats == 0 || (bank.wolves == 0 && bank.cabbages == 0)
+        })
+    }
+
     /// Expands the world state into new (applicable) actions.
     /// If this state cannot be expanded, an empty vector is returned.
-    fn get_actions(&self) -> Vec<WorldAction> {
-        let mut actions = Vec::with_capacity(5);
+    fn get_actions(&self) -> Actions<WorldAction> {
+        let mut actions = Actions::with_capacity(5);
 
         let bank = self.boat_bank();
 
@@ -248,16 +385,36 @@ impl State for WorldState {
 
     /// Gets the hash of this state.
     fn unique_hash(&self) -> Self::Hash {
+        // We explicitly use a 64-bit hash here since shifting the farmers
+        // by 32 bits would lose them on 32-bit targets.
         let boat = if self.boat.bank == RiverBank::Left {
             0
         } else {
             1
         };
-        (self.left.farmers as usize) << 32
-            | (self.left.wolves as usize) << 24
-            | (self.left.goats as usize) << 16
-            | (self.left.cabbages as usize) << 8
-            | (boat as usize)
+        (self.left.farmers as u64) << 32
+            | (self.left.wolves as u64) << 24
+            | (self.left.goats as u64) << 16
+            | (self.left.cabbages as u64) << 8
+            | (boat as u64)
+    }
+
+    /// Estimates the remaining cost as the number of entities still on the left river bank.
+    fn heuristic(&self) -> u32 {
+        self.left.farmers as u32
+            + self.left.wolves as u32
+            + self.left.goats as u32
+            + self.left.cabbages as u32
+    }
+
+    /// Counts the farmers, wolves, goats and cabbages.
+    fn total_entities(&self) -> Vec<u32> {
+        vec![
+            self.left.farmers as u32 + self.right.farmers as u32,
+            self.left.wolves as u32 + self.right.wolves as u32,
+            self.left.goats as u32 + self.right.goats as u32,
+            self.left.cabbages as u32 + self.right.cabbages as u32,
+        ]
     }
 }
 
@@ -268,6 +425,15 @@ impl Action for WorldAction {
     fn is_applicable(&self, state: &Self::State) -> bool {
         let (here, there) = state.here_there();
 
+        // We cannot move more than there is on the current bank.
+        if self.farmers > here.farmers
+            || self.wolves > here.wolves
+            || self.goats > here.goats
+            || self.cabbages > here.cabbages
+        {
+            return false;
+        }
+
         // Someone must be on the boat, but the boat capacity must not be exceeded.
         if self.is_empty() || self.len() > state.boat.capacity as _ {
             return false;
@@ -284,7 +450,9 @@ impl Action for WorldAction {
             && (here.goats - self.goats) > 0
         {
             return false;
-        } else if (there.farmers + self.farmers) == 0
+        }
+
+        if (there.farmers + self.farmers) == 0
             && (there.wolves + self.wolves) > 0
             && (there.goats + self.goats) > 0
         {
@@ -297,7 +465,9 @@ impl Action for WorldAction {
             && (here.cabbages - self.cabbages) > 0
         {
             return false;
-        } else if (there.farmers + self.farmers) == 0
+        }
+
+        if (there.farmers + self.farmers) == 0
             && (there.goats + self.goats) > 0
             && (there.cabbages + self.cabbages) > 0
         {
@@ -311,8 +481,8 @@ impl Action for WorldAction {
 
     /// Applies the specified action to the specified world state,
     /// returning the new state after the action was applied.
-    fn apply(&self, state: &Self::State) -> Self::State {
-        let mut state = state.clone();
+    fn apply(&self, before: &Self::State) -> Self::State {
+        let mut state = before.clone();
         let (here, there) = state.here_there_mut();
         here.farmers -= self.farmers;
         here.wolves -= self.wolves;
@@ -324,18 +494,60 @@ impl Action for WorldAction {
         there.cabbages += self.cabbages;
         state.plan_depth += 1;
         state.boat = state.boat.switch_bank();
+        debug_assert_conserved(before, &state);
+        debug_assert_valid(before, &state);
         state
     }
+
+    /// Gets the action undoing this one. Since the boat always leaves from the bank it is at,
+    /// this is the same entities crossing back.
+    fn inverse(&self) -> Self {
+        self.clone()
+    }
+
+    /// Determines whether this action rows the boat back, i.e. whether it is at the right bank.
+    fn is_return(&self, state: &Self::State) -> bool {
+        state.boat.bank == RiverBank::Right
+    }
+
+    /// Gets the cost of this action, where moves count every entity on the boat.
+    fn cost(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Moves => {
+                self.farmers as u32 + self.wolves as u32 + self.goats as u32 + self.cabbages as u32
+            }
+            _ => 1,
+        }
+    }
+
+    /// Describes the farmers, wolves, goats and cabbages crossing towards the bank the boat
+    /// arrived at.
+    fn describe(&self, state: &Self::State) -> ActionSummary {
+        ActionSummary::crossing(
+            state.boat.bank == RiverBank::Left,
+            ActionSummary::repeated([
+                (self.farmers, "farmer"),
+                (self.wolves, "wolf"),
+                (self.goats, "goat"),
+                (self.cabbages, "cabbage"),
+            ]),
+        )
+    }
 }
 
 impl PrettyPrintState for WorldState {
     /// Pretty-prints a world state.
     fn pretty_print(&self) -> String {
+        self.pretty_print_styled(&RenderStyle::default())
+    }
+
+    /// Pretty-prints a world state using the symbols of the specified style.
+    fn pretty_print_styled(&self, style: &RenderStyle) -> String {
         format!(
             "At t={}; left bank: {}; right bank: {}",
             self.plan_depth,
-            readable_bank(&self.left),
-            readable_bank(&self.right)
+            readable_bank(&self.left, style),
+            readable_bank(&self.right, style)
         )
     }
 }
@@ -343,17 +555,22 @@ impl PrettyPrintState for WorldState {
 impl PrettyPrintAction<WorldState> for WorldAction {
     /// Pretty-prints an action
     fn pretty_print(&self, state: &WorldState) -> String {
+        self.pretty_print_styled(state, &RenderStyle::default())
+    }
+
+    /// Pretty-prints an action using the symbols of the specified style.
+    fn pretty_print_styled(&self, state: &WorldState, style: &RenderStyle) -> String {
         // Note the conditions here are flipped as this represent the state
         // after the action was applied.
         match state.boat.bank {
             RiverBank::Right => format!(
                 " → {} cross{} forward",
-                readable_action(self),
+                readable_action(self, style),
                 if self.len() == 1 { "es" } else { "" },
             ),
             RiverBank::Left => format!(
                 " ← {} return{}",
-                readable_action(self),
+                readable_action(self, style),
                 if self.len() == 1 { "s alone" } else { "" },
             ),
         }
@@ -361,41 +578,34 @@ impl PrettyPrintAction<WorldState> for WorldAction {
 }
 
 /// Makes a human-readable list of a river bank state.
-fn readable_bank(bank: &RiverBankState) -> String {
-    readable_list(bank.farmers, bank.wolves, bank.goats, bank.cabbages)
+fn readable_bank(bank: &RiverBankState, style: &RenderStyle) -> String {
+    readable_list(bank.farmers, bank.wolves, bank.goats, bank.cabbages, style)
 }
 
 /// Makes a human-readable list of a river bank state.
-fn readable_action(bank: &WorldAction) -> String {
-    readable_list(bank.farmers, bank.wolves, bank.goats, bank.cabbages)
+fn readable_action(bank: &WorldAction, style: &RenderStyle) -> String {
+    readable_list(bank.farmers, bank.wolves, bank.goats, bank.cabbages, style)
 }
 
-/// Makes a human-readable list of the provided numbers.
-fn readable_list(farmers: u8, wolves: u8, goats: u8, cabbages: u8) -> String {
+/// Makes a human-readable list of the provided numbers, using the symbols
+/// of the specified style where there are any.
+fn readable_list(farmers: u8, wolves: u8, goats: u8, cabbages: u8, style: &RenderStyle) -> String {
     let mut parts = Vec::new();
 
-    if farmers == 1 {
-        parts.push("farmer".into())
-    } else if farmers > 0 {
-        parts.push(format!("{} farmers", farmers))
-    }
-
-    if wolves == 1 {
-        parts.push("wolf".into())
-    } else if wolves > 0 {
-        parts.push(format!("{} wolves", wolves))
-    }
-
-    if goats == 1 {
-        parts.push("goat".into())
-    } else if goats > 0 {
-        parts.push(format!("{} goats", goats))
-    }
-
-    if cabbages == 1 {
-        parts.push("cabbage".into())
-    } else if cabbages > 0 {
-        parts.push(format!("{} cabbages", cabbages))
+    let entities = [
+        (farmers, &style.farmer, "farmer", "farmers"),
+        (wolves, &style.wolf, "wolf", "wolves"),
+        (goats, &style.goat, "goat", "goats"),
+        (cabbages, &style.cabbage, "cabbage", "cabbages"),
+    ];
+
+    for (count, symbol, singular, plural) in entities {
+        match (count, symbol) {
+            (0, _) => {}
+            (_, Some(symbol)) => parts.push(symbol.repeat(count as _)),
+            (1, None) => parts.push(singular.into()),
+            (_, None) => parts.push(format!("{} {}", count, plural)),
+        }
     }
 
     if parts.is_empty() {
@@ -418,3 +628,62 @@ fn readable_list(farmers: u8, wolves: u8, goats: u8, cabbages: u8) -> String {
         })
         .join("")
 }
+
+impl MarkdownState for WorldState {
+    fn cells(&self) -> Vec<String> {
+        vec![self.left.to_string(), self.right.to_string()]
+    }
+}
+
+impl MarkdownAction<WorldState> for WorldAction {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+    use std::collections::HashSet;
+
+    #[test]
+    fn inverse_undoes_action() {
+        let plan = search(WorldState::default()).expect("problem is solvable");
+        for (_, state) in plan {
+            for action in state.get_actions() {
+                let next = action.apply(&state);
+                assert!(action.inverse().is_applicable(&next));
+                assert_eq!(action.inverse().apply(&next), state);
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_action_is_not_applicable() {
+        let state = WorldState::default();
+        assert!(!WorldAction::new(5, 0, 0, 0).is_applicable(&state));
+
+        // Even if the boat could carry everyone, the bank must hold enough.
+        let state = WorldState::new(
+            0,
+            RiverBankState::new(1, 1, 1, 1),
+            RiverBankState::new(0, 0, 0, 0),
+            Boat::new(8, RiverBank::Left),
+        );
+        assert!(!WorldAction::new(1, 0, 2, 0).is_applicable(&state));
+    }
+
+    #[test]
+    fn hash_distinguishes_farmers() {
+        let hashes: HashSet<u64> = (1..=4)
+            .map(|farmers| {
+                WorldState::new(
+                    0,
+                    RiverBankState::new(farmers, 1, 1, 1),
+                    RiverBankState::new(0, 0, 0, 0),
+                    Boat::new(2, RiverBank::Left),
+                )
+                .unique_hash()
+            })
+            .collect();
+
+        assert_eq!(hashes.len(), 4);
+    }
+}
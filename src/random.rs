@@ -0,0 +1,37 @@
+/// A small, seedable pseudo-random number generator based on SplitMix64.
+///
+/// This is by no means cryptographically secure, but it is fast, has a good
+/// distribution for the purpose of randomizing searches, and - most importantly -
+/// produces the same sequence for the same seed on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator from the specified seed.
+    pub const fn seed_from_u64(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Gets the next pseudo-random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Gets a pseudo-random number in the range `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        debug_assert_ne!(bound, 0);
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Shuffles the items in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
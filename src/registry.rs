@@ -0,0 +1,922 @@
+//! The problems that can be solved by name, shared by the command-line application and the
+//! [`wasm`](crate::wasm) entry point.
+//!
+//! Every problem describes its instances by the arguments of a [`clap`] subcommand of its
+//! name, e.g. `humans-and-zombies --humans 5 --zombies 5 --boat 3`, which are turned into the
+//! instance by [`ProblemInfo::build`].
+
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::bridge_and_torch::{BridgeAndTorch, Person};
+use crate::problems::generic::{Generic, Spec};
+use crate::problems::hanoi::{Hanoi, MAX_DISKS};
+use crate::problems::humans_and_zombies::{
+    random_solvable, HumansAndZombies, RiverBank, RowingCost, SafetyRule,
+};
+use crate::problems::jealous_husbands::{JealousHusbands, MAX_COUPLES};
+use crate::problems::missionaries_cannibals::MissionariesCannibals;
+use crate::problems::predator_prey::{Constraints, PredatorPrey, MAX_ITEMS};
+use crate::problems::water_jugs::WaterJugs;
+use crate::problems::wolf_goat_cabbage::WolfGoatCabbage;
+use crate::problems::Problem;
+use crate::search::{Action, State};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+/// A problem that can be solved by name, using the subcommand of its name.
+pub struct ProblemInfo {
+    /// The name of the subcommand.
+    pub name: &'static str,
+    /// A short description of the problem.
+    pub description: &'static str,
+    /// Adds the arguments describing an instance of the problem to its subcommand.
+    arguments: fn(Command) -> Command,
+    /// Builds the instance described by the arguments of the subcommand.
+    build: Builder,
+}
+
+/// Builds a problem instance from the arguments of its subcommand, using the seed for
+/// random instances.
+type Builder = fn(&ArgMatches, u64) -> Result<Instance, String>;
+
+/// A problem instance built from the arguments of its subcommand.
+pub struct Instance {
+    /// The problem.
+    pub problem: AnyProblem,
+    /// A remark on how the instance came about, e.g. the numbers generated for a random one.
+    pub remark: Option<String>,
+}
+
+/// Any of the problems that can be solved by name.
+pub enum AnyProblem {
+    /// The Humans and Zombies problem.
+    HumansAndZombies(HumansAndZombies),
+    /// The Bridge and Torch problem.
+    BridgeAndTorch(BridgeAndTorch),
+    /// The Wolves, Goats and Cabbages problem.
+    WolfGoatCabbage(WolfGoatCabbage),
+    /// The Missionaries and Cannibals problem.
+    MissionariesCannibals(MissionariesCannibals),
+    /// The Jealous Husbands problem.
+    JealousHusbands(JealousHusbands),
+    /// A predator/prey/bait crossing.
+    PredatorPrey(PredatorPrey),
+    /// The Tower of Hanoi.
+    Hanoi(Hanoi),
+    /// The Water Jugs problem.
+    WaterJugs(WaterJugs),
+    /// A crossing puzzle described by a spec.
+    Generic(Generic),
+}
+
+/// Does something with a problem regardless of its type, see [`AnyProblem::visit`].
+///
+/// This works around the cyclic dependencies of associated types on the State and Action
+/// traits, which keep the problems from being used as trait objects.
+pub trait ProblemVisitor {
+    /// The result of visiting the problem.
+    type Output;
+
+    /// Does something with the problem.
+    fn visit<P, S, A>(self, problem: P) -> Self::Output
+    where
+        P: Problem<State = S> + 'static,
+        S: State<Action = A>
+            + Clone
+            + Eq
+            + Send
+            + Sync
+            + Debug
+            + PrettyPrintState
+            + MarkdownState
+            + Serialize
+            + DeserializeOwned,
+        A: Action<State = S>
+            + Clone
+            + Send
+            + Debug
+            + PrettyPrintAction<S>
+            + MarkdownAction<S>
+            + Serialize
+            + DeserializeOwned,
+        S::Hash: Eq + Hash;
+}
+
+impl AnyProblem {
+    /// Passes the problem to the visitor.
+    pub fn visit<V: ProblemVisitor>(self, visitor: V) -> V::Output {
+        match self {
+            Self::HumansAndZombies(problem) => visitor.visit(problem),
+            Self::BridgeAndTorch(problem) => visitor.visit(problem),
+            Self::WolfGoatCabbage(problem) => visitor.visit(problem),
+            Self::MissionariesCannibals(problem) => visitor.visit(problem),
+            Self::JealousHusbands(problem) => visitor.visit(problem),
+            Self::PredatorPrey(problem) => visitor.visit(problem),
+            Self::Hanoi(problem) => visitor.visit(problem),
+            Self::WaterJugs(problem) => visitor.visit(problem),
+            Self::Generic(problem) => visitor.visit(problem),
+        }
+    }
+}
+
+impl From<AnyProblem> for Instance {
+    fn from(problem: AnyProblem) -> Self {
+        Self {
+            problem,
+            remark: None,
+        }
+    }
+}
+
+/// The problems that can be solved, in the order they are listed.
+static PROBLEMS: &[ProblemInfo] = &[
+    ProblemInfo {
+        name: "humans-and-zombies",
+        description: "The Humans and Zombies problem",
+        arguments: humans_and_zombies_arguments,
+        build: |matches, seed| Ok(humans_and_zombies(matches, seed)),
+    },
+    ProblemInfo {
+        name: "bridge-and-torch",
+        description: "The Bridge and Torch problem",
+        arguments: bridge_and_torch_arguments,
+        build: |matches, _| Ok(AnyProblem::BridgeAndTorch(bridge_and_torch(matches)?).into()),
+    },
+    ProblemInfo {
+        name: "wolf-goat-cabbage",
+        description: "The Wolves, Goats and Cabbages problem",
+        arguments: wolf_goat_cabbage_arguments,
+        build: |matches, _| Ok(AnyProblem::WolfGoatCabbage(wolf_goat_cabbage(matches)).into()),
+    },
+    ProblemInfo {
+        name: "mc",
+        description: "The textbook Missionaries and Cannibals problem",
+        arguments: missionaries_cannibals_arguments,
+        build: |matches, _| {
+            Ok(AnyProblem::MissionariesCannibals(missionaries_cannibals(matches)).into())
+        },
+    },
+    ProblemInfo {
+        name: "jealous-husbands",
+        description: "The Jealous Husbands problem",
+        arguments: jealous_husbands_arguments,
+        build: |matches, _| Ok(AnyProblem::JealousHusbands(jealous_husbands(matches)).into()),
+    },
+    ProblemInfo {
+        name: "fox-goose-beans",
+        description: "The Fox, Goose and Beans problem, or any other predator/prey/bait crossing",
+        arguments: predator_prey_arguments,
+        build: |matches, _| Ok(AnyProblem::PredatorPrey(predator_prey(matches)?).into()),
+    },
+    ProblemInfo {
+        name: "hanoi",
+        description: "The Tower of Hanoi",
+        arguments: hanoi_arguments,
+        build: |matches, _| Ok(AnyProblem::Hanoi(hanoi(matches)).into()),
+    },
+    ProblemInfo {
+        name: "water-jugs",
+        description: "The Water Jugs problem",
+        arguments: water_jugs_arguments,
+        build: |matches, _| Ok(AnyProblem::WaterJugs(water_jugs(matches)).into()),
+    },
+    ProblemInfo {
+        name: "generic",
+        description: "A crossing puzzle described by a JSON spec",
+        arguments: generic_arguments,
+        build: |matches, _| Ok(AnyProblem::Generic(generic(matches)?).into()),
+    },
+];
+
+/// Gets the problems that can be solved; adding a problem only takes registering it here.
+pub fn problems() -> &'static [ProblemInfo] {
+    PROBLEMS
+}
+
+/// Finds the registered problem with the specified name.
+pub fn find(name: &str) -> Result<&'static ProblemInfo, String> {
+    problems()
+        .iter()
+        .find(|problem| problem.name == name)
+        .ok_or_else(|| format!("Unknown problem: {name}"))
+}
+
+impl ProblemInfo {
+    /// Builds the subcommand of the problem, taking the arguments describing an instance.
+    pub fn command(&self) -> Command {
+        (self.arguments)(Command::new(self.name).about(self.description))
+    }
+
+    /// Builds the instance described by the arguments of the subcommand, using the seed for
+    /// random instances.
+    pub fn build(&self, matches: &ArgMatches, seed: u64) -> Result<Instance, String> {
+        (self.build)(matches, seed)
+    }
+}
+
+/// Describes an error parsing the arguments of a subcommand in a single line.
+pub fn describe_error(error: &clap::Error) -> String {
+    let message = error.render().to_string();
+    let message = message.lines().next().unwrap_or_default();
+    message.trim_start_matches("error: ").to_string()
+}
+
+/// Determines whether the crossings take longer the more entities are aboard,
+/// as selected by the `--cost` argument of the problems supporting it.
+pub fn rows_linearly(matches: &ArgMatches) -> bool {
+    matches
+        .try_get_one::<String>("cost")
+        .ok()
+        .flatten()
+        .is_some_and(|cost| cost == "linear")
+}
+
+/// Adds the arguments of the Humans and Zombies problem to its subcommand.
+fn humans_and_zombies_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("humans")
+                .short('H')
+                .long("humans")
+                .help("The number of humans on the river bank")
+                .default_value("3")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("zombies")
+                .short('Z')
+                .long("zombies")
+                .help("The number of zombies on the river bank")
+                .default_value("3")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("boat")
+                .short('B')
+                .long("boat")
+                .help("The capacity of the boat")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("start-side")
+                .long("start-side")
+                .help("The river bank everyone starts on, along with the boat")
+                .default_value("left")
+                .value_parser(["left", "right"])
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("require-rower")
+                .long("require-rower")
+                .help("Requires a human on the boat to row it")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("allow-empty-crossing")
+                .long("allow-empty-crossing")
+                .help("Allows the boat to drift across with nobody aboard")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("safety-margin")
+                .long("safety-margin")
+                .help("Allows zombies to outnumber humans by at most this many")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u8))
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("cost")
+                .long("cost")
+                .help("How long a crossing takes: always the same, or one unit plus one per entity aboard; linear costs minimize the time by default")
+                .default_value("constant")
+                .value_parser(["constant", "linear"])
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("safest")
+                .long("safest")
+                .help("Picks the solution with the minimal number of trips in which the zombies come least close to outnumbering the humans")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("random")
+                .long("random")
+                .help("Generates a random solvable instance from the seed instead of using the given numbers")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("max-population")
+                .long("max-population")
+                .help("The maximum number of humans and zombies each in a random instance")
+                .default_value("10")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the Bridge and Torch problem to its subcommand.
+fn bridge_and_torch_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("bridge")
+                .short('B')
+                .long("bridge")
+                .help("The capacity of the bridge, i.e. how many people may cross at once")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("return-capacity")
+                .long("return-capacity")
+                .help("How many people may cross back at once, if not as many as the bridge holds")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("torch")
+                .short('T')
+                .long("torch")
+                .help("The capacity of the torch, i.e. how long it will burn")
+                .default_value("15")
+                .value_name("MINUTES")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("people")
+                .short('P')
+                .long("person")
+                .help("The walking time of a person to add to the problem, optionally preceded by their name")
+                .value_name("[NAME:]MINUTES")
+                .value_parser(parse_person)
+                .allow_negative_numbers(false)
+                .action(ArgAction::Append)
+                .num_args(1..),
+        )
+        .arg(
+            Arg::new("before")
+                .long("before")
+                .help("Requires the person named A to arrive on the right before the person named B crosses")
+                .value_name("A:B")
+                .value_parser(parse_ordering)
+                .action(ArgAction::Append)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("refuel")
+                .long("refuel")
+                .help("Tops the torch up to the given time after every crossing")
+                .value_name("MAX")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the Wolves, Goats and Cabbages problem to its subcommand.
+fn wolf_goat_cabbage_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("farmers")
+                .short('F')
+                .long("farmers")
+                .help("The number of farmers on the river bank")
+                .default_value("1")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("wolves")
+                .short('W')
+                .long("wolves")
+                .help("The number of wolves on the river bank")
+                .default_value("1")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("goats")
+                .short('G')
+                .long("goats")
+                .help("The number of goats on the river bank")
+                .default_value("1")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("cabbages")
+                .short('C')
+                .long("cabbages")
+                .help("The number of cabbages on the river bank")
+                .default_value("1")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("boat")
+                .short('B')
+                .long("boat")
+                .help("The capacity of the boat")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the Missionaries and Cannibals problem to its subcommand.
+fn missionaries_cannibals_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("missionaries")
+                .short('M')
+                .long("missionaries")
+                .help("The number of missionaries on the river bank")
+                .default_value("3")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("cannibals")
+                .short('C')
+                .long("cannibals")
+                .help("The number of cannibals on the river bank")
+                .default_value("3")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("boat")
+                .short('B')
+                .long("boat")
+                .help("The capacity of the boat")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the Jealous Husbands problem to its subcommand.
+fn jealous_husbands_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("couples")
+                .short('C')
+                .long("couples")
+                .help("The number of couples on the river bank")
+                .default_value("3")
+                .value_name("COUNT")
+                .value_parser(parse_couples)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("boat")
+                .short('B')
+                .long("boat")
+                .help("The capacity of the boat")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the predator/prey/bait crossing to its subcommand.
+fn predator_prey_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("items")
+                .short('I')
+                .long("items")
+                .help("The names of the items the farmer has to bring across, separated by commas")
+                .default_value("fox,goose,beans")
+                .value_name("NAMES")
+                .value_delimiter(',')
+                .num_args(1..),
+        )
+        .arg(
+            Arg::new("forbid")
+                .long("forbid")
+                .help("Forbids leaving the items named A and B unattended together; if omitted, every item would eat the next one")
+                .value_name("A,B")
+                .value_parser(parse_forbidden_pair)
+                .action(ArgAction::Append)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("boat")
+                .short('B')
+                .long("boat")
+                .help("The capacity of the boat, including the farmer")
+                .default_value("2")
+                .value_name("COUNT")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of the Tower of Hanoi to its subcommand.
+fn hanoi_arguments(command: Command) -> Command {
+    command.arg(
+        Arg::new("disks")
+            .short('D')
+            .long("disks")
+            .help("The number of disks on the first peg")
+            .default_value("3")
+            .value_name("COUNT")
+            .value_parser(parse_disks)
+            .allow_negative_numbers(false)
+            .num_args(1),
+    )
+}
+
+/// Adds the arguments of the Water Jugs problem to its subcommand.
+fn water_jugs_arguments(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("capacities")
+                .short('C')
+                .long("capacities")
+                .help("The capacities of the jugs, separated by commas")
+                .default_value("3,5")
+                .value_name("LITERS")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .value_delimiter(',')
+                .num_args(1..),
+        )
+        .arg(
+            Arg::new("target")
+                .short('T')
+                .long("target")
+                .help("The amount of water to measure")
+                .default_value("4")
+                .value_name("LITERS")
+                .value_parser(parse_nonzero_u8)
+                .allow_negative_numbers(false)
+                .num_args(1),
+        )
+}
+
+/// Adds the arguments of a crossing puzzle described by a spec to its subcommand.
+fn generic_arguments(command: Command) -> Command {
+    command.arg(
+        Arg::new("spec")
+            .short('S')
+            .long("spec")
+            .help("The JSON file describing the kinds of entities, the boat and who may not be left alone together, or the JSON itself")
+            .value_name("FILE")
+            .required(true)
+            .num_args(1),
+    )
+}
+
+/// Value parser that attempts to read a positive [`u8`] value.
+fn parse_nonzero_u8(value: &str) -> Result<u8, String> {
+    let value = value.parse().map_err(|e| format!("{e:?}"))?;
+    if value == 0 {
+        Err(String::from("value must be positive"))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Value parser that reads the number of couples.
+fn parse_couples(value: &str) -> Result<u8, String> {
+    let value = parse_nonzero_u8(value)?;
+    if value > MAX_COUPLES {
+        Err(format!("at most {MAX_COUPLES} couples are supported"))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Value parser that reads the number of disks.
+fn parse_disks(value: &str) -> Result<u8, String> {
+    let value = parse_nonzero_u8(value)?;
+    if value > MAX_DISKS {
+        Err(format!("at most {MAX_DISKS} disks are supported"))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Parses a person as either `MINUTES` or `NAME:MINUTES`.
+fn parse_person(value: &str) -> Result<(Option<String>, u8), String> {
+    match value.rsplit_once(':') {
+        Some(("", _)) => Err(String::from("name must not be empty")),
+        Some((name, walking_time)) => Ok((Some(name.into()), parse_nonzero_u8(walking_time)?)),
+        None => Ok((None, parse_nonzero_u8(value)?)),
+    }
+}
+
+/// Parses an ordering constraint as `A:B`, where both are names of people.
+fn parse_ordering(value: &str) -> Result<(String, String), String> {
+    match value.split_once(':') {
+        Some((a, b)) if !a.is_empty() && !b.is_empty() => Ok((a.into(), b.into())),
+        _ => Err(String::from("expected two names separated by a colon")),
+    }
+}
+
+/// Parses a forbidden pair as `A,B`, where both are names of items.
+fn parse_forbidden_pair(value: &str) -> Result<(String, String), String> {
+    match value.split_once(',') {
+        Some((a, b)) if !a.is_empty() && !b.is_empty() => Ok((a.into(), b.into())),
+        _ => Err(String::from("expected two names separated by a comma")),
+    }
+}
+
+/// Builds the Humans and Zombies problem, or generates a random one from the `seed`.
+fn humans_and_zombies(matches: &ArgMatches, seed: u64) -> Instance {
+    let start = match matches.get_one::<String>("start-side").map(String::as_str) {
+        Some("right") => RiverBank::Right,
+        _ => RiverBank::Left,
+    };
+    let rowing = match rows_linearly(matches) {
+        true => RowingCost::Linear,
+        false => RowingCost::Constant,
+    };
+
+    if matches.get_flag("random") {
+        let max_population = matches
+            .get_one::<u8>("max-population")
+            .cloned()
+            .expect("value is required");
+
+        let (initial, length) = random_solvable(seed, max_population);
+        let remark = format!(
+            "Generated {} humans, {} zombies and a boat for {} from seed {}; the optimal plan takes {} trips.",
+            initial.left.humans, initial.left.zombies, initial.boat.capacity, seed, length
+        );
+        return Instance {
+            problem: AnyProblem::HumansAndZombies(HumansAndZombies {
+                start,
+                rowing,
+                ..HumansAndZombies::new(
+                    initial.left.humans,
+                    initial.left.zombies,
+                    initial.boat.capacity,
+                )
+            }),
+            remark: Some(remark),
+        };
+    }
+
+    let humans = matches
+        .get_one::<u8>("humans")
+        .cloned()
+        .expect("value is required");
+    let zombies = matches
+        .get_one::<u8>("zombies")
+        .cloned()
+        .expect("value is required");
+    let boat = matches
+        .get_one::<u8>("boat")
+        .cloned()
+        .expect("value is required");
+
+    AnyProblem::HumansAndZombies(HumansAndZombies {
+        start,
+        rowing,
+        require_rower: matches.get_flag("require-rower"),
+        allow_empty_crossing: matches.get_flag("allow-empty-crossing"),
+        safety: matches
+            .get_one::<u8>("safety-margin")
+            .map_or(SafetyRule::StrictMajority, |margin| {
+                SafetyRule::Margin(*margin)
+            }),
+        ..HumansAndZombies::new(humans, zombies, boat)
+    })
+    .into()
+}
+
+/// Builds the Bridge and Torch problem.
+fn bridge_and_torch(matches: &ArgMatches) -> Result<BridgeAndTorch, String> {
+    let bridge = matches
+        .get_one::<u8>("bridge")
+        .cloned()
+        .expect("value is required");
+    let torch = matches
+        .get_one::<u8>("torch")
+        .cloned()
+        .expect("value is required");
+    let people = matches.get_many::<(Option<String>, u8)>("people").map_or(
+        BridgeAndTorch::default().people,
+        |values| {
+            values
+                .into_iter()
+                .zip(0..)
+                .map(|((name, walking_time), id)| match name {
+                    Some(name) => Person::named(id, name, *walking_time),
+                    None => Person::new(id, *walking_time),
+                })
+                .collect_vec()
+        },
+    );
+
+    let before = match matches.get_many::<(String, String)>("before") {
+        Some(values) => values
+            .map(|(a, b)| Ok((person_id(&people, a)?, person_id(&people, b)?)))
+            .collect::<Result<_, String>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(BridgeAndTorch {
+        refuel: matches.get_one::<u8>("refuel").cloned(),
+        return_capacity: matches.get_one::<u8>("return-capacity").cloned(),
+        before,
+        ..BridgeAndTorch::with_people(bridge, torch, people)
+    })
+}
+
+/// Looks up the ID of the person with the specified name.
+fn person_id(people: &[Person], name: &str) -> Result<u32, String> {
+    people
+        .iter()
+        .find(|p| p.name.as_deref() == Some(name))
+        .map(|person| person.id)
+        .ok_or_else(|| format!("Unknown person: {name}"))
+}
+
+/// Builds the Wolf, Goat and Cabbage problem.
+fn wolf_goat_cabbage(matches: &ArgMatches) -> WolfGoatCabbage {
+    let farmers = matches
+        .get_one::<u8>("farmers")
+        .cloned()
+        .expect("value is required");
+    let wolves = matches
+        .get_one::<u8>("wolves")
+        .cloned()
+        .expect("value is required");
+    let goats = matches
+        .get_one::<u8>("goats")
+        .cloned()
+        .expect("value is required");
+    let cabbages = matches
+        .get_one::<u8>("cabbages")
+        .cloned()
+        .expect("value is required");
+    let boat = matches
+        .get_one::<u8>("boat")
+        .cloned()
+        .expect("value is required");
+
+    WolfGoatCabbage::new(farmers, wolves, goats, cabbages, boat)
+}
+
+/// Builds the Missionaries and Cannibals problem.
+fn missionaries_cannibals(matches: &ArgMatches) -> MissionariesCannibals {
+    let missionaries = matches
+        .get_one::<u8>("missionaries")
+        .cloned()
+        .expect("value is required");
+    let cannibals = matches
+        .get_one::<u8>("cannibals")
+        .cloned()
+        .expect("value is required");
+    let boat = matches
+        .get_one::<u8>("boat")
+        .cloned()
+        .expect("value is required");
+
+    MissionariesCannibals::new(missionaries, cannibals, boat)
+}
+
+/// Builds the Jealous Husbands problem.
+fn jealous_husbands(matches: &ArgMatches) -> JealousHusbands {
+    let couples = matches
+        .get_one::<u8>("couples")
+        .cloned()
+        .expect("value is required");
+    let boat = matches
+        .get_one::<u8>("boat")
+        .cloned()
+        .expect("value is required");
+
+    JealousHusbands::new(couples, boat)
+}
+
+/// Builds the predator/prey/bait crossing.
+fn predator_prey(matches: &ArgMatches) -> Result<PredatorPrey, String> {
+    let items = matches
+        .get_many::<String>("items")
+        .expect("value is required")
+        .map(String::as_str)
+        .collect_vec();
+    if items.len() > MAX_ITEMS {
+        return Err(format!("At most {MAX_ITEMS} items are supported."));
+    }
+
+    let boat = matches
+        .get_one::<u8>("boat")
+        .cloned()
+        .expect("value is required");
+
+    let problem = PredatorPrey {
+        boat,
+        ..PredatorPrey::food_chain(&items)
+    };
+
+    let Some(mut pairs) = matches.get_many::<(String, String)>("forbid") else {
+        return Ok(problem);
+    };
+
+    let constraints = pairs.try_fold(Constraints::new(), |constraints, (a, b)| {
+        Ok::<_, String>(constraints.forbid(item_id(&problem, a)?, item_id(&problem, b)?))
+    })?;
+
+    Ok(PredatorPrey {
+        constraints,
+        ..problem
+    })
+}
+
+/// Looks up the index of the item with the specified name.
+fn item_id(problem: &PredatorPrey, name: &str) -> Result<u8, String> {
+    problem
+        .item(name)
+        .ok_or_else(|| format!("Unknown item: {name}"))
+}
+
+/// Builds the Tower of Hanoi.
+fn hanoi(matches: &ArgMatches) -> Hanoi {
+    let disks = matches
+        .get_one::<u8>("disks")
+        .cloned()
+        .expect("value is required");
+
+    Hanoi::new(disks)
+}
+
+/// Builds the Water Jugs problem.
+fn water_jugs(matches: &ArgMatches) -> WaterJugs {
+    let capacities = matches
+        .get_many::<u8>("capacities")
+        .expect("value is required")
+        .cloned()
+        .collect_vec();
+    let target = matches
+        .get_one::<u8>("target")
+        .cloned()
+        .expect("value is required");
+
+    WaterJugs::new(capacities, target)
+}
+
+/// Builds the crossing puzzle described by the spec, given inline or as a file.
+fn generic(matches: &ArgMatches) -> Result<Generic, String> {
+    let spec = matches
+        .get_one::<String>("spec")
+        .expect("value is required");
+    if spec.trim_start().starts_with('{') {
+        return Generic::new(&Spec::from_json(spec)?);
+    }
+
+    let path = PathBuf::from(spec);
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read spec file {}: {e}", path.display()))?;
+    Generic::new(&Spec::from_json(&json)?)
+}
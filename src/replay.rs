@@ -0,0 +1,201 @@
+use crate::plan::Plan;
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::search::{Action, ActionSummary, State};
+use std::fmt::{Display, Formatter};
+
+/// A plan as recorded elsewhere, e.g. by [`format_plan_json`](crate::json::format_plan_json),
+/// describing every action by its [`ActionSummary`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordedPlan {
+    /// The actions taken, in order.
+    pub steps: Vec<RecordedStep>,
+}
+
+/// An action of a [`RecordedPlan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedStep {
+    /// The pretty-printed action, if recorded; this only names the step in error messages.
+    pub action: Option<String>,
+    /// The summary of the action taken.
+    pub summary: ActionSummary,
+}
+
+/// The first step of a recorded plan that cannot be replayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidStep {
+    /// The number of the step, starting at one; zero refers to the initial state
+    /// and one past the last step to a plan falling short of the goal.
+    pub step: usize,
+    /// Why the step is invalid.
+    pub reason: String,
+}
+
+impl RecordedPlan {
+    /// Records a plan by summarizing every action, and pretty-printing it to name the step,
+    /// just like [`format_plan_json`](crate::json::format_plan_json) does.
+    pub fn record<S, A>(plan: &Plan<S, A>) -> Self
+    where
+        A: Action<State = S> + PrettyPrintAction<S>,
+    {
+        Self {
+            steps: plan
+                .steps()
+                .iter()
+                .map(|(action, state)| RecordedStep {
+                    action: Some(action.pretty_print(state).trim().to_string()),
+                    summary: action.describe(state),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl RecordedStep {
+    /// Names the step by the pretty-printed action, or by its summary if there is none.
+    fn name(&self) -> String {
+        self.action
+            .clone()
+            .unwrap_or_else(|| self.summary.to_string())
+    }
+}
+
+impl Display for InvalidStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Step {}: {}", self.step, self.reason)
+    }
+}
+
+/// Replays a recorded plan from the initial state, re-applying every action in turn.
+///
+/// Every recorded action is matched against the [summaries](Action::describe) of the actions
+/// applicable in the current state, so the plan does not depend on how actions or states are
+/// pretty-printed. The plan is valid if every action is legal and the last one reaches a goal
+/// state.
+///
+/// Returns the replayed plan, or the first step that could not be replayed.
+pub fn validate_plan<S, A, G>(
+    initial: S,
+    recorded: &RecordedPlan,
+    is_goal: G,
+) -> Result<Plan<S, A>, InvalidStep>
+where
+    S: State<Action = A> + PrettyPrintState,
+    A: Action<State = S>,
+    G: Fn(&S) -> bool,
+{
+    if !initial.is_valid() {
+        return Err(InvalidStep {
+            step: 0,
+            reason: format!(
+                "the plan starts at `{}`, which is not a valid state",
+                initial.pretty_print().trim()
+            ),
+        });
+    }
+
+    let mut plan = Plan::new(initial);
+    for (index, recorded) in recorded.steps.iter().enumerate() {
+        let step = index + 1;
+        let state = plan.goal();
+
+        let reached = state
+            .get_actions()
+            .into_iter()
+            .filter(|action| action.is_applicable(state))
+            .map(|action| {
+                let next = action.apply(state);
+                (action, next)
+            })
+            .find(|(action, next)| action.describe(next) == recorded.summary);
+
+        match reached {
+            Some((_, next)) if !next.is_valid() => {
+                return Err(InvalidStep {
+                    step,
+                    reason: format!(
+                        "`{}` leads to `{}`, which is not a valid state",
+                        recorded.name(),
+                        next.pretty_print().trim()
+                    ),
+                })
+            }
+            Some((action, next)) => plan.push(action, next),
+            None => {
+                return Err(InvalidStep {
+                    step,
+                    reason: format!("`{}` is not a legal action here", recorded.name()),
+                })
+            }
+        }
+    }
+
+    if !is_goal(plan.goal()) {
+        return Err(InvalidStep {
+            step: plan.len() + 1,
+            reason: String::from("the plan ends before reaching the goal"),
+        });
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{format_plan_json, parse_plan_json};
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn recorded_plans_are_replayed() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let mut recorded = parse_plan_json(&format_plan_json(&plan)).expect("JSON is valid");
+
+        let replayed = validate_plan(WorldState::default(), &recorded, WorldState::is_goal)
+            .expect("plan is valid");
+        assert_eq!(replayed.len(), plan.len());
+
+        recorded.steps.swap(0, 1);
+        let invalid = validate_plan(WorldState::default(), &recorded, WorldState::is_goal);
+        assert_eq!(invalid.err().map(|e| e.step), Some(1));
+
+        recorded.steps.swap(0, 1);
+        recorded.steps.pop();
+        let invalid = validate_plan(WorldState::default(), &recorded, WorldState::is_goal);
+        assert_eq!(invalid.err().map(|e| e.step), Some(plan.len()));
+    }
+
+    #[test]
+    fn replaying_ignores_the_pretty_printed_actions() {
+        let plan = quietly(|| search(WorldState::default())).expect("problem is solvable");
+        let mut recorded = RecordedPlan::record(&plan);
+        for step in &mut recorded.steps {
+            step.action = Some(String::from("rendered differently"));
+        }
+        assert!(validate_plan(WorldState::default(), &recorded, WorldState::is_goal).is_ok());
+
+        // Without the pretty-printed action, the step is named by its summary.
+        recorded.steps[0].action = None;
+        recorded.steps[0].summary.entities.clear();
+        let invalid = validate_plan(WorldState::default(), &recorded, WorldState::is_goal);
+        assert_eq!(
+            invalid.err().map(|e| e.to_string()),
+            Some(String::from("Step 1: `right: ` is not a legal action here"))
+        );
+    }
+
+    #[test]
+    fn plans_from_invalid_states_are_rejected() {
+        use crate::problems::humans_and_zombies::HumansAndZombies;
+        use crate::problems::Problem;
+
+        // Two humans and three zombies can never be on the same bank.
+        let initial = HumansAndZombies::new(2, 3, 2).initial();
+        assert!(!initial.is_valid());
+
+        let recorded = RecordedPlan::default();
+        let invalid = validate_plan(initial, &recorded, WorldState::is_goal);
+        assert_eq!(invalid.err().map(|e| e.step), Some(0));
+    }
+}
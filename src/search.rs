@@ -1,7 +1,31 @@
-use crate::history::History;
-use crate::strategies::Fifo;
-use std::collections::HashSet;
-use std::fmt::Debug;
+use crate::analysis::count_generated;
+use crate::history::{History, Lineage};
+use crate::memo::ActionCache;
+use crate::plan::Plan;
+use crate::random::Rng;
+use crate::strategies::{Fifo, Fringe, Objective, PriorityFringe};
+use crate::visited::Visited;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt::{Debug, Display, Formatter};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The actions applicable in a state, as returned by [`State::get_actions`].
+///
+/// With the `smallvec` feature, up to eight actions are stored inline rather than on the heap,
+/// which saves an allocation for almost every state expanded.
+#[cfg(not(feature = "smallvec"))]
+pub type Actions<A> = Vec<A>;
+
+/// The actions applicable in a state, as returned by [`State::get_actions`].
+///
+/// With the `smallvec` feature, up to eight actions are stored inline rather than on the heap,
+/// which saves an allocation for almost every state expanded.
+#[cfg(feature = "smallvec")]
+pub type Actions<A> = smallvec::SmallVec<[A; 8]>;
 
 /// A state of the world.
 pub trait State {
@@ -14,12 +38,74 @@ pub trait State {
     /// Tests whether the specified world state is a goal state.
     fn is_goal(&self) -> bool;
 
+    /// Determines whether this state is legal in itself, regardless of how it was reached,
+    /// e.g. whether no humans are outnumbered by zombies on either river bank. Applying an
+    /// applicable action to a valid state always leads to a valid state again; see
+    /// [`debug_assert_valid`].
+    ///
+    /// The default implementation considers every state valid.
+    fn is_valid(&self) -> bool {
+        true
+    }
+
+    /// Explains why no action can be taken in this state, e.g. because every crossing would
+    /// leave someone in danger, for the explanations of a [traced](crate::trace) search.
+    ///
+    /// The default implementation has nothing to say and returns [`None`].
+    fn explain_dead_end(&self) -> Option<String> {
+        None
+    }
+
     /// Expands the world state into new (applicable) actions.
     /// If this state cannot be expanded, an empty vector is returned.
-    fn get_actions(&self) -> Vec<Self::Action>;
+    fn get_actions(&self) -> Actions<Self::Action>;
 
     /// Gets the hash of this state.
     fn unique_hash(&self) -> Self::Hash;
+
+    /// Estimates the remaining cost to reach a goal state from this state.
+    /// Lower values indicate states that appear closer to the goal.
+    ///
+    /// The default implementation provides no guidance and always returns zero.
+    fn heuristic(&self) -> u32 {
+        0
+    }
+
+    /// Estimates the remaining cost to reach a goal state under the specified objective without
+    /// ever overestimating it, as [`ida_star`] requires to find the cheapest plan.
+    ///
+    /// The default implementation returns zero, which is never an overestimate.
+    fn admissible_heuristic(&self, _objective: Objective) -> u32 {
+        0
+    }
+
+    /// Gets the mirror image of this state, i.e. the same state with the river sides swapped,
+    /// if the problem is symmetric: whenever an action leads from one state to another, some
+    /// action leads from the mirror image of the latter to the mirror image of the former.
+    ///
+    /// The default implementation declares the problem as not symmetric.
+    fn mirror(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Gets a hash shared by this state and its [mirror image](State::mirror),
+    /// such that the two can be told apart from all other states at once.
+    ///
+    /// The default implementation returns the [`State::unique_hash`].
+    fn canonical_hash(&self) -> Self::Hash {
+        self.unique_hash()
+    }
+
+    /// Counts the entities of every kind in this state, such as the people on both river sides,
+    /// which no action may create or destroy; see [`debug_assert_conserved`].
+    ///
+    /// The default implementation counts nothing, for problems without such entities.
+    fn total_entities(&self) -> Vec<u32> {
+        Vec::new()
+    }
 }
 
 /// An action that can be performed in the world.
@@ -33,13 +119,99 @@ pub trait Action {
     /// Applies the specified action to the specified world state,
     /// returning the new state after the action was applied.
     fn apply(&self, state: &Self::State) -> Self::State;
+
+    /// Gets the action undoing this one, i.e. the action that leads from the state
+    /// this action was applied to back to the state it was applied in.
+    fn inverse(&self) -> Self;
+
+    /// Gets the cost of this action with regard to the specified objective.
+    ///
+    /// The default implementation counts every action once, which is only meaningful
+    /// for [`Objective::Trips`]; problems supporting other objectives override it.
+    fn cost(&self, _objective: Objective) -> u32 {
+        1
+    }
+
+    /// Determines whether this action, applied in the specified state, returns from the bank
+    /// everyone is to get to, e.g. by rowing the boat back to pick up more people.
+    ///
+    /// The default implementation returns `false`, which suits problems without crossings.
+    fn is_return(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// Describes this action in a neutral, machine-readable way, e.g. for exporting plans.
+    ///
+    /// Like [pretty-printing](crate::pretty_print::PrettyPrintAction) an action, this takes the
+    /// state the action led to.
+    fn describe(&self, state: &Self::State) -> ActionSummary;
+}
+
+/// A structured description of an action, free of any presentation.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ActionSummary {
+    /// Where the action moves the entities, e.g. `left` or `right` for a crossing towards
+    /// that bank, or `peg 1 to peg 3` for problems without a river.
+    pub direction: String,
+    /// The entities that were moved, one entry per entity, e.g. `["human", "zombie"]`.
+    pub entities: Vec<String>,
+}
+
+impl ActionSummary {
+    /// Creates a summary from the direction of the action and the entities it moves.
+    pub fn new(direction: impl Into<String>, entities: Vec<String>) -> Self {
+        Self {
+            direction: direction.into(),
+            entities,
+        }
+    }
+
+    /// Creates the summary of a crossing towards the left or the right bank.
+    pub fn crossing(towards_left: bool, entities: Vec<String>) -> Self {
+        Self::new(if towards_left { "left" } else { "right" }, entities)
+    }
+
+    /// Lists the specified number of entities of every kind, e.g. `[(2, "human")]` as
+    /// `["human", "human"]`.
+    pub fn repeated<'a>(counts: impl IntoIterator<Item = (u8, &'a str)>) -> Vec<String> {
+        counts
+            .into_iter()
+            .flat_map(|(count, name)| std::iter::repeat_n(String::from(name), count as usize))
+            .collect()
+    }
+}
+
+impl Display for ActionSummary {
+    /// Formats the summary as its direction and the entities moved, e.g. `right: human, zombie`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.direction, self.entities.join(", "))
+    }
+}
+
+/// Asserts that applying an action did not change the [entities](State::total_entities)
+/// of the state, i.e. that nobody appeared or vanished. Release builds skip the check.
+pub fn debug_assert_conserved<S: State>(before: &S, after: &S) {
+    debug_assert_eq!(
+        before.total_entities(),
+        after.total_entities(),
+        "entities are not conserved"
+    );
+}
+
+/// Asserts that applying an action to a [valid](State::is_valid) state lead to a valid state,
+/// i.e. that the action was rightly deemed applicable. Release builds skip the check.
+pub fn debug_assert_valid<S: State + Debug>(before: &S, after: &S) {
+    debug_assert!(
+        !before.is_valid() || after.is_valid(),
+        "invalid state reached: {after:?}"
+    );
 }
 
 /// Expands the world state into new (applicable) actions.
 /// If this state cannot be expanded, an empty vector is returned.
-pub fn expand<S, A>(state: &S, observed: &mut HashSet<S::Hash>) -> Vec<(A, S)>
+pub fn expand<S, A>(state: &S, observed: &mut Visited<S>) -> Vec<(A, S)>
 where
-    S: State<Action = A> + Debug,
+    S: State<Action = A> + Clone + Eq + Debug,
     A: Action<State = S> + Debug,
     S::Hash: Eq + std::hash::Hash,
 {
@@ -48,53 +220,1267 @@ where
         let new_state = action.apply(state);
 
         // Only expand states we did not see before.
-        if !observed.insert(new_state.unique_hash()) {
-            println!("  Ignored:    {:?} (recursion)", action);
+        let duplicate = !observed.insert(&new_state);
+        count_generated(duplicate);
+        if duplicate {
+            trace!("  Ignored:    {:?} (recursion)", action);
             continue;
         }
 
-        println!(
+        trace!(
             "  Applicable: Move {:?} leads to state {:?}",
-            action, new_state
+            action,
+            new_state
         );
         states.push((action, new_state));
     }
     states
 }
 
+/// The outcome of a search.
+#[derive(Debug)]
+pub enum SearchOutcome<S, A> {
+    /// A plan reaching the goal was found.
+    Solved(Plan<S, A>),
+    /// The entire reachable state space was explored without reaching the goal,
+    /// i.e. the problem is provably unsolvable.
+    Unsolvable {
+        /// The number of states explored.
+        states_explored: usize,
+    },
+    /// The search was stopped before the reachable state space was fully explored.
+    Exhausted {
+        /// Why the search was stopped.
+        reason: String,
+        /// The number of states explored until then.
+        states_explored: usize,
+    },
+}
+
+impl<S, A> SearchOutcome<S, A> {
+    /// Gets the plan if the search was successful.
+    pub fn into_plan(self) -> Option<Plan<S, A>> {
+        match self {
+            SearchOutcome::Solved(plan) => Some(plan),
+            _ => None,
+        }
+    }
+}
+
 /// Searches the state space for a plan.
-pub fn search<S, A>(initial_state: S) -> Option<impl Iterator<Item = (Option<A>, S)>>
+pub fn search<S, A>(initial_state: S) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    search_with_goal(initial_state, S::is_goal)
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate
+/// rather than [`State::is_goal`].
+///
+/// The predicate is evaluated exactly once for every state taken from the fringe.
+pub fn search_with_goal<S, A, G>(initial_state: S, goal: G) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    solve_with_goal(initial_state, goal, &mut History::new()).into_plan()
+}
+
+/// Searches the state space for a plan reaching any of the specified goal states
+/// rather than a state satisfying [`State::is_goal`].
+///
+/// States are compared by their hash and equality, like when detecting visited states.
+/// The plan leads to whichever goal state is reached first, i.e. the closest one.
+pub fn search_any_goal<S, A>(initial_state: S, goals: &[S]) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let mut targets = Visited::new();
+    for goal in goals {
+        targets.insert(goal);
+    }
+
+    search_with_goal(initial_state, |state| targets.contains(state))
+}
+
+/// Searches the state space for a plan, discarding every state for which `prune` returns `true`.
+///
+/// Pruned states are never added to the fringe, so their successors are not explored either.
+/// Note that pruning a state on an optimal path can make the search return a longer plan,
+/// and pruning every path to a goal makes the search incomplete.
+pub fn search_with_prune<S, A, P>(initial_state: S, prune: P) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    P: Fn(&S) -> bool,
+{
+    search_with_goal_and_prune(initial_state, S::is_goal, prune)
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate,
+/// discarding every state for which `prune` returns `true`.
+///
+/// See [`search_with_goal`] and [`search_with_prune`] for the semantics of both predicates.
+pub fn search_with_goal_and_prune<S, A, G, P>(
+    initial_state: S,
+    goal: G,
+    prune: P,
+) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    P: Fn(&S) -> bool,
+{
+    solve_with_prune(initial_state, goal, prune, Fifo::new(), &mut History::new()).into_plan()
+}
+
+/// Searches the state space for a plan like [`search`], additionally collecting every dead end
+/// explored along the way, i.e. every state other than a goal state without any applicable
+/// actions. Dead ends often reveal overly restrictive rules.
+pub fn search_with_dead_ends<S, A>(initial_state: S) -> (Option<Plan<S, A>>, Vec<S>)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let (outcome, dead_ends) = solve_with_dead_ends(initial_state, S::is_goal, &mut History::new());
+    (outcome.into_plan(), dead_ends)
+}
+
+/// Searches the state space like [`solve_with_goal`], additionally collecting every dead end
+/// explored along the way in the order they were explored; see [`search_with_dead_ends`].
+///
+/// Unlike states whose actions all lead to states seen before, dead ends have no applicable
+/// actions at all, which takes listing them once more for every state explored.
+pub fn solve_with_dead_ends<S, A, G>(
+    initial_state: S,
+    goal: G,
+    history: &mut History<S, A>,
+) -> (SearchOutcome<S, A>, Vec<S>)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    // The goal is tested exactly once for every state taken from the fringe.
+    let dead_ends = RefCell::new(Vec::new());
+    let outcome = solve_with_goal(
+        initial_state,
+        |state: &S| {
+            if goal(state) {
+                return true;
+            }
+            if state.get_actions().is_empty() {
+                dead_ends.borrow_mut().push(state.clone());
+            }
+            false
+        },
+        history,
+    );
+    (outcome, dead_ends.into_inner())
+}
+
+/// Searches the state space for a plan, reporting why no plan was found if unsuccessful.
+pub fn solve<S, A>(initial_state: S) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    solve_with_history(initial_state, &mut History::new())
+}
+
+/// Searches the state space for a plan, recording every explored state
+/// in the specified [`History`].
+pub fn solve_with_history<S, A>(
+    initial_state: S,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    solve_with_goal(initial_state, S::is_goal, history)
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate,
+/// recording every explored state in the specified [`History`].
+///
+/// The predicate is evaluated exactly once for every state taken from the fringe.
+pub fn solve_with_goal<S, A, G>(
+    initial_state: S,
+    goal: G,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    solve_with_fringe(initial_state, goal, Fifo::new(), history)
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate,
+/// exploring states in the order determined by the `fringe` and recording every explored
+/// state in the specified [`History`].
+///
+/// The predicate is evaluated exactly once for every state taken from the fringe.
+pub fn solve_with_fringe<S, A, G, F>(
+    initial_state: S,
+    goal: G,
+    fringe: F,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    F: Fringe<Rc<Lineage<S, A>>>,
+{
+    solve_with_prune(initial_state, goal, |_| false, fringe, history)
+}
+
+/// Searches the state space like [`solve_with_fringe`], but discards every state for which
+/// `prune` returns `true` before it is added to the fringe.
+///
+/// Pruning a state on an optimal path can make the search return a longer plan, and pruning
+/// every path to a goal makes the search incomplete; in that case, the problem is reported
+/// as unsolvable even though it is not.
+pub fn solve_with_prune<S, A, G, P, F>(
+    initial_state: S,
+    goal: G,
+    prune: P,
+    fringe: F,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    P: Fn(&S) -> bool,
+    F: Fringe<Rc<Lineage<S, A>>>,
+{
+    solve_until(
+        initial_state,
+        goal,
+        prune,
+        fringe,
+        Visited::new(),
+        history,
+        usize::MAX,
+        |_| None,
+    )
+}
+
+/// Searches the state space for a plan, recording every explored state in the specified
+/// [`History`], until a state is found to satisfy the `goal` predicate or `cancel` is set.
+///
+/// The flag is checked once for every state taken from the fringe, so the search returns
+/// within the time it takes to expand a single state after the flag was set. A cancelled
+/// search is reported as [`SearchOutcome::Exhausted`]; the history then holds every state
+/// discovered up to that point.
+pub fn solve_with_cancel<S, A, G>(
+    initial_state: S,
+    goal: G,
+    cancel: &AtomicBool,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    solve_until(
+        initial_state,
+        goal,
+        |_| false,
+        Fifo::new(),
+        Visited::new(),
+        history,
+        usize::MAX,
+        |_| {
+            cancel
+                .load(Ordering::Relaxed)
+                .then(|| String::from("cancelled"))
+        },
+    )
+}
+
+/// Searches the state space for a plan like [`search`], returning [`None`] early if
+/// `cancel` is set from another thread. See [`solve_with_cancel`] for details.
+pub fn search_with_cancel<S, A>(initial_state: S, cancel: Arc<AtomicBool>) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    solve_with_cancel(initial_state, S::is_goal, &cancel, &mut History::new()).into_plan()
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate,
+/// recording every explored state in the specified [`History`], but gives up after taking
+/// `max_nodes` states from the fringe.
+///
+/// Unlike a depth limit, this bounds the work done regardless of the shape of the state space.
+/// A search running out of its budget is reported as [`SearchOutcome::Exhausted`].
+pub fn solve_with_max_nodes<S, A, G>(
+    initial_state: S,
+    goal: G,
+    max_nodes: usize,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    solve_until(
+        initial_state,
+        goal,
+        |_| false,
+        Fifo::new(),
+        Visited::new(),
+        history,
+        usize::MAX,
+        |explored| (explored >= max_nodes).then(|| String::from("node budget exhausted")),
+    )
+}
+
+/// The number of states explored between two checks of the clock.
+pub(crate) const TIMEOUT_CHECK_INTERVAL: usize = 64;
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate,
+/// recording every explored state in the specified [`History`], but gives up once `timeout`
+/// has passed.
+///
+/// To keep the overhead low, the clock is only checked every few states, so the search may
+/// take slightly longer than the timeout. A search running out of time is reported as
+/// [`SearchOutcome::Exhausted`].
+pub fn solve_with_timeout<S, A, G>(
+    initial_state: S,
+    goal: G,
+    timeout: Duration,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    let started = Instant::now();
+    solve_until(
+        initial_state,
+        goal,
+        |_| false,
+        Fifo::new(),
+        Visited::new(),
+        history,
+        usize::MAX,
+        |explored| {
+            (explored % TIMEOUT_CHECK_INTERVAL == 0 && started.elapsed() >= timeout)
+                .then(|| format!("timed out after {} ms", timeout.as_millis()))
+        },
+    )
+}
+
+/// Searches the state space like [`solve_with_prune`], but stops as soon as `stop` returns
+/// the reason for stopping. It is called with the number of states explored so far before
+/// every state is taken from the fringe.
+///
+/// States `max_depth` actions away from the initial state are not expanded any further;
+/// if this cuts off any state, the search is reported as exhausted rather than unsolvable.
+/// States already in the `observed` set, usually an empty one, are not explored.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_until<S, A, G, P, F, X>(
+    initial_state: S,
+    goal: G,
+    prune: P,
+    mut fringe: F,
+    mut observed: Visited<S, Rc<Lineage<S, A>>>,
+    history: &mut History<S, A>,
+    max_depth: usize,
+    stop: X,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    P: Fn(&S) -> bool,
+    F: Fringe<Rc<Lineage<S, A>>>,
+    X: FnMut(usize) -> Option<String>,
+{
+    if prune(&initial_state) {
+        trace!("Pruned initial state {:?}", initial_state);
+        return SearchOutcome::Unsolvable { states_explored: 0 };
+    }
+
+    let lineage = history.create_root(initial_state);
+    observed.insert_entry(Rc::clone(&lineage));
+    fringe.push(lineage);
+
+    explore(
+        goal,
+        prune,
+        fringe,
+        history,
+        &mut observed,
+        max_depth,
+        0,
+        stop,
+    )
+}
+
+/// Takes states from the fringe and expands them until a goal state is found, the fringe runs
+/// empty or `stop` returns the reason for stopping, continuing the search set up by
+/// [`solve_until`] or restored from a [snapshot](crate::snapshot::SearchSnapshot).
+///
+/// The `observed` states must include every state in the fringe, and `states_explored`
+/// counts the states explored before. Rather than copies of the states, the set keeps the
+/// lineages created in the `history`, so a pruned state is not remembered and is pruned again
+/// whenever it is reached.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn explore<S, A, G, P, F, X>(
+    goal: G,
+    prune: P,
+    mut fringe: F,
+    history: &mut History<S, A>,
+    observed: &mut Visited<S, Rc<Lineage<S, A>>>,
+    max_depth: usize,
+    mut states_explored: usize,
+    mut stop: X,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    P: Fn(&S) -> bool,
+    F: Fringe<Rc<Lineage<S, A>>>,
+    X: FnMut(usize) -> Option<String>,
+{
+    let mut depth_limited = false;
+    loop {
+        if let Some(reason) = stop(states_explored) {
+            trace!("Stopped: {reason}.");
+            return SearchOutcome::Exhausted {
+                reason,
+                states_explored,
+            };
+        }
+
+        let Some(lineage) = fringe.pop() else {
+            break;
+        };
+
+        let state = &lineage.state;
+        trace!("Exploring state {}: {:?}", lineage.id, state);
+        states_explored += 1;
+
+        if goal(state) {
+            trace!("  Goal reached.");
+            history.set_goal(&lineage);
+            return SearchOutcome::Solved(history.backtrack(&lineage).collect());
+        }
+
+        if lineage.depth >= max_depth {
+            trace!("  Depth limit: State {} is not expanded.", lineage.id);
+            depth_limited = true;
+            continue;
+        }
+
+        let mut expanded = false;
+        for action in state.get_actions() {
+            let new_state = action.apply(state);
+
+            // Only expand states we did not see before.
+            let duplicate = observed.revisit(&new_state);
+            count_generated(duplicate);
+            if duplicate {
+                trace!("  Ignored:    {:?} (recursion)", action);
+                continue;
+            }
+
+            expanded = true;
+            if prune(&new_state) {
+                trace!("  Pruned:     {:?}", new_state);
+                continue;
+            }
+
+            trace!(
+                "  Applicable: Move {:?} leads to state {:?}",
+                action,
+                new_state
+            );
+            let child = history.create_entry(action, new_state, &lineage);
+            observed.insert_entry(Rc::clone(&child));
+            fringe.push(child);
+        }
+
+        if !expanded {
+            trace!("  Dead end: State {} could not be expanded.", lineage.id);
+        }
+    }
+
+    if depth_limited {
+        return SearchOutcome::Exhausted {
+            reason: format!("no plan within {max_depth} actions"),
+            states_explored,
+        };
+    }
+
+    SearchOutcome::Unsolvable { states_explored }
+}
+
+/// Searches the state space for a plan using greedy best-first search, recording every explored
+/// state in the specified [`History`].
+///
+/// States are explored in order of their [`State::heuristic`] alone, regardless of the cost of
+/// the path that lead to them. This typically explores far fewer states than breadth-first
+/// search, but the resulting plan is not necessarily optimal.
+pub fn greedy_best_first<S, A>(initial_state: S, history: &mut History<S, A>) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let fringe = PriorityFringe::new(|lineage: &Rc<Lineage<S, A>>| lineage.state.heuristic());
+    solve_with_fringe(initial_state, S::is_goal, fringe, history)
+}
+
+/// Searches the state space for the cheapest plan reaching a state accepted by the `goal`
+/// predicate using uniform-cost search, recording every explored state in the specified
+/// [`History`].
+///
+/// States are explored in order of the summed `cost` of the actions leading to them, where
+/// the cost of an action is determined from the action and the state it is applied in.
+/// Unlike the other searches, states are deduplicated when they are explored rather than
+/// when they are discovered, since a cheaper path to a state may be discovered later.
+pub fn uniform_cost_search<S, A, G, C>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
 where
-    S: State<Action = A> + Clone + Debug,
+    S: State<Action = A> + Clone + Eq + Debug,
     A: Action<State = S> + Clone + Debug,
     S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
 {
-    let mut observed = HashSet::default();
-    observed.insert(initial_state.unique_hash());
-    let mut history = History::new();
-    let lineage = history.create_root(initial_state.clone());
+    a_star(initial_state, goal, cost, |_| 0, history)
+}
+
+/// Searches the state space for the cheapest plan reaching a state accepted by the `goal`
+/// predicate using A* search, recording every explored state in the specified [`History`].
+///
+/// States are explored in order of the summed `cost` of the actions leading to them plus the
+/// `heuristic` estimate of the remaining cost, such as [`State::heuristic`] or one of the
+/// functions in [`heuristics`](crate::heuristics). Without a heuristic, this is a uniform-cost
+/// search. The plan is the cheapest one as long as the heuristic never overestimates the
+/// remaining cost, and never drops by more than the cost of an action along the way.
+pub fn a_star<S, A, G, C, H>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    heuristic: H,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    H: Fn(&S) -> u32,
+{
+    let fringe = PriorityFringe::new(|(cost, estimate, _): &Estimated<S, A>| cost + estimate);
+    a_star_with_fringe(initial_state, goal, cost, heuristic, fringe, history).0
+}
+
+/// A lineage in the fringe of an A* search, along with the cost of the path leading to it
+/// and the estimated remaining cost.
+pub(crate) type Estimated<S, A> = (u32, u32, Rc<Lineage<S, A>>);
+
+/// Searches the state space like [`a_star`], taking states from the specified fringe, which
+/// must order them by the sum of the path cost and the estimate. Also returns the number of
+/// states explored, which excludes states taken from the fringe again via a costlier path.
+pub(crate) fn a_star_with_fringe<S, A, G, C, H, F>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    heuristic: H,
+    mut fringe: F,
+    history: &mut History<S, A>,
+) -> (SearchOutcome<S, A>, usize)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    H: Fn(&S) -> u32,
+    F: Fringe<Estimated<S, A>>,
+{
+    let estimate = heuristic(&initial_state);
+    fringe.push((0, estimate, history.create_root(initial_state)));
+
+    let mut explored = Visited::new();
+    while let Some((path_cost, _, lineage)) = fringe.pop() {
+        if !explored.insert_entry(Rc::clone(&lineage)) {
+            continue;
+        }
 
-    let mut fringe = Fifo::from(lineage);
-    while let Some(lineage) = fringe.pop() {
         let state = &lineage.state;
-        println!("Exploring state {}: {:?}", lineage.id, state);
+        trace!(
+            "Exploring state {} at cost {}: {:?}",
+            lineage.id,
+            path_cost,
+            state
+        );
+        if goal(state) {
+            trace!("  Goal reached.");
+            history.set_goal(&lineage);
+            let plan = history.backtrack(&lineage).collect();
+            return (SearchOutcome::Solved(plan), explored.len());
+        }
 
-        if state.is_goal() {
-            println!("  Goal reached.");
-            return Some(history.backtrack(&lineage));
+        for action in state.get_actions() {
+            let new_state = action.apply(state);
+            if explored.contains(&new_state) {
+                trace!("  Ignored:    {:?} (recursion)", action);
+                continue;
+            }
+
+            let action_cost = cost(&action, state);
+            let estimate = heuristic(&new_state);
+            let new_lineage = history.create_entry(action, new_state, &lineage);
+            fringe.push((path_cost + action_cost, estimate, new_lineage));
         }
+    }
+
+    let states_explored = explored.len();
+    (
+        SearchOutcome::Unsolvable { states_explored },
+        states_explored,
+    )
+}
+
+/// Searches the state space for the cheapest plan reaching a state accepted by the `goal`
+/// predicate using iterative deepening A* (IDA*), recording the plan in the specified [`History`].
+///
+/// Every iteration is a depth-first search that abandons a path once the summed `cost` of its
+/// actions plus the `heuristic` estimate for its last state, such as [`State::heuristic`],
+/// exceeds a threshold. The
+/// threshold starts at the heuristic of the initial state and is raised to the smallest value
+/// that exceeded it, until a goal state is reached. Only the current path is kept in memory, and
+/// only the plan is recorded in the history; in exchange, states are explored over and over again.
+///
+/// The plan is the cheapest one as long as the heuristic never overestimates the remaining cost.
+pub fn ida_star<S, A, G, C, H>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    heuristic: H,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    H: Fn(&S) -> u32,
+{
+    iterative_deepening(
+        initial_state,
+        goal,
+        cost,
+        heuristic,
+        S::get_actions,
+        history,
+    )
+}
 
-        let expansions = expand(state, &mut observed);
-        if expansions.is_empty() {
-            println!("  Dead end: State {} could not be expanded.", lineage.id);
+/// Searches the state space like [`ida_star`], but looks up the actions of every state in the
+/// specified [`ActionCache`] rather than expanding it again in every iteration.
+///
+/// This finds the same plan as [`ida_star`], trading memory for every state explored for time.
+pub fn ida_star_memoized<S, A, G, C, H>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    heuristic: H,
+    cache: &mut ActionCache<S>,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    H: Fn(&S) -> u32,
+{
+    let actions = |state: &S| cache.get_actions(state);
+    iterative_deepening(initial_state, goal, cost, heuristic, actions, history)
+}
+
+/// Runs IDA* as described for [`ida_star`], obtaining the actions of every state from `expand`.
+fn iterative_deepening<S, A, G, C, H, E>(
+    initial_state: S,
+    goal: G,
+    cost: C,
+    heuristic: H,
+    mut expand: E,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    C: Fn(&A, &S) -> u32,
+    H: Fn(&S) -> u32,
+    E: FnMut(&S) -> Actions<A>,
+{
+    let mut states_explored = 0;
+    let mut threshold = heuristic(&initial_state);
+    loop {
+        trace!("Searching with a cost threshold of {}.", threshold);
+
+        // The path holds every state along with the action leading to it, the cost of the
+        // path up to it and the actions not tried from it yet.
+        let mut on_path = Visited::new();
+        on_path.insert(&initial_state);
+        states_explored += 1;
+        let actions = expand(&initial_state);
+        let mut path = vec![(None, initial_state.clone(), 0, actions)];
+        let mut exceeded: Option<u32> = None;
+
+        while let Some((_, state, path_cost, actions)) = path.last_mut() {
+            if goal(state) {
+                trace!("  Goal reached at cost {}.", path_cost);
+                let mut lineage = history.create_root(initial_state);
+                for (action, state, _, _) in path.into_iter().skip(1) {
+                    let action = action.expect("only the initial state has no action");
+                    lineage = history.create_entry(action, state, &lineage);
+                }
+                history.set_goal(&lineage);
+                return SearchOutcome::Solved(history.backtrack(&lineage).collect());
+            }
+
+            let Some(action) = actions.pop() else {
+                on_path.remove(state);
+                path.pop();
+                continue;
+            };
+
+            let new_state = action.apply(state);
+            if on_path.contains(&new_state) {
+                trace!("  Ignored:    {:?} (recursion)", action);
+                continue;
+            }
+
+            let new_cost = *path_cost + cost(&action, state);
+            let estimate = new_cost + heuristic(&new_state);
+            if estimate > threshold {
+                exceeded = Some(exceeded.map_or(estimate, |e| e.min(estimate)));
+                continue;
+            }
+
+            trace!("Exploring state at cost {}: {:?}", new_cost, new_state);
+            states_explored += 1;
+            on_path.insert(&new_state);
+            let actions = expand(&new_state);
+            path.push((Some(action), new_state, new_cost, actions));
+        }
+
+        match exceeded {
+            Some(next) => threshold = next,
+            None => return SearchOutcome::Unsolvable { states_explored },
+        }
+    }
+}
+
+/// Searches the state space for a plan by walking it randomly, recording every explored
+/// state in the specified [`History`].
+///
+/// At every step, a random applicable action is taken; if a state cannot be expanded any further,
+/// the walk backtracks to the previous state and tries another action. States already on the
+/// current path are never revisited. The same `seed` always produces the same plan, which is
+/// valid but not necessarily optimal. The walk gives up after `max_steps` actions were taken.
+pub fn random_search<S, A>(
+    initial_state: S,
+    seed: u64,
+    max_steps: usize,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let mut rng = Rng::seed_from_u64(seed);
+    let mut shuffled_actions = |state: &S| {
+        let mut actions = state.get_actions();
+        rng.shuffle(&mut actions);
+        actions
+    };
+
+    let mut on_path = Visited::new();
+    on_path.insert(&initial_state);
+    let actions = shuffled_actions(&initial_state);
+    let lineage = history.create_root(initial_state);
+    let mut path = vec![(lineage, actions)];
+
+    let mut steps = 0;
+    while let Some((lineage, actions)) = path.last_mut() {
+        if lineage.state.is_goal() {
+            let lineage = Rc::clone(lineage);
+            history.set_goal(&lineage);
+            return SearchOutcome::Solved(history.backtrack(&lineage).collect());
+        }
+
+        // Backtrack if there is nothing left to try from here.
+        let Some(action) = actions.pop() else {
+            on_path.remove(&lineage.state);
+            path.pop();
+            continue;
+        };
+
+        let state = action.apply(&lineage.state);
+        if !on_path.insert(&state) {
             continue;
         }
 
-        for (action, state) in expansions {
-            let lineage = history.create_entry(action, state, &lineage);
-            fringe.push(lineage);
+        if steps == max_steps {
+            return SearchOutcome::Exhausted {
+                reason: format!("gave up after {max_steps} random steps"),
+                states_explored: history.len(),
+            };
         }
+
+        steps += 1;
+        let parent = Rc::clone(lineage);
+        let actions = shuffled_actions(&state);
+        path.push((history.create_entry(action, state, &parent), actions));
+    }
+
+    SearchOutcome::Unsolvable {
+        states_explored: history.len(),
     }
+}
+
+/// Counts the distinct plans reaching the goal, i.e. all paths from the initial state
+/// to a goal state that never visit a state twice.
+///
+/// Unlike the searches, states are only deduplicated along the current path rather than
+/// globally, since reaching a state on a different route makes for a different plan.
+/// The number of such paths can grow exponentially with the size of the state space.
+pub fn count_solutions<S, A>(initial_state: S) -> usize
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    count_solutions_within(initial_state, usize::MAX)
+}
+
+/// Counts the distinct plans reaching the goal in no more than `max_depth` actions.
+///
+/// See [`count_solutions`] for what makes two plans distinct.
+pub fn count_solutions_within<S, A>(initial_state: S, max_depth: usize) -> usize
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let mut solutions = 0;
+    visit_solutions_within(initial_state, max_depth, |_| solutions += 1);
+    solutions
+}
+
+/// Finds all distinct plans of minimal length, i.e. all optimal solutions with regard
+/// to the number of actions taken.
+///
+/// The plans are returned in the order they are found, which only depends on the order
+/// of the actions returned by [`State::get_actions`].
+pub fn search_all_optimal<S, A>(initial_state: S) -> Vec<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let Some(shortest) = search(initial_state.clone()) else {
+        return Vec::new();
+    };
 
-    None
+    let mut plans = Vec::new();
+    visit_solutions_within(initial_state, shortest.len(), |path| {
+        plans.push(
+            path.iter()
+                .map(|(action, state, _)| (action.clone(), state.clone()))
+                .collect(),
+        );
+    });
+    plans
+}
+
+/// Finds all distinct plans of minimal length, like [`search_all_optimal`], and picks the one
+/// ordered first by `compare`, e.g. by a secondary objective breaking the tie between them.
+///
+/// Of equally preferred plans, the one found first is picked.
+pub fn search_preferred_optimal<S, A, F>(initial_state: S, mut compare: F) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    F: FnMut(&Plan<S, A>, &Plan<S, A>) -> std::cmp::Ordering,
+{
+    search_all_optimal(initial_state)
+        .into_iter()
+        .min_by(|a, b| compare(a, b))
+}
+
+/// A path explored by [`visit_solutions_within`]: every state along with the action that
+/// lead to it and the actions not tried from it yet.
+type SolutionPath<S, A> = Vec<(Option<A>, S, Actions<A>)>;
+
+/// Walks all distinct plans reaching the goal in no more than `max_depth` actions,
+/// calling `on_solution` with the path of every one of them.
+///
+/// See [`count_solutions`] for what makes two plans distinct.
+fn visit_solutions_within<S, A, F>(initial_state: S, max_depth: usize, mut on_solution: F)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    F: FnMut(&SolutionPath<S, A>),
+{
+    let mut on_path = Visited::new();
+    on_path.insert(&initial_state);
+    let actions = initial_state.get_actions();
+    let mut path: SolutionPath<S, A> = vec![(None, initial_state, actions)];
+
+    let mut solutions = 0;
+    while let Some(depth) = path.len().checked_sub(1) {
+        let (_, state, _) = path.last().expect("path is not empty");
+
+        // A plan ends at the first goal state it reaches.
+        let is_goal = state.is_goal();
+        if is_goal {
+            trace!("Found solution {}.", solutions + 1);
+            solutions += 1;
+            on_solution(&path);
+        }
+
+        // Backtrack if there is nothing left to try from here.
+        let (_, state, actions) = path.last_mut().expect("path is not empty");
+        let next = if is_goal || depth >= max_depth {
+            None
+        } else {
+            actions.pop()
+        };
+
+        let Some(action) = next else {
+            on_path.remove(state);
+            path.pop();
+            continue;
+        };
+
+        let state = action.apply(state);
+        if !on_path.insert(&state) {
+            continue;
+        }
+
+        let actions = state.get_actions();
+        path.push((Some(action), state, actions));
+    }
+}
+
+/// Counts the distinct plans of minimal length, i.e. the number of optimal solutions
+/// with regard to the number of actions taken.
+pub fn count_shortest_solutions<S, A>(initial_state: S) -> usize
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    match search(initial_state.clone()) {
+        Some(plan) => count_solutions_within(initial_state, plan.len()),
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::{
+        Boat, HumansAndZombies, RiverBank, RiverBankState, WorldState,
+    };
+    use crate::problems::Problem;
+    use crate::verbosity::quietly;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "entities are not conserved")]
+    fn vanishing_entities_are_caught() {
+        let before = WorldState::default();
+        let mut after = before.clone();
+        after.right.zombies += 1;
+        debug_assert_conserved(&before, &after);
+    }
+
+    #[test]
+    fn dead_ends_are_collected() {
+        use crate::problems::bridge_and_torch::{BridgeAndTorch, RiverSide};
+
+        let (plan, dead_ends) = quietly(|| search_with_dead_ends(WorldState::default()));
+        assert!(plan.is_some());
+        assert!(dead_ends.is_empty());
+
+        // Nobody can cross once the torch burns too low.
+        let initial = BridgeAndTorch::default().initial();
+        let (plan, dead_ends) = quietly(|| search_with_dead_ends(initial));
+        assert!(plan.is_some());
+        assert!(!dead_ends.is_empty());
+        for state in dead_ends {
+            let side = match state.torch.side {
+                RiverSide::Left => &state.left,
+                RiverSide::Right => &state.right,
+            };
+            assert!(side
+                .people
+                .iter()
+                .all(|person| person.walking_time > state.torch.remaining_time));
+        }
+    }
+
+    #[test]
+    fn custom_goal_is_reached() {
+        let plan = search_with_goal(WorldState::default(), |state| state.right.humans == 2)
+            .expect("goal is reachable");
+
+        assert_eq!(plan.goal().right.humans, 2);
+        assert!(!plan.goal().is_goal());
+    }
+
+    #[test]
+    fn greedy_explores_fewer_states_than_bfs() {
+        let initial = HumansAndZombies::new(20, 20, 4).initial();
+
+        let mut bfs = History::new();
+        let bfs_plan = solve_with_history(initial.clone(), &mut bfs)
+            .into_plan()
+            .expect("problem is solvable");
+
+        let mut greedy = History::new();
+        let greedy_plan = greedy_best_first(initial, &mut greedy)
+            .into_plan()
+            .expect("problem is solvable");
+
+        assert!(greedy.len() < bfs.len());
+        assert!(greedy_plan.len() >= bfs_plan.len());
+    }
+
+    #[test]
+    fn random_search_is_reproducible() {
+        let walk = |seed| {
+            random_search(WorldState::default(), seed, 1000, &mut History::new())
+                .into_plan()
+                .expect("problem is solvable")
+                .into_iter()
+                .map(|(_, state)| state)
+                .collect::<Vec<_>>()
+        };
+
+        let plan = walk(42);
+        assert!(plan.last().expect("plan is not empty").is_goal());
+        assert_eq!(plan, walk(42));
+    }
+
+    #[test]
+    fn benign_prune_yields_same_plan() {
+        use crate::problems::bridge_and_torch::BridgeAndTorch;
+
+        let initial = BridgeAndTorch::default().initial();
+        let expected = search(initial.clone()).expect("problem is solvable");
+
+        // The slowest person still on the left has to cross at least once,
+        // so states where the torch cannot last that long are dead ends anyway.
+        let plan = search_with_prune(initial, |state| {
+            (state.torch.remaining_time as u32) < state.heuristic()
+        })
+        .expect("problem is solvable");
+
+        let states = |plan: Plan<_, _>| plan.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+        assert_eq!(states(plan), states(expected));
+    }
+
+    #[test]
+    fn uniform_cost_search_minimizes_objective() {
+        use crate::problems::bridge_and_torch::BridgeAndTorch;
+
+        // Counting trips, uniform-cost search is as good as breadth-first search.
+        let trips = uniform_cost_search(
+            WorldState::default(),
+            WorldState::is_goal,
+            |a: &_, _: &_| Action::cost(a, Objective::Trips),
+            &mut History::new(),
+        )
+        .into_plan()
+        .expect("problem is solvable");
+        assert_eq!(trips.len(), 11);
+
+        // Breadth-first search does not take the walking time into account.
+        let initial = BridgeAndTorch::new(2, 30, vec![1, 2, 5, 10]).initial();
+        let fewest_trips = search(initial.clone()).expect("problem is solvable");
+        let fastest = uniform_cost_search(
+            initial,
+            |s: &_| State::is_goal(s),
+            |a: &_, _: &_| Action::cost(a, Objective::Time),
+            &mut History::new(),
+        )
+        .into_plan()
+        .expect("problem is solvable");
+
+        assert_eq!(fastest.goal().time, 17);
+        assert!(fewest_trips.goal().time > fastest.goal().time);
+    }
+
+    #[test]
+    fn cancelled_search_returns_promptly() {
+        use crate::problems::hanoi::Hanoi;
+        use std::thread;
+
+        // Far too many states to be explored in any reasonable time.
+        let initial = Hanoi::new(24).initial();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let flag = Arc::clone(&cancel);
+        let handle = thread::spawn(move || quietly(|| search_with_cancel(initial, flag)));
+
+        thread::sleep(Duration::from_millis(50));
+        let cancelled_at = Instant::now();
+        cancel.store(true, Ordering::Relaxed);
+
+        assert!(handle.join().expect("search does not panic").is_none());
+        assert!(cancelled_at.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn node_budget_bounds_search() {
+        use crate::problems::hanoi::Hanoi;
+
+        let initial = Hanoi::new(20).initial();
+        let outcome =
+            quietly(|| solve_with_max_nodes(initial, State::is_goal, 100, &mut History::new()));
+        assert!(matches!(
+            outcome,
+            SearchOutcome::Exhausted {
+                states_explored: 100,
+                ..
+            }
+        ));
+
+        // A sufficient budget does not change the plan.
+        let plan = solve_with_max_nodes(
+            WorldState::default(),
+            State::is_goal,
+            1000,
+            &mut History::new(),
+        )
+        .into_plan()
+        .expect("problem is solvable");
+        assert_eq!(plan.len(), 11);
+    }
+
+    #[test]
+    fn timeout_bounds_search() {
+        use crate::problems::hanoi::Hanoi;
+
+        let initial = Hanoi::new(24).initial();
+        let timeout = Duration::from_millis(50);
+
+        let started = Instant::now();
+        let outcome =
+            quietly(|| solve_with_timeout(initial, State::is_goal, timeout, &mut History::new()));
+        let elapsed = started.elapsed();
+
+        assert!(matches!(outcome, SearchOutcome::Exhausted { .. }));
+        assert!(elapsed >= timeout);
+        assert!(elapsed < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn counts_distinct_solutions() {
+        // The classic puzzle has four optimal solutions, and every detour
+        // returns to a state already on the path.
+        assert_eq!(count_shortest_solutions(WorldState::default()), 4);
+        assert_eq!(count_solutions(WorldState::default()), 4);
+        assert_eq!(count_solutions_within(WorldState::default(), 10), 0);
+    }
+
+    #[test]
+    fn finds_all_optimal_solutions() {
+        let plans = quietly(|| search_all_optimal(WorldState::default()));
+        assert_eq!(plans.len(), 4);
+
+        let states = |plan: &Plan<WorldState, _>| {
+            plan.steps()
+                .iter()
+                .map(|(_, s)| s.clone())
+                .collect::<Vec<_>>()
+        };
+        for (index, plan) in plans.iter().enumerate() {
+            assert_eq!(plan.len(), 11);
+            assert!(plan.goal().is_goal());
+            assert!(plans[..index]
+                .iter()
+                .all(|other| states(other) != states(plan)));
+        }
+    }
+
+    #[test]
+    fn any_of_several_goals_is_reached() {
+        let near = WorldState::new(
+            RiverBankState::new(3, 2),
+            RiverBankState::new(0, 1),
+            Boat::new(2, RiverBank::Left),
+        );
+        let far = HumansAndZombies::default()
+            .initial()
+            .mirror()
+            .expect("symmetric");
+
+        let plan = quietly(|| search_any_goal(WorldState::default(), &[far.clone(), near.clone()]))
+            .expect("goals are reachable");
+        assert_eq!(plan.goal(), &near);
+        assert_eq!(plan.len(), 2);
+
+        let plan = quietly(|| search_any_goal(WorldState::default(), std::slice::from_ref(&far)))
+            .expect("goal is reachable");
+        assert_eq!(plan.goal(), &far);
+        assert!(quietly(|| search_any_goal(WorldState::default(), &[])).is_none());
+    }
 }
@@ -0,0 +1,210 @@
+//! Snapshots of a breadth-first search, allowing to stop a long search and resume it later.
+
+use crate::history::{History, Lineage};
+use crate::search::{explore, Action, SearchOutcome, State};
+use crate::strategies::Fifo;
+use crate::visited::Visited;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// A state discovered by a search, along with how it was reached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry<S, A> {
+    /// The ID of the entry the state was reached from, or [`None`] for the initial state.
+    pub parent: Option<usize>,
+    /// The action that lead to the state, or [`None`] for the initial state.
+    pub action: Option<A>,
+    /// The state.
+    pub state: S,
+}
+
+/// The progress of a breadth-first search, from which it can be [resumed](resume).
+///
+/// A breadth-first search has visited exactly the states it discovered, i.e. those of the
+/// entries, so the set of visited states is restored from them rather than stored twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSnapshot<S, A> {
+    /// Every state discovered so far, indexed by its ID in the [`History`].
+    pub entries: Vec<SnapshotEntry<S, A>>,
+    /// The IDs of the entries waiting in the fringe, in the order they are to be explored.
+    pub fringe: Vec<usize>,
+    /// The number of states explored so far.
+    pub states_explored: usize,
+}
+
+impl<S, A> SearchSnapshot<S, A> {
+    /// Creates the snapshot of a search that has yet to explore the initial state.
+    pub fn new(initial_state: S) -> Self {
+        Self {
+            entries: vec![SnapshotEntry {
+                parent: None,
+                action: None,
+                state: initial_state,
+            }],
+            fringe: vec![0],
+            states_explored: 0,
+        }
+    }
+
+    /// Captures the progress recorded in the history, with the specified lineages yet to be
+    /// explored.
+    fn capture(
+        history: &History<S, A>,
+        fringe: impl Iterator<Item = Rc<Lineage<S, A>>>,
+        states_explored: usize,
+    ) -> Self
+    where
+        S: Clone,
+        A: Clone,
+    {
+        Self {
+            entries: history
+                .iter()
+                .map(|lineage| SnapshotEntry {
+                    parent: lineage.parent_id(),
+                    action: lineage.action.clone(),
+                    state: lineage.state.clone(),
+                })
+                .collect(),
+            fringe: fringe.map(|lineage| lineage.id).collect(),
+            states_explored,
+        }
+    }
+}
+
+/// The outcome of a resumed search, along with the snapshot to resume it from once more
+/// if it was stopped.
+pub type Resumed<S, A> = (SearchOutcome<S, A>, Option<SearchSnapshot<S, A>>);
+
+/// Continues the breadth-first search captured in the snapshot, recording every state in the
+/// specified history, which has to be empty.
+///
+/// The search stops like [`solve_with_goal`](crate::search::solve_with_goal) once a state
+/// accepted by the `goal` predicate is found or the state space is exhausted, or as soon as
+/// `stop` returns the reason for stopping; it is called with the number of states explored
+/// so far, including those explored before the snapshot was taken, before every state is taken
+/// from the fringe. Only in the latter case, a snapshot to resume from is returned as well.
+/// Since the search continues exactly where it was stopped, resuming it any number of times
+/// finds the same plan as an uninterrupted search.
+///
+/// Fails if the snapshot is inconsistent, e.g. because it was edited by hand.
+pub fn resume<S, A, G, X>(
+    snapshot: SearchSnapshot<S, A>,
+    goal: G,
+    history: &mut History<S, A>,
+    stop: X,
+) -> Result<Resumed<S, A>, String>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    X: FnMut(usize) -> Option<String>,
+{
+    if !history.is_empty() {
+        return Err(String::from(
+            "The history to resume a search in must be empty.",
+        ));
+    }
+
+    let mut observed = Visited::new();
+    for (id, entry) in snapshot.entries.into_iter().enumerate() {
+        if observed.contains(&entry.state) {
+            return Err(format!("Entry {id} of the snapshot was discovered before."));
+        }
+
+        let lineage = match (entry.parent, entry.action) {
+            (None, None) => history.create_root(entry.state),
+            (Some(parent), Some(action)) if parent < id => {
+                let parent = Rc::clone(history.get(parent).expect("parent was restored"));
+                history.create_entry(action, entry.state, &parent)
+            }
+            _ => return Err(format!("Entry {id} of the snapshot has no valid parent.")),
+        };
+        observed.insert_entry(lineage);
+    }
+
+    let mut fringe = Fifo::new();
+    for id in snapshot.fringe {
+        let Some(lineage) = history.get(id) else {
+            return Err(format!(
+                "The fringe of the snapshot refers to unknown entry {id}."
+            ));
+        };
+        fringe.push(Rc::clone(lineage));
+    }
+
+    let outcome = explore(
+        goal,
+        |_| false,
+        &mut fringe,
+        history,
+        &mut observed,
+        usize::MAX,
+        snapshot.states_explored,
+        stop,
+    );
+
+    let snapshot = match &outcome {
+        SearchOutcome::Exhausted {
+            states_explored, ..
+        } => Some(SearchSnapshot::capture(
+            history,
+            std::iter::from_fn(|| fringe.pop()),
+            *states_explored,
+        )),
+        _ => None,
+    };
+    Ok((outcome, snapshot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::{HumansAndZombies, WorldAction, WorldState};
+    use crate::problems::Problem;
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn resumed_search_finds_the_same_plan() {
+        let initial = HumansAndZombies::new(10, 10, 4).initial();
+        let expected = quietly(|| search(initial.clone())).expect("problem is solvable");
+
+        // Stop after every few states, round-tripping the snapshot through JSON each time.
+        let mut snapshot = SearchSnapshot::new(initial);
+        let mut resumed = 0;
+        let plan = loop {
+            let json = serde_json::to_string(&snapshot).expect("snapshot is serializable");
+            let restored: SearchSnapshot<WorldState, WorldAction> =
+                serde_json::from_str(&json).expect("snapshot is deserializable");
+
+            let budget = restored.states_explored + 25;
+            let (outcome, next) = quietly(|| {
+                resume(
+                    restored,
+                    WorldState::is_goal,
+                    &mut History::new(),
+                    |explored| (explored >= budget).then(|| String::from("budget exhausted")),
+                )
+            })
+            .expect("snapshot is consistent");
+
+            match next {
+                Some(next) => snapshot = next,
+                None => break outcome.into_plan().expect("problem is solvable"),
+            }
+            resumed += 1;
+        };
+
+        assert!(resumed > 1);
+        let steps = |plan: &crate::plan::Plan<WorldState, WorldAction>| {
+            plan.steps()
+                .iter()
+                .map(|(action, state)| (action.humans, action.zombies, state.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert!(steps(&plan) == steps(&expected));
+    }
+}
@@ -0,0 +1,301 @@
+//! Statistics about a solution and summaries of search outcomes, rendered as text or
+//! serialized as JSON.
+
+use crate::analysis::SearchAnalysis;
+use crate::compare::OptimalityGap;
+use crate::plan::Plan;
+use crate::problems::Problem;
+use crate::search::{Action, SearchOutcome, State};
+use crate::strategies::Objective;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Statistics about a solution, such as its cost and the effort it took to find it.
+///
+/// The [`Display`] implementation lists every statistic on a line of its own, with the
+/// values aligned and large numbers separated into thousands.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statistics {
+    /// The cost of the plan by every objective the problem supports.
+    pub costs: Vec<(Objective, u32)>,
+    /// How far the plan is from the cheapest one, if the strategy may have missed it.
+    pub gap: Option<OptimalityGap>,
+    /// The problem-specific statistics, as pairs of a name and a value.
+    pub problem: Vec<(String, String)>,
+    /// The number of states explored.
+    pub states_explored: usize,
+    /// The shape of the explored search tree, if it was analyzed.
+    pub analysis: Option<SearchAnalysis>,
+}
+
+impl Statistics {
+    /// Collects the statistics of a plan found for the problem after exploring the specified
+    /// number of states; the optimality gap and the analysis are left to be filled in.
+    pub fn new<P, S, A>(problem: &P, plan: &Plan<S, A>, states_explored: usize) -> Self
+    where
+        P: Problem<State = S>,
+        S: State<Action = A>,
+        A: Action<State = S>,
+    {
+        Self {
+            costs: costs(problem, plan),
+            gap: None,
+            problem: problem.statistics(plan),
+            states_explored,
+            analysis: None,
+        }
+    }
+
+    /// Gets the lines of the statistics, each as a name, a number to align with the others
+    /// and the text following it.
+    fn lines(&self) -> Vec<(String, String, String)> {
+        let number = |name: String, value: u64| (name, thousands(value), String::new());
+
+        let mut lines = Vec::new();
+        for (objective, cost) in &self.costs {
+            lines.push(number(objective.to_string(), *cost as u64));
+        }
+        if let Some(gap) = &self.gap {
+            lines.push(number(
+                format!("optimal {}", gap.objective),
+                gap.optimal as u64,
+            ));
+            lines.push((
+                String::from("optimality gap"),
+                thousands(gap.absolute() as u64),
+                format!(" ({:.1}%)", gap.percentage()),
+            ));
+        }
+        for (name, value) in &self.problem {
+            match value.parse() {
+                Ok(value) => lines.push(number(name.clone(), value)),
+                Err(_) => lines.push((name.clone(), String::new(), value.clone())),
+            }
+        }
+        lines.push(number(
+            String::from("states explored"),
+            self.states_explored as u64,
+        ));
+        if let Some(analysis) = &self.analysis {
+            for (name, value) in analysis.lines() {
+                lines.push((name, String::new(), value));
+            }
+        }
+        lines
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self.lines();
+        let names = lines.iter().map(|(name, ..)| name.chars().count() + 1);
+        let name_width = names.max().unwrap_or_default();
+        let values = lines.iter().map(|(_, value, _)| value.chars().count());
+        let value_width = values.max().unwrap_or_default();
+
+        for (name, value, rest) in lines {
+            let name = format!("{name}:");
+            // Anything but a number follows the names directly.
+            let line = if value.is_empty() {
+                format!("  {name:<name_width$} {rest}")
+            } else {
+                format!("  {name:<name_width$} {value:>value_width$}{rest}")
+            };
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// A one-line summary of the outcome of a search.
+///
+/// The [`Display`] implementation renders it as e.g. `solved in 11 steps (trips: 11, moves: 18)`,
+/// while it is serialized as a JSON object tagged with the `outcome`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "lowercase")]
+pub enum Summary {
+    /// A plan was found.
+    Solved {
+        /// The number of steps of the plan.
+        steps: usize,
+        /// The cost of the plan by every objective the problem supports.
+        costs: Vec<(Objective, u32)>,
+    },
+    /// The problem was proven to be unsolvable.
+    Unsolvable {
+        /// The number of states explored.
+        states_explored: usize,
+    },
+    /// The search was stopped before it found a plan.
+    Stopped {
+        /// Why the search was stopped.
+        reason: String,
+        /// The number of states explored until then.
+        states_explored: usize,
+    },
+}
+
+impl Summary {
+    /// Summarizes the outcome of a search for the problem.
+    pub fn new<P, S, A>(problem: &P, outcome: &SearchOutcome<S, A>) -> Self
+    where
+        P: Problem<State = S>,
+        S: State<Action = A>,
+        A: Action<State = S>,
+    {
+        match outcome {
+            SearchOutcome::Solved(plan) => Summary::Solved {
+                steps: plan.len(),
+                costs: costs(problem, plan),
+            },
+            SearchOutcome::Unsolvable { states_explored } => Summary::Unsolvable {
+                states_explored: *states_explored,
+            },
+            SearchOutcome::Exhausted {
+                reason,
+                states_explored,
+            } => Summary::Stopped {
+                reason: reason.clone(),
+                states_explored: *states_explored,
+            },
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let states = |count: usize| {
+            let plural = if count == 1 { "" } else { "s" };
+            format!("{} state{plural}", thousands(count as u64))
+        };
+
+        match self {
+            Summary::Solved { steps, costs } => {
+                let plural = if *steps == 1 { "" } else { "s" };
+                write!(f, "solved in {steps} step{plural}")?;
+                if !costs.is_empty() {
+                    let costs = costs
+                        .iter()
+                        .map(|(objective, cost)| format!("{objective}: {cost}"))
+                        .collect::<Vec<_>>();
+                    write!(f, " ({})", costs.join(", "))?;
+                }
+                Ok(())
+            }
+            Summary::Unsolvable { states_explored } => {
+                write!(f, "unsolvable after exploring {}", states(*states_explored))
+            }
+            Summary::Stopped {
+                reason,
+                states_explored,
+            } => write!(
+                f,
+                "stopped after exploring {} ({reason})",
+                states(*states_explored)
+            ),
+        }
+    }
+}
+
+/// Gets the cost of the plan by every objective the problem supports.
+fn costs<P, S, A>(problem: &P, plan: &Plan<S, A>) -> Vec<(Objective, u32)>
+where
+    P: Problem<State = S>,
+    S: State<Action = A>,
+    A: Action<State = S>,
+{
+    problem
+        .objectives()
+        .iter()
+        .map(|&objective| {
+            let cost = plan
+                .steps()
+                .iter()
+                .map(|(action, _)| action.cost(objective))
+                .sum();
+            (objective, cost)
+        })
+        .collect()
+}
+
+/// Formats a number with commas separating every three digits, e.g. `32,767`.
+pub fn thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::History;
+    use crate::problems::hanoi::Hanoi;
+    use crate::problems::humans_and_zombies::HumansAndZombies;
+    use crate::search::{search, solve_with_goal};
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn thousands_are_separated() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1000), "1,000");
+        assert_eq!(thousands(32767), "32,767");
+        assert_eq!(thousands(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn values_are_aligned() {
+        let problem = Hanoi::new(10);
+        let plan = quietly(|| search(problem.initial())).expect("problem is solvable");
+        let statistics = Statistics::new(&problem, &plan, 12345);
+
+        let text = statistics.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            ["  trips:            1,023", "  states explored: 12,345"]
+        );
+
+        let json = serde_json::to_string(&statistics).expect("statistics are serializable");
+        assert_eq!(
+            json,
+            r#"{"costs":[["trips",1023]],"gap":null,"problem":[],"states_explored":12345,"analysis":null}"#
+        );
+    }
+
+    #[test]
+    fn outcomes_are_summarized() {
+        let summarize = |problem: &HumansAndZombies| {
+            let outcome = quietly(|| {
+                solve_with_goal(
+                    problem.initial(),
+                    |s| problem.is_goal(s),
+                    &mut History::new(),
+                )
+            });
+            Summary::new(problem, &outcome)
+        };
+
+        let solved = summarize(&HumansAndZombies::default());
+        assert_eq!(
+            solved.to_string(),
+            "solved in 11 steps (trips: 11, moves: 18)"
+        );
+        assert_eq!(
+            serde_json::to_string(&solved).expect("summary is serializable"),
+            r#"{"outcome":"solved","steps":11,"costs":[["trips",11],["moves",18]]}"#
+        );
+
+        let unsolvable = summarize(&HumansAndZombies::new(4, 4, 2));
+        assert!(matches!(unsolvable, Summary::Unsolvable { .. }));
+        assert!(unsolvable
+            .to_string()
+            .starts_with("unsolvable after exploring "));
+    }
+}
@@ -1,4 +1,69 @@
-use std::collections::VecDeque;
+use serde::Serialize;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::{Display, Formatter};
+
+/// The search strategy, i.e. the order in which states are explored.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Breadth-first search, exploring states in the order they were discovered.
+    #[default]
+    BreadthFirst,
+    /// Greedy best-first search, exploring states with the lowest heuristic estimate first.
+    Greedy,
+    /// Breadth-first search, expanding all states of a depth level in parallel.
+    #[cfg(feature = "rayon")]
+    ParallelBreadthFirst,
+    /// A random walk, backtracking on dead ends.
+    Random,
+    /// Iterative deepening A*, repeating a depth-first search with a growing cost threshold.
+    IterativeDeepening,
+}
+
+impl Strategy {
+    /// Determines whether the strategy finds a plan with the minimal cost by the objective
+    /// searched for, if it finds one at all.
+    pub fn is_optimal(self) -> bool {
+        match self {
+            Strategy::BreadthFirst => true,
+            #[cfg(feature = "rayon")]
+            Strategy::ParallelBreadthFirst => true,
+            _ => false,
+        }
+    }
+}
+
+/// The objective to minimize, i.e. how the cost of a plan is measured.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Objective {
+    /// The number of crossings.
+    #[default]
+    Trips,
+    /// The time the crossings take.
+    Time,
+    /// The number of entities moved, summed over all crossings.
+    Moves,
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Objective::Trips => write!(f, "trips"),
+            Objective::Time => write!(f, "time"),
+            Objective::Moves => write!(f, "moves"),
+        }
+    }
+}
+
+/// A fringe, i.e. the collection of items yet to be explored.
+pub trait Fringe<T> {
+    /// Adds an item to the fringe.
+    fn push(&mut self, item: T);
+
+    /// Takes the next item to explore from the fringe.
+    fn pop(&mut self) -> Option<T>;
+}
 
 /// A last in, first out structure, i.e. a stack.
 #[derive(Debug)]
@@ -8,7 +73,38 @@ pub struct Lifo<T>(Vec<T>);
 #[derive(Debug)]
 pub struct Fifo<T>(VecDeque<T>);
 
-#[allow(dead_code)]
+/// A priority queue that yields the item with the lowest key first.
+/// Items with equal keys are yielded in the order they were added.
+pub struct PriorityFringe<T, K, F> {
+    heap: BinaryHeap<Prioritized<T, K>>,
+    key: F,
+    sequence: usize,
+}
+
+/// A fringe keeping track of the number of items taken from it, and of the number of items
+/// it held at once at most.
+#[derive(Debug, Default)]
+pub struct Measured<F> {
+    fringe: F,
+    len: usize,
+    peak: usize,
+    popped: usize,
+}
+
+/// A fringe passing the number of items taken from it so far, the number of items it still
+/// holds and the item taken to a callback every time an item is taken from it, e.g. to show the
+/// progress of a long search.
+pub struct Reported<F, R> {
+    fringe: Measured<F>,
+    report: R,
+}
+
+/// An item in a [`PriorityFringe`], ordered by its key and sequence number.
+struct Prioritized<T, K> {
+    priority: Reverse<(K, usize)>,
+    item: T,
+}
+
 impl<T> Lifo<T> {
     pub const fn new() -> Self {
         Self(Vec::new())
@@ -21,6 +117,32 @@ impl<T> Lifo<T> {
     pub fn pop(&mut self) -> Option<T> {
         self.0.pop()
     }
+
+    /// Gets the number of items in the stack.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Determines whether the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets the item that is popped next, i.e. the one pushed last.
+    pub fn peek(&self) -> Option<&T> {
+        self.0.last()
+    }
+
+    /// Gets the item that is popped next for modification.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.0.last_mut()
+    }
+}
+
+impl<T> Default for Lifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> From<T> for Lifo<T> {
@@ -31,7 +153,6 @@ impl<T> From<T> for Lifo<T> {
     }
 }
 
-#[allow(dead_code)]
 impl<T> Fifo<T> {
     pub const fn new() -> Self {
         Self(VecDeque::new())
@@ -44,6 +165,32 @@ impl<T> Fifo<T> {
     pub fn pop(&mut self) -> Option<T> {
         self.0.pop_front()
     }
+
+    /// Gets the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Determines whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets the item that is popped next, i.e. the one pushed first.
+    pub fn peek(&self) -> Option<&T> {
+        self.0.front()
+    }
+
+    /// Gets the item that is popped next for modification.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.0.front_mut()
+    }
+}
+
+impl<T> Default for Fifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> From<T> for Fifo<T> {
@@ -53,3 +200,261 @@ impl<T> From<T> for Fifo<T> {
         set
     }
 }
+
+/// Drains the stack, yielding the items in the order they would be popped.
+impl<T> Iterator for Lifo<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+/// Drains the queue, yielding the items in the order they would be popped.
+impl<T> Iterator for Fifo<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for Lifo<T> {}
+
+impl<T> ExactSizeIterator for Fifo<T> {}
+
+impl<T> Fringe<T> for Lifo<T> {
+    fn push(&mut self, item: T) {
+        Lifo::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Lifo::pop(self)
+    }
+}
+
+impl<T> Fringe<T> for Fifo<T> {
+    fn push(&mut self, item: T) {
+        Fifo::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Fifo::pop(self)
+    }
+}
+
+impl<T, K, F> PriorityFringe<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    /// Creates a new, empty priority fringe ordering its items by the specified key.
+    pub fn new(key: F) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            key,
+            sequence: 0,
+        }
+    }
+}
+
+impl<T, K, F> Fringe<T> for PriorityFringe<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    fn push(&mut self, item: T) {
+        let key = (self.key)(&item);
+        self.heap.push(Prioritized {
+            priority: Reverse((key, self.sequence)),
+            item,
+        });
+        self.sequence += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|entry| entry.item)
+    }
+}
+
+impl<F> Measured<F> {
+    /// Wraps the specified, usually empty, fringe.
+    pub const fn new(fringe: F) -> Self {
+        Self {
+            fringe,
+            len: 0,
+            peak: 0,
+            popped: 0,
+        }
+    }
+
+    /// Gets the number of items taken from the fringe so far.
+    pub fn popped(&self) -> usize {
+        self.popped
+    }
+
+    /// Gets the number of items the fringe held at once at most.
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Gets the number of items in the fringe.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determines whether the fringe is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T, F: Fringe<T>> Fringe<T> for Measured<F> {
+    fn push(&mut self, item: T) {
+        self.fringe.push(item);
+        self.len += 1;
+        self.peak = self.peak.max(self.len);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let item = self.fringe.pop()?;
+        self.len -= 1;
+        self.popped += 1;
+        Some(item)
+    }
+}
+
+/// Allows passing a fringe by reference in order to inspect it after the search.
+impl<F, R> Reported<F, R> {
+    /// Wraps the specified, usually empty, fringe, reporting to the callback.
+    pub const fn new(fringe: F, report: R) -> Self {
+        Self {
+            fringe: Measured::new(fringe),
+            report,
+        }
+    }
+}
+
+impl<T, F, R> Fringe<T> for Reported<F, R>
+where
+    F: Fringe<T>,
+    R: FnMut(usize, usize, &T),
+{
+    fn push(&mut self, item: T) {
+        self.fringe.push(item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let item = self.fringe.pop()?;
+        (self.report)(self.fringe.popped(), self.fringe.len(), &item);
+        Some(item)
+    }
+}
+
+impl<T, F: Fringe<T>> Fringe<T> for &mut F {
+    fn push(&mut self, item: T) {
+        (**self).push(item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        (**self).pop()
+    }
+}
+
+impl<T, K: Ord> PartialEq for Prioritized<T, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<T, K: Ord> Eq for Prioritized<T, K> {}
+
+impl<T, K: Ord> PartialOrd for Prioritized<T, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, K: Ord> Ord for Prioritized<T, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_fringe_pops_in_ascending_order() {
+        let mut fringe = PriorityFringe::new(|item: &(u32, char)| item.0);
+        for item in [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')] {
+            fringe.push(item);
+        }
+
+        let popped: String = std::iter::from_fn(|| fringe.pop())
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(popped, "ebdca");
+    }
+
+    #[test]
+    fn measured_fringe_tracks_peak_size() {
+        fn push_all(mut fringe: impl Fringe<u32>) {
+            for item in [1, 2, 3] {
+                fringe.push(item);
+            }
+        }
+
+        let mut fringe = Measured::new(Lifo::new());
+        push_all(&mut fringe);
+        fringe.pop();
+        fringe.pop();
+        fringe.push(4);
+
+        assert_eq!(fringe.peak(), 3);
+        assert_eq!(fringe.popped(), 2);
+        assert_eq!(fringe.len(), 2);
+    }
+
+    #[test]
+    fn reported_fringe_passes_on_its_progress() {
+        let mut reports = Vec::new();
+        let mut fringe = Reported::new(Fifo::new(), |popped, len, item: &char| {
+            reports.push((popped, len, *item))
+        });
+        for item in ['a', 'b', 'c'] {
+            fringe.push(item);
+        }
+        while fringe.pop().is_some() {}
+
+        assert_eq!(reports, [(1, 2, 'a'), (2, 1, 'b'), (3, 0, 'c')]);
+    }
+
+    #[test]
+    fn fifo_and_lifo_peek_at_next_item() {
+        let mut fifo = Fifo::from(1);
+        let mut lifo = Lifo::from(1);
+        for item in [2, 3] {
+            fifo.push(item);
+            lifo.push(item);
+        }
+
+        assert_eq!(fifo.len(), 3);
+        assert_eq!(fifo.peek(), Some(&1));
+        assert_eq!(lifo.peek(), Some(&3));
+
+        *lifo.peek_mut().expect("stack is not empty") = 4;
+        assert_eq!(fifo.collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(lifo.by_ref().collect::<Vec<_>>(), [4, 2, 1]);
+        assert!(lifo.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::history::{History, Lineage};
+use crate::search::{solve_with_goal, Action, SearchOutcome, State};
+use crate::visited::StateMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate using
+/// breadth-first search, collapsing every state with its [mirror image](State::mirror) and
+/// recording every explored state in the specified [`History`].
+///
+/// If the mirror image of the initial state is a goal state, a path from the initial state to the
+/// mirror image of any explored state, played backwards and mirrored, leads from that state to the
+/// goal. Once a state is discovered whose mirror image was explored already, the plan is therefore
+/// complete, and only about half of the states up to the middle of the plan need to be explored.
+/// Like the plain breadth-first search, the returned plan is the shortest one.
+///
+/// If the problem is not symmetric, this falls back to [`solve_with_goal`].
+pub fn solve_with_symmetry<S, A, G>(
+    initial_state: S,
+    goal: G,
+    history: &mut History<S, A>,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + Hash,
+    G: Fn(&S) -> bool,
+{
+    if !initial_state.mirror().is_some_and(|mirror| goal(&mirror)) {
+        trace!("The problem is not symmetric; exploring the full state space.");
+        return solve_with_goal(initial_state, goal, history);
+    }
+
+    let root = history.create_root(initial_state);
+    let mut observed: StateMap<S::Hash, Vec<Rc<Lineage<S, A>>>> = StateMap::default();
+    observed
+        .entry(root.state.canonical_hash())
+        .or_default()
+        .push(Rc::clone(&root));
+
+    let mut level = vec![root];
+    let mut states_explored = 0;
+    while !level.is_empty() {
+        // Pairs of a discovered state and the explored lineage of its mirror image.
+        let mut meetings = Vec::new();
+        let mut next_level = Vec::new();
+
+        for lineage in std::mem::take(&mut level) {
+            let state = &lineage.state;
+            trace!("Exploring state {}: {:?}", lineage.id, state);
+            states_explored += 1;
+
+            if goal(state) {
+                trace!("  Goal reached.");
+                history.set_goal(&lineage);
+                return SearchOutcome::Solved(history.backtrack(&lineage).collect());
+            }
+
+            for action in state.get_actions() {
+                let new_state = action.apply(state);
+                let bucket = observed.entry(new_state.canonical_hash()).or_default();
+                if bucket.iter().any(|known| known.state == new_state) {
+                    trace!("  Ignored:    {:?} (recursion)", action);
+                    continue;
+                }
+
+                let mirror = new_state.mirror().expect("problem is symmetric");
+                let mirrored = bucket.iter().find(|known| known.state == mirror).cloned();
+
+                let child = history.create_entry(action, new_state, &lineage);
+                match mirrored {
+                    Some(mirrored) => {
+                        trace!(
+                            "  Mirrored:   state {} meets state {}",
+                            child.id,
+                            mirrored.id
+                        );
+                        meetings.push((child, mirrored));
+                    }
+                    None => {
+                        bucket.push(Rc::clone(&child));
+                        next_level.push(child);
+                    }
+                }
+            }
+        }
+
+        // Several meetings may occur within the same level; keep the shortest plan.
+        let shortest = meetings
+            .into_iter()
+            .min_by_key(|(child, mirrored)| child.depth + mirrored.depth);
+        if let Some((child, mirrored)) = shortest {
+            let goal = unwind(history, child, &mirrored);
+            history.set_goal(&goal);
+            return SearchOutcome::Solved(history.backtrack(&goal).collect());
+        }
+
+        level = next_level;
+    }
+
+    SearchOutcome::Unsolvable { states_explored }
+}
+
+/// Continues the path of `lineage` along the mirror images of the states on the path of
+/// `mirrored`, backwards, recording every step in the specified [`History`].
+///
+/// Returns the lineage of the last state, i.e. the mirror image of the initial state.
+fn unwind<S, A>(
+    history: &mut History<S, A>,
+    mut lineage: Rc<Lineage<S, A>>,
+    mirrored: &Lineage<S, A>,
+) -> Rc<Lineage<S, A>>
+where
+    S: State<Action = A> + Clone + Eq,
+    A: Action<State = S> + Clone,
+{
+    let path = history.backtrack(mirrored).collect::<Vec<_>>();
+    for (_, state) in path.into_iter().rev().skip(1) {
+        let target = state.mirror().expect("problem is symmetric");
+        let action = lineage
+            .state
+            .get_actions()
+            .into_iter()
+            .find(|action| action.apply(&lineage.state) == target)
+            .expect("mirrored actions are applicable");
+        lineage = history.create_entry(action, target, &lineage);
+    }
+
+    lineage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::{HumansAndZombies, WorldState};
+    use crate::problems::Problem;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn symmetric_search_finds_shortest_plan_exploring_fewer_states() {
+        let initial = HumansAndZombies::new(20, 20, 4).initial();
+
+        let mut plain = History::new();
+        let expected =
+            quietly(|| solve_with_goal(initial.clone(), WorldState::is_goal, &mut plain))
+                .into_plan()
+                .expect("problem is solvable");
+
+        let mut symmetric = History::new();
+        let plan = quietly(|| solve_with_symmetry(initial, WorldState::is_goal, &mut symmetric))
+            .into_plan()
+            .expect("problem is solvable");
+
+        assert_eq!(plan.len(), expected.len());
+        assert!(plan.goal().is_goal());
+        let mut state = plan.initial().clone();
+        for (action, next) in plan.steps() {
+            assert!(action.is_applicable(&state));
+            state = action.apply(&state);
+            assert_eq!(&state, next);
+        }
+
+        assert!(symmetric.len() < plain.len());
+    }
+}
@@ -0,0 +1,88 @@
+//! Generators of solvable problem instances and checkers of the invariants every plan upholds,
+//! bundled for property tests, e.g. using `proptest` or `quickcheck`.
+//!
+//! ```
+//! use river_crossing::problems::humans_and_zombies::WorldState;
+//! use river_crossing::search::{search, State};
+//! use river_crossing::testing::{check_plan, solvable_instances};
+//! use river_crossing::verbosity::quietly;
+//!
+//! for (initial, length) in solvable_instances(42, 5).take(3) {
+//!     let plan = quietly(|| search(initial)).expect("instance is solvable");
+//!     assert_eq!(plan.len(), length);
+//!     check_plan(&plan, WorldState::is_goal).expect("plan is valid");
+//! }
+//! ```
+
+use crate::plan::Plan;
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::humans_and_zombies::WorldState;
+use crate::replay::RecordedPlan;
+use crate::search::{Action, State};
+use std::iter::once;
+
+pub use crate::problems::humans_and_zombies::random_solvable;
+pub use crate::replay::{validate_plan, InvalidStep};
+
+/// Generates an endless sequence of solvable Humans and Zombies instances, drawn by
+/// [`random_solvable`] from consecutive seeds starting at `seed`, each together with the length
+/// of its optimal plan.
+pub fn solvable_instances(
+    seed: u64,
+    max_population: u8,
+) -> impl Iterator<Item = (WorldState, usize)> {
+    (seed..).map(move |seed| random_solvable(seed, max_population))
+}
+
+/// Checks that every state of the plan [is valid](State::is_valid), and that replaying the plan
+/// via [`validate_plan`] re-applies every action legally and reaches the goal.
+///
+/// Returns the first step violating an invariant, if any.
+pub fn check_plan<S, A, G>(plan: &Plan<S, A>, is_goal: G) -> Result<(), InvalidStep>
+where
+    S: State<Action = A> + PrettyPrintState + Clone,
+    A: Action<State = S> + PrettyPrintAction<S>,
+    G: Fn(&S) -> bool,
+{
+    let states = once(plan.initial()).chain(plan.steps().iter().map(|(_, state)| state));
+    for (step, state) in states.enumerate() {
+        if !state.is_valid() {
+            return Err(InvalidStep {
+                step,
+                reason: format!("`{}` is not a valid state", state.pretty_print().trim()),
+            });
+        }
+    }
+
+    validate_plan(plan.initial().clone(), &RecordedPlan::record(plan), is_goal).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::{Boat, RiverBank, RiverBankState, WorldAction};
+    use crate::search::search;
+    use crate::verbosity::quietly;
+
+    #[test]
+    fn plans_of_generated_instances_uphold_the_invariants() {
+        for (initial, length) in solvable_instances(0, 6).take(20) {
+            let plan = quietly(|| search(initial)).expect("instance is solvable");
+            assert_eq!(plan.len(), length);
+            assert_eq!(check_plan(&plan, WorldState::is_goal), Ok(()));
+        }
+
+        // Leaving three zombies with a lone human breaks the plan right away.
+        let mut plan = Plan::new(WorldState::default());
+        let outnumbered = WorldState::new(
+            RiverBankState::new(1, 3),
+            RiverBankState::new(2, 0),
+            Boat::new(2, RiverBank::Right),
+        );
+        plan.push(WorldAction::new(2, 0), outnumbered);
+        assert_eq!(
+            check_plan(&plan, WorldState::is_goal).map_err(|e| e.step),
+            Err(1)
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::analysis::count_generated;
+use crate::history::History;
+use crate::json::escape_json;
+use crate::plan::Plan;
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState, RenderStyle};
+use crate::search::{Action, SearchOutcome, State};
+use crate::strategies::Fifo;
+use crate::visited::Visited;
+use std::fmt::{Debug, Write};
+use std::rc::Rc;
+use std::sync::mpsc::Sender;
+
+/// An event recorded while exploring the state space.
+///
+/// States are identified by the IDs of their lineages in the [`History`],
+/// and their depth is the number of actions taken to reach them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TraceEvent {
+    /// A state was taken from the fringe and expanded into new states.
+    Expanded {
+        /// The ID of the expanded state.
+        id: usize,
+        /// The depth of the expanded state.
+        depth: usize,
+        /// The IDs of the newly discovered states.
+        children: Vec<usize>,
+    },
+    /// A state was discovered again and discarded.
+    PrunedDuplicate {
+        /// The ID of the state it was discovered from.
+        parent: usize,
+        /// The depth it was discovered at.
+        depth: usize,
+        /// Why the state was discarded, naming the action that lead to it.
+        reason: String,
+    },
+    /// A state was taken from the fringe, but did not lead to any new states.
+    DeadEnd {
+        /// The ID of the state.
+        id: usize,
+        /// The depth of the state.
+        depth: usize,
+        /// Why the state did not lead anywhere, e.g. because no action is possible there.
+        reason: String,
+    },
+    /// A state was taken from the fringe and found to be a goal state.
+    GoalFound {
+        /// The ID of the goal state.
+        id: usize,
+        /// The depth of the goal state, i.e. the length of the plan.
+        depth: usize,
+    },
+}
+
+impl TraceEvent {
+    /// Renders the event as a JSON object.
+    pub fn to_json(&self) -> String {
+        match self {
+            TraceEvent::Expanded {
+                id,
+                depth,
+                children,
+            } => {
+                let children = children.iter().map(usize::to_string).collect::<Vec<_>>();
+                format!(
+                    r#"{{"event":"expanded","id":{id},"depth":{depth},"children":[{}]}}"#,
+                    children.join(",")
+                )
+            }
+            TraceEvent::PrunedDuplicate {
+                parent,
+                depth,
+                reason,
+            } => format!(
+                r#"{{"event":"pruned_duplicate","parent":{parent},"depth":{depth},"reason":"{}"}}"#,
+                escape_json(reason)
+            ),
+            TraceEvent::DeadEnd { id, depth, reason } => format!(
+                r#"{{"event":"dead_end","id":{id},"depth":{depth},"reason":"{}"}}"#,
+                escape_json(reason)
+            ),
+            TraceEvent::GoalFound { id, depth } => {
+                format!(r#"{{"event":"goal_found","id":{id},"depth":{depth}}}"#)
+            }
+        }
+    }
+}
+
+/// Renders the events as a JSON array, one event per line.
+pub fn to_json(events: &[TraceEvent]) -> String {
+    let mut json = String::from("[\n");
+    for (index, event) in events.iter().enumerate() {
+        let separator = if index + 1 < events.len() { "," } else { "" };
+        writeln!(json, "  {}{separator}", event.to_json())
+            .expect("writing to a string cannot fail");
+    }
+    json.push_str("]\n");
+    json
+}
+
+/// Searches the state space for a plan like [`search`](crate::search::search),
+/// recording every step of the exploration.
+pub fn search_with_trace<S, A>(initial_state: S) -> (Option<Plan<S, A>>, Vec<TraceEvent>)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let (outcome, events) = solve_with_trace(initial_state, S::is_goal, &mut History::new());
+    (outcome.into_plan(), events)
+}
+
+/// Searches the state space for a plan like [`search`](crate::search::search),
+/// sending every step of the exploration to the specified channel as it happens.
+///
+/// This allows e.g. a separate thread to render the progress of the search while it runs.
+/// The events are the same as recorded by [`search_with_trace`], in the same order.
+///
+/// The channel is unbounded, so a slow receiver never holds up the search; instead, the
+/// events not received yet queue up in memory, roughly one per explored state. If the
+/// receiver is dropped, the search continues without sending any further events.
+pub fn search_with_events<S, A>(initial_state: S, tx: Sender<TraceEvent>) -> Option<Plan<S, A>>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+{
+    let mut connected = true;
+    let outcome = solve_observed(initial_state, S::is_goal, &mut History::new(), |event| {
+        connected = connected && tx.send(event).is_ok();
+    });
+    outcome.into_plan()
+}
+
+/// Searches the state space for a plan reaching a state accepted by the `goal` predicate using
+/// breadth-first search, recording every explored state in the specified [`History`] and every
+/// step of the exploration as a [`TraceEvent`].
+///
+/// This explores the same states in the same order as
+/// [`solve_with_goal`](crate::search::solve_with_goal).
+pub fn solve_with_trace<S, A, G>(
+    initial_state: S,
+    goal: G,
+    history: &mut History<S, A>,
+) -> (SearchOutcome<S, A>, Vec<TraceEvent>)
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+{
+    let mut events = Vec::new();
+    let outcome = solve_observed(initial_state, goal, history, |event| events.push(event));
+    (outcome, events)
+}
+
+/// Searches the state space like [`solve_with_trace`], passing every step of the exploration
+/// to `emit` as it happens.
+fn solve_observed<S, A, G, E>(
+    initial_state: S,
+    goal: G,
+    history: &mut History<S, A>,
+    mut emit: E,
+) -> SearchOutcome<S, A>
+where
+    S: State<Action = A> + Clone + Eq + Debug,
+    A: Action<State = S> + Clone + Debug,
+    S::Hash: Eq + std::hash::Hash,
+    G: Fn(&S) -> bool,
+    E: FnMut(TraceEvent),
+{
+    let mut observed = Visited::new();
+    let root = history.create_root(initial_state);
+    observed.insert_entry(Rc::clone(&root));
+    let mut fringe = Fifo::new();
+    fringe.push(root);
+
+    let mut states_explored = 0;
+    while let Some(lineage) = fringe.pop() {
+        let depth = lineage.depth;
+        let state = &lineage.state;
+        trace!("Exploring state {}: {:?}", lineage.id, state);
+        states_explored += 1;
+
+        if goal(state) {
+            trace!("  Goal reached.");
+            emit(TraceEvent::GoalFound {
+                id: lineage.id,
+                depth,
+            });
+            history.set_goal(&lineage);
+            return SearchOutcome::Solved(history.backtrack(&lineage).collect());
+        }
+
+        let actions = state.get_actions();
+        let possible = !actions.is_empty();
+        let mut children = Vec::new();
+        for action in actions {
+            let new_state = action.apply(state);
+            let duplicate = observed.revisit(&new_state);
+            count_generated(duplicate);
+            if duplicate {
+                trace!("  Ignored:    {:?} (recursion)", action);
+                emit(TraceEvent::PrunedDuplicate {
+                    parent: lineage.id,
+                    depth: depth + 1,
+                    reason: format!("{action:?} leads to a state discovered before"),
+                });
+                continue;
+            }
+
+            let child = history.create_entry(action, new_state, &lineage);
+            observed.insert_entry(Rc::clone(&child));
+            children.push(child.id);
+            fringe.push(child);
+        }
+
+        if children.is_empty() {
+            trace!("  Dead end: State {} could not be expanded.", lineage.id);
+            let reason = if possible {
+                String::from("every action leads to a state discovered before")
+            } else {
+                state
+                    .explain_dead_end()
+                    .unwrap_or_else(|| String::from("no action is possible"))
+            };
+            emit(TraceEvent::DeadEnd {
+                id: lineage.id,
+                depth,
+                reason,
+            });
+        } else {
+            emit(TraceEvent::Expanded {
+                id: lineage.id,
+                depth,
+                children,
+            });
+        }
+    }
+
+    SearchOutcome::Unsolvable { states_explored }
+}
+
+/// Formats the path to the goal found by a [traced](solve_with_trace) search using the symbols
+/// of the specified style, listing below
+/// every state on it the branches the search abandoned there, each with the reason why.
+///
+/// A branch is abandoned if it leads to a state discovered before, or to a state that does not
+/// lead anywhere. The search also passes over the states it explored, or has yet to explore, when
+/// it finds the goal through another one, since breadth-first search finds a shortest path first.
+/// Returns [`None`] if the trace does not end in a goal state.
+pub fn format_explanation<S, A>(
+    history: &History<S, A>,
+    events: &[TraceEvent],
+    style: &RenderStyle,
+) -> Option<String>
+where
+    S: PrettyPrintState,
+    A: PrettyPrintAction<S> + Debug,
+{
+    let Some(TraceEvent::GoalFound { id: goal, .. }) = events.last() else {
+        return None;
+    };
+
+    let mut path = vec![history.get(*goal)?];
+    while let Some(parent) = path.last().and_then(|lineage| lineage.parent_id()) {
+        path.push(history.get(parent)?);
+    }
+    path.reverse();
+
+    let mut buffer = String::new();
+    for (index, lineage) in path.iter().enumerate() {
+        writeln!(buffer, "  {}", lineage.state.pretty_print_styled(style))
+            .expect("writing to a string cannot fail");
+
+        let next = path.get(index + 1);
+        for event in events {
+            match event {
+                TraceEvent::PrunedDuplicate { parent, reason, .. } if *parent == lineage.id => {
+                    writeln!(buffer, "    ✗ {reason}").expect("writing to a string cannot fail");
+                }
+                TraceEvent::Expanded { id, children, .. } if *id == lineage.id => {
+                    let abandoned = children
+                        .iter()
+                        .filter(|child| next.map(|next| next.id) != Some(**child));
+                    for child in abandoned {
+                        let action = history.get(*child).and_then(|c| c.action.as_ref());
+                        if let Some(action) = action {
+                            writeln!(buffer, "    ✗ {action:?} {}", fate(*child, events))
+                                .expect("writing to a string cannot fail");
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((next, Some(action))) = next.map(|next| (next, &next.action)) {
+            writeln!(
+                buffer,
+                "  {}",
+                style.highlight(action.pretty_print_styled(&next.state, style))
+            )
+            .expect("writing to a string cannot fail");
+        }
+    }
+    Some(buffer)
+}
+
+/// Describes what became of a state discovered off the path to the goal.
+fn fate(id: usize, events: &[TraceEvent]) -> String {
+    let event = events.iter().find(|event| match event {
+        TraceEvent::Expanded { id: other, .. } | TraceEvent::DeadEnd { id: other, .. } => {
+            *other == id
+        }
+        _ => false,
+    });
+    match event {
+        Some(TraceEvent::DeadEnd { reason, .. }) => format!("leads to a dead end: {reason}"),
+        Some(_) => String::from("was explored, but the goal was reached another way"),
+        None => String::from("was not explored, since the goal was found first"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::search;
+
+    #[test]
+    fn trace_ends_in_goal_at_plan_depth() {
+        let (plan, events) = search_with_trace(WorldState::default());
+        let plan = plan.expect("problem is solvable");
+        let expected = search(WorldState::default()).expect("problem is solvable");
+        assert_eq!(plan.len(), expected.len());
+
+        assert!(matches!(
+            events.last(),
+            Some(TraceEvent::GoalFound { depth, .. }) if *depth == plan.len()
+        ));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, TraceEvent::PrunedDuplicate { .. })));
+
+        let json = to_json(&events);
+        assert!(json.starts_with("[\n  {\"event\":\"expanded\",\"id\":0,\"depth\":0,"));
+        assert_eq!(json.lines().count(), events.len() + 2);
+    }
+
+    #[test]
+    fn explanation_follows_the_path_to_the_goal() {
+        let mut history = History::new();
+        let (outcome, events) =
+            solve_with_trace(WorldState::default(), WorldState::is_goal, &mut history);
+        let plan = outcome.into_plan().expect("problem is solvable");
+
+        let explanation = format_explanation(&history, &events, &RenderStyle::default())
+            .expect("trace ends in the goal");
+        let lines = explanation.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "  HHH ZZZ |B~~~|");
+        assert_eq!(
+            lines[1],
+            "    ✗ { 1×H, 1×Z } leads to a dead end: every action leads to a state discovered before"
+        );
+        assert_eq!(lines.last(), Some(&"          |~~~B| HHH ZZZ"));
+
+        // Every state and action on the path is listed, along with the abandoned branches.
+        let abandoned = lines.iter().filter(|line| line.contains('✗')).count();
+        assert_eq!(lines.len(), 2 * plan.len() + 1 + abandoned);
+    }
+
+    #[test]
+    fn events_are_streamed_to_another_thread() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let searcher = std::thread::spawn(move || search_with_events(WorldState::default(), tx));
+
+        let events = rx.iter().collect::<Vec<_>>();
+        let plan = searcher.join().expect("search does not panic");
+
+        let (expected_plan, expected_events) = search_with_trace(WorldState::default());
+        assert_eq!(
+            plan.map(|plan| plan.len()),
+            expected_plan.map(|plan| plan.len())
+        );
+        assert_eq!(events, expected_events);
+    }
+}
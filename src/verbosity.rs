@@ -0,0 +1,114 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+/// Receives every line of search progress, already formatted.
+pub type Listener = Box<dyn Fn(fmt::Arguments<'_>) + Send + Sync>;
+
+/// The listener receiving the search progress of all threads, if any.
+static LISTENER: RwLock<Option<Listener>> = RwLock::new(None);
+
+/// Whether a listener is installed, allowing to skip formatting the progress otherwise.
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Whether search progress is printed on the current thread.
+    static VERBOSE: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Enables or disables printing the search progress on the current thread.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.with(|v| v.set(verbose));
+}
+
+/// Determines whether search progress is printed on the current thread, i.e. whether
+/// it was not disabled and a [`Listener`] is installed to receive it.
+pub fn is_verbose() -> bool {
+    LISTENING.load(Ordering::Relaxed) && VERBOSE.with(Cell::get)
+}
+
+/// Installs the listener receiving the search progress of all threads, replacing the previous one.
+///
+/// The search algorithms never print anything themselves; without a listener, which is the
+/// default, the progress is discarded. Pass [`None`] to remove the listener again.
+pub fn set_listener(listener: Option<Listener>) {
+    let mut current = LISTENER.write().unwrap_or_else(|e| e.into_inner());
+    LISTENING.store(listener.is_some(), Ordering::Relaxed);
+    *current = listener;
+}
+
+/// Passes a line of search progress to the listener, if any.
+pub fn emit(line: fmt::Arguments<'_>) {
+    let listener = LISTENER.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(listener) = listener.as_ref() {
+        listener(line);
+    }
+}
+
+/// Runs the specified function without printing any search progress,
+/// restoring the previous verbosity afterwards, even if the function panics.
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    // Not `is_verbose`, which is false while no listener is installed.
+    let _restore = RestoreVerbosity(VERBOSE.with(Cell::get));
+    set_verbose(false);
+    f()
+}
+
+/// Restores the verbosity of the current thread when dropped.
+struct RestoreVerbosity(bool);
+
+impl Drop for RestoreVerbosity {
+    fn drop(&mut self) {
+        set_verbose(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::humans_and_zombies::WorldState;
+    use crate::search::search;
+    use std::sync::{Arc, Mutex};
+
+    /// Serializes the tests installing a listener, which is shared by all threads.
+    static LISTENER_TESTS: Mutex<()> = Mutex::new(());
+
+    /// Collects the lines of search progress passed to a listener while searching.
+    fn received_lines() -> Vec<String> {
+        let _guard = LISTENER_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&lines);
+        set_listener(Some(Box::new(move |line| {
+            received.lock().unwrap().push(line.to_string())
+        })));
+
+        let plan = search(WorldState::default());
+        set_listener(None);
+        assert!(plan.is_some());
+
+        let lines = lines.lock().unwrap().clone();
+        lines
+    }
+
+    #[test]
+    fn progress_is_passed_to_the_listener() {
+        // Tests running in parallel may pass on their progress as well.
+        let lines = received_lines();
+        assert!(lines.iter().any(|line| line.contains("Goal reached")));
+    }
+
+    #[test]
+    fn running_quietly_before_listening_keeps_the_progress() {
+        quietly(|| ());
+        let lines = received_lines();
+        assert!(lines.iter().any(|line| line.contains("Goal reached")));
+    }
+
+    #[test]
+    fn panicking_quietly_restores_the_verbosity() {
+        let result = std::panic::catch_unwind(|| quietly(|| panic!("search failed")));
+        assert!(result.is_err());
+        assert!(VERBOSE.with(Cell::get));
+    }
+}
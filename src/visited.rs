@@ -0,0 +1,347 @@
+use crate::history::Lineage;
+use crate::search::State;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// A map keyed by [`State::unique_hash`], as used to keep track of the states observed during a
+/// search.
+///
+/// This uses the standard library's DoS-resistant SipHash, unless the `fxhash` feature is enabled
+/// to use the much faster, but predictable, Fx hash instead.
+#[cfg(not(feature = "fxhash"))]
+pub(crate) type StateMap<K, V> = std::collections::HashMap<K, V>;
+
+/// A map keyed by [`State::unique_hash`], as used to keep track of the states observed during a
+/// search.
+///
+/// This uses the standard library's DoS-resistant SipHash, unless the `fxhash` feature is enabled
+/// to use the much faster, but predictable, Fx hash instead.
+#[cfg(feature = "fxhash")]
+pub(crate) type StateMap<K, V> = rustc_hash::FxHashMap<K, V>;
+
+/// An entry of a [`Visited`] set, giving access to the state it was observed for.
+pub trait VisitedEntry<S> {
+    /// Gets the observed state.
+    fn state(&self) -> &S;
+}
+
+impl<S: State> VisitedEntry<S> for S {
+    fn state(&self) -> &S {
+        self
+    }
+}
+
+/// A lineage already kept in the [`History`](crate::history::History) shares its state with the
+/// set rather than having it copied.
+impl<S, A> VisitedEntry<S> for Rc<Lineage<S, A>> {
+    fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+/// The set of states observed during a search.
+///
+/// The set keeps one entry per state, by default a copy of the state itself. A search recording
+/// its states in a [`History`](crate::history::History) anyway keeps the lineages handed out by
+/// it instead, see [`Visited::insert_entry`].
+///
+/// States are bucketed by a digest of their [`State::unique_hash`] and then compared for
+/// equality, so a collision of two distinct states' hashes never causes one of them to be
+/// dropped. The hash merely serves as a fast pre-filter.
+///
+/// A [bounded](Visited::bounded) set acts as a transposition table of limited size, evicting
+/// the least recently observed state whenever it is full.
+pub struct Visited<S: State, E = S> {
+    /// The entries by the digest of their state's hash, each along with the time it was last
+    /// observed. Buckets are removed once empty, so there are never more buckets than states.
+    buckets: StateMap<u64, Vec<(E, u64)>>,
+    len: usize,
+    /// The maximum number of states kept, if bounded.
+    cap: Option<usize>,
+    /// The digests of the states of a bounded set by the time the states were last observed,
+    /// the oldest first.
+    recency: BTreeMap<u64, u64>,
+    /// The number of times a state was observed.
+    clock: u64,
+    states: PhantomData<S>,
+}
+
+impl<S, E> Visited<S, E>
+where
+    S: State + Eq,
+    S::Hash: Eq + Hash,
+    E: VisitedEntry<S>,
+{
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        Self {
+            buckets: StateMap::default(),
+            len: 0,
+            cap: None,
+            recency: BTreeMap::new(),
+            clock: 0,
+            states: PhantomData,
+        }
+    }
+
+    /// Creates a new, empty set keeping at most `cap` states, evicting the least recently
+    /// inserted or re-inserted state to make room for a new one.
+    ///
+    /// An evicted state is no longer recognized as observed, so a search using the set may
+    /// explore it again, and may go around in circles forever unless it is limited otherwise.
+    pub fn bounded(cap: usize) -> Self {
+        assert!(cap > 0, "capacity must be positive");
+        Self {
+            cap: Some(cap),
+            ..Self::new()
+        }
+    }
+
+    /// Inserts the entry of a state into the set, unless the state was observed before.
+    /// Returns `true` if the state was not observed before, or was evicted since.
+    pub fn insert_entry(&mut self, entry: E) -> bool {
+        match self.observe(entry.state()) {
+            Ok(()) => false,
+            Err(digest) => {
+                self.push(digest, entry);
+                true
+            }
+        }
+    }
+
+    /// Marks the state as observed just now, like inserting it again would.
+    /// Returns `true` if the state was observed before, and was not evicted since.
+    pub fn revisit(&mut self, state: &S) -> bool {
+        self.observe(state).is_ok()
+    }
+
+    /// Marks the state as observed just now if it is in the set, or returns the digest of its
+    /// bucket otherwise.
+    fn observe(&mut self, state: &S) -> Result<(), u64> {
+        self.clock += 1;
+        let digest = self.digest(state);
+        let bucket = self.buckets.get_mut(&digest).ok_or(digest)?;
+        let (_, observed) = bucket
+            .iter_mut()
+            .find(|(e, _)| e.state() == state)
+            .ok_or(digest)?;
+        if self.recency.remove(observed).is_some() {
+            self.recency.insert(self.clock, digest);
+        }
+        *observed = self.clock;
+        Ok(())
+    }
+
+    /// Adds the entry of a state not in the set, evicting the least recently observed one if
+    /// the set is full.
+    fn push(&mut self, digest: u64, entry: E) {
+        self.buckets
+            .entry(digest)
+            .or_default()
+            .push((entry, self.clock));
+        self.len += 1;
+
+        if let Some(cap) = self.cap {
+            self.recency.insert(self.clock, digest);
+            if self.len > cap {
+                let (observed, digest) = self.recency.pop_first().expect("set is not empty");
+                self.remove_from_bucket(digest, |(_, o)| *o == observed);
+            }
+        }
+    }
+
+    /// Removes a state from the set.
+    /// Returns `true` if the state was observed before.
+    pub fn remove(&mut self, state: &S) -> bool {
+        let digest = self.digest(state);
+        match self.remove_from_bucket(digest, |(e, _)| e.state() == state) {
+            Some(observed) => {
+                self.recency.remove(&observed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Determines whether the state was observed before, and was not evicted since.
+    pub fn contains(&self, state: &S) -> bool {
+        self.buckets
+            .get(&self.digest(state))
+            .is_some_and(|bucket| bucket.iter().any(|(e, _)| e.state() == state))
+    }
+
+    /// Gets the digest of the state's hash, identifying its bucket.
+    fn digest(&self, state: &S) -> u64 {
+        self.buckets.hasher().hash_one(state.unique_hash())
+    }
+
+    /// Removes the first entry matching the predicate from the bucket, and the bucket itself
+    /// once it is empty. Returns the time the entry was last observed, if any.
+    fn remove_from_bucket(
+        &mut self,
+        digest: u64,
+        matches: impl Fn(&(E, u64)) -> bool,
+    ) -> Option<u64> {
+        let bucket = self.buckets.get_mut(&digest)?;
+        let index = bucket.iter().position(matches)?;
+        let (_, observed) = bucket.swap_remove(index);
+        if bucket.is_empty() {
+            self.buckets.remove(&digest);
+        }
+        self.len -= 1;
+        Some(observed)
+    }
+
+    /// Gets the number of observed states.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determines whether no state was observed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<S> Visited<S>
+where
+    S: State + Clone + Eq,
+    S::Hash: Eq + Hash,
+{
+    /// Inserts a copy of the state into the set.
+    /// Returns `true` if the state was not observed before, or was evicted since.
+    pub fn insert(&mut self, state: &S) -> bool {
+        match self.observe(state) {
+            Ok(()) => false,
+            Err(digest) => {
+                self.push(digest, state.clone());
+                true
+            }
+        }
+    }
+}
+
+impl<S, E> Default for Visited<S, E>
+where
+    S: State + Eq,
+    S::Hash: Eq + Hash,
+    E: VisitedEntry<S>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::History;
+    use crate::search::Actions;
+
+    /// A state whose hash always collides.
+    #[derive(Clone, PartialEq, Eq)]
+    struct Colliding(u8);
+
+    impl State for Colliding {
+        type Action = ();
+        type Hash = ();
+
+        fn is_goal(&self) -> bool {
+            false
+        }
+
+        fn get_actions(&self) -> Actions<()> {
+            Actions::new()
+        }
+
+        fn unique_hash(&self) {}
+    }
+
+    /// A state whose hash is unique.
+    #[derive(Clone, PartialEq, Eq)]
+    struct Distinct(u8);
+
+    impl State for Distinct {
+        type Action = ();
+        type Hash = u8;
+
+        fn is_goal(&self) -> bool {
+            false
+        }
+
+        fn get_actions(&self) -> Actions<()> {
+            Actions::new()
+        }
+
+        fn unique_hash(&self) -> u8 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn colliding_hashes_are_distinguished() {
+        let mut visited = Visited::new();
+        assert!(visited.insert(&Colliding(1)));
+        assert!(visited.insert(&Colliding(2)));
+        assert!(!visited.insert(&Colliding(1)));
+
+        assert!(visited.contains(&Colliding(2)));
+        assert!(!visited.contains(&Colliding(3)));
+        assert_eq!(visited.len(), 2);
+
+        assert!(visited.remove(&Colliding(1)));
+        assert!(!visited.remove(&Colliding(1)));
+        assert!(visited.contains(&Colliding(2)));
+        assert_eq!(visited.len(), 1);
+    }
+
+    #[test]
+    fn least_recently_observed_states_are_evicted() {
+        let mut visited = Visited::bounded(2);
+        assert!(visited.insert(&Colliding(1)));
+        assert!(visited.insert(&Colliding(2)));
+        assert!(!visited.insert(&Colliding(1)));
+
+        // The second state was observed longest ago.
+        assert!(visited.insert(&Colliding(3)));
+        assert_eq!(visited.len(), 2);
+        assert!(visited.contains(&Colliding(1)));
+        assert!(!visited.contains(&Colliding(2)));
+
+        assert!(visited.insert(&Colliding(2)));
+        assert!(!visited.contains(&Colliding(1)));
+    }
+
+    #[test]
+    fn lineages_are_shared_with_the_history() {
+        let mut history = History::<Distinct, ()>::new();
+        let root = history.create_root(Distinct(1));
+        let mut visited = Visited::new();
+        assert!(visited.insert_entry(Rc::clone(&root)));
+        assert!(!visited.insert_entry(Rc::clone(&root)));
+        assert!(visited.revisit(&Distinct(1)));
+        assert!(!visited.revisit(&Distinct(2)));
+
+        // The history, the set and this test hold the only handles to the lineage.
+        assert_eq!(Rc::strong_count(&root), 3);
+        assert_eq!(visited.len(), 1);
+    }
+
+    #[test]
+    fn bounded_sets_keep_no_more_buckets_than_states() {
+        let mut visited = Visited::bounded(3);
+        for value in 0..100 {
+            assert!(visited.insert(&Distinct(value)));
+            assert!(visited.len() <= 3);
+            assert!(visited.buckets.len() <= 3);
+            assert!(visited.recency.len() <= 3);
+        }
+
+        assert!(visited.remove(&Distinct(99)));
+        assert_eq!(visited.buckets.len(), 2);
+        assert!(visited.contains(&Distinct(98)));
+        assert!(!visited.contains(&Distinct(96)));
+    }
+}
@@ -0,0 +1,191 @@
+//! The entry point for running the solver in the browser, built with the `wasm` feature.
+
+use crate::json::{escape_json, format_plan_json};
+use crate::markdown::{MarkdownAction, MarkdownState};
+use crate::pretty_print::{PrettyPrintAction, PrettyPrintState};
+use crate::problems::Problem;
+use crate::registry::{self, describe_error, ProblemVisitor};
+use crate::search::{search_with_goal, Action, State};
+use crate::verbosity::set_verbose;
+use clap::Command;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::hash::Hash;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Solves a problem and returns the solution as JSON, as produced by [`format_plan_json`].
+///
+/// The problem is named like the subcommands of the command-line application, e.g.
+/// `humans-and-zombies`, and configured by a JSON object named like their options,
+/// e.g. `{"humans": 5, "zombies": 5, "boat": 3}`; omitted options take their default values.
+/// Lists such as the walking times of the Bridge and Torch `people` are JSON arrays, and the
+/// `spec` of a `generic` crossing puzzle is a JSON object.
+///
+/// If the parameters are invalid or there is no solution, an object with an `error`
+/// message is returned instead. Nothing is ever printed.
+#[wasm_bindgen]
+pub fn solve_json(problem: &str, params_json: &str) -> String {
+    // There is no standard output to print the search progress to.
+    set_verbose(false);
+
+    match solve(problem, params_json) {
+        Ok(solution) => solution,
+        Err(e) => format!(r#"{{"error":"{}"}}"#, escape_json(&e)),
+    }
+}
+
+/// Builds the named problem from its parameters and solves it.
+fn solve(problem: &str, params_json: &str) -> Result<String, String> {
+    let info = registry::find(problem)?;
+    let command = info.command().no_binary_name(true);
+    let args = arguments(&command, params_json)?;
+    let matches = command
+        .try_get_matches_from(args)
+        .map_err(|e| describe_error(&e))?;
+    info.build(&matches, 0)?.problem.visit(Solve)
+}
+
+/// Solves the problem, formatting its plan as JSON.
+struct Solve;
+
+impl ProblemVisitor for Solve {
+    type Output = Result<String, String>;
+
+    fn visit<P, S, A>(self, problem: P) -> Result<String, String>
+    where
+        P: Problem<State = S> + 'static,
+        S: State<Action = A>
+            + Clone
+            + Eq
+            + Send
+            + Sync
+            + Debug
+            + PrettyPrintState
+            + MarkdownState
+            + Serialize
+            + DeserializeOwned,
+        A: Action<State = S>
+            + Clone
+            + Send
+            + Debug
+            + PrettyPrintAction<S>
+            + MarkdownAction<S>
+            + Serialize
+            + DeserializeOwned,
+        S::Hash: Eq + Hash,
+    {
+        search_with_goal(problem.initial(), |s| problem.is_goal(s))
+            .map(|plan| format_plan_json(&plan))
+            .ok_or_else(|| String::from("No solution found."))
+    }
+}
+
+/// Turns the parameters, a JSON object named like the arguments of the subcommand, into
+/// those arguments; an empty string means no parameters.
+///
+/// Lists are passed as repeated arguments, or joined if the argument takes a delimited list.
+/// `true` sets a flag, and an object is passed as JSON, e.g. the `spec` of a `generic` problem.
+fn arguments(command: &Command, json: &str) -> Result<Vec<String>, String> {
+    if json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let params = match serde_json::from_str(json) {
+        Ok(Value::Object(params)) => params,
+        Ok(_) => return Err(String::from("The parameters must be a JSON object.")),
+        Err(e) => return Err(format!("Invalid parameters: {e}")),
+    };
+
+    let mut args = Vec::new();
+    for (name, value) in params {
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == name.as_str())
+            .ok_or_else(|| format!("Unknown parameter: {name}"))?;
+        let flag = format!("--{}", arg.get_long().unwrap_or(name.as_str()));
+
+        match value {
+            Value::Bool(set) if !arg.get_action().takes_values() => {
+                if set {
+                    args.push(flag);
+                }
+            }
+            Value::Array(values) => {
+                let values = values.iter().map(text).collect::<Result<Vec<_>, _>>()?;
+                match arg.get_value_delimiter() {
+                    Some(delimiter) => {
+                        args.push(format!("{flag}={}", values.join(&delimiter.to_string())));
+                    }
+                    None => {
+                        args.extend(values.into_iter().map(|value| format!("{flag}={value}")));
+                    }
+                }
+            }
+            value => args.push(format!("{flag}={}", text(&value)?)),
+        }
+    }
+    Ok(args)
+}
+
+/// Renders a parameter value as the text of an argument.
+fn text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Object(_) => Ok(value.to_string()),
+        _ => Err(format!("Unsupported parameter value: {value}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_named_problem_with_parameters() {
+        let solution = solve_json(
+            "humans-and-zombies",
+            r#"{"humans": 5, "zombies": 5, "boat": 3}"#,
+        );
+        assert!(solution.starts_with(r#"{"steps":11,"#));
+
+        let solution = solve_json("bridge-and-torch", "");
+        assert!(solution.starts_with(r#"{"steps":5,"#));
+
+        let solution = solve_json("water-jugs", r#"{"capacities": [3, 5], "target": 4}"#);
+        assert!(solution.starts_with(r#"{"steps":6,"#));
+
+        let solution = solve_json(
+            "generic",
+            r#"{"spec": {
+                "entities": [["farmer", 1], ["wolf", 1], ["goat", 1], ["cabbage", 1]],
+                "boat": 2,
+                "forbidden": [["wolf", "goat"], ["goat", "cabbage"]],
+                "guardian": "farmer"
+            }}"#,
+        );
+        assert!(solution.starts_with(r#"{"steps":7,"#));
+    }
+
+    #[test]
+    fn errors_are_returned_as_json() {
+        assert_eq!(
+            solve_json("sudoku", "{}"),
+            r#"{"error":"Unknown problem: sudoku"}"#
+        );
+        assert_eq!(
+            solve_json("hanoi", r#"{"disks": 0}"#),
+            r#"{"error":"invalid value '0' for '--disks <COUNT>': value must be positive"}"#
+        );
+        assert_eq!(
+            solve_json("hanoi", r#"{"pegs": 4}"#),
+            r#"{"error":"Unknown parameter: pegs"}"#
+        );
+        assert_eq!(
+            solve_json("mc", r#"{"boat": 1}"#),
+            r#"{"error":"No solution found."}"#
+        );
+    }
+}